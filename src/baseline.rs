@@ -0,0 +1,326 @@
+//! A baseline of tests that are known to fail
+//!
+//! Sometimes a check stays broken for weeks while a fix is pending, for example in the staging
+//! environment. Instead of ignoring the smoke tests until the fix lands, the failing tests can be
+//! listed in a baseline file. Each entry names the path of the test, the reason why it fails, and
+//! the date when the entry expires:
+//!
+//! ```toml
+//! [[expected_failure]]
+//! path = "crates.io > Database dumps > Fastly"
+//! reason = "Fastly is not configured for staging yet"
+//! expires = 2024-06-30
+//! ```
+//!
+//! Failures of tests in the baseline are expected and don't fail the run, while passes are
+//! unexpected and are flagged so that the entry can be removed. Entries that have expired no longer
+//! apply and are reported as errors, so that the baseline does not silently hide regressions.
+
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use getset::{CopyGetters, Getters};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+
+use crate::test::{TestPath, TestSuiteResult};
+
+/// A baseline of tests that are known to fail
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Baseline {
+    /// The tests that are expected to fail
+    #[serde(default, rename = "expected_failure")]
+    #[getset(get = "pub")]
+    expected_failures: Vec<ExpectedFailure>,
+}
+
+/// A test that is expected to fail
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, Getters, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExpectedFailure {
+    /// The path of the test
+    #[getset(get = "pub")]
+    path: TestPath,
+
+    /// The reason why the test is expected to fail
+    #[getset(get = "pub")]
+    reason: String,
+
+    /// The date after which the entry no longer applies
+    #[serde(deserialize_with = "deserialize_date")]
+    #[getset(get_copy = "pub")]
+    expires: NaiveDate,
+}
+
+/// Errors that can occur when loading a baseline
+#[derive(Debug)]
+pub enum BaselineError {
+    /// The baseline file could not be read
+    Read(std::io::Error),
+
+    /// The baseline file is not a valid baseline
+    Parse(toml::de::Error),
+}
+
+impl Baseline {
+    /// Load the baseline from the file at the given path
+    pub fn load(path: &Path) -> Result<Self, BaselineError> {
+        std::fs::read_to_string(path)
+            .map_err(BaselineError::Read)?
+            .parse()
+    }
+
+    /// Return the entries that have expired on the given day
+    ///
+    /// An entry expires at the end of its expiry date.
+    pub fn expired(&self, today: NaiveDate) -> Vec<&ExpectedFailure> {
+        self.expected_failures
+            .iter()
+            .filter(|entry| entry.is_expired(today))
+            .collect()
+    }
+
+    /// Mark the results of the tests in the baseline as expected failures
+    ///
+    /// Expired entries are ignored, so that the tests that they list fail the run again.
+    pub fn apply(&self, results: &mut [TestSuiteResult], today: NaiveDate) {
+        for suite in results {
+            let suite_name = suite.name();
+
+            for group in suite.results_mut() {
+                let group_name = group.name();
+
+                for result in group.results_mut() {
+                    let path = TestPath::new(suite_name, group_name, result.name());
+
+                    let entry = self
+                        .expected_failures
+                        .iter()
+                        .find(|entry| entry.path == path && !entry.is_expired(today));
+
+                    if let Some(entry) = entry {
+                        result.set_expected_failure(Some(entry.reason.clone()));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for Baseline {
+    type Err = BaselineError;
+
+    fn from_str(baseline: &str) -> Result<Self, Self::Err> {
+        toml::from_str(baseline).map_err(BaselineError::Parse)
+    }
+}
+
+impl ExpectedFailure {
+    /// Check if the entry has expired on the given day
+    pub fn is_expired(&self, today: NaiveDate) -> bool {
+        today > self.expires
+    }
+}
+
+impl Display for ExpectedFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (expired on {}: {})",
+            self.path, self.expires, self.reason
+        )
+    }
+}
+
+impl Display for BaselineError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BaselineError::Read(error) => write!(f, "failed to read the baseline: {error}"),
+            BaselineError::Parse(error) => write!(f, "failed to parse the baseline: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for BaselineError {}
+
+/// Deserialize a TOML date, e.g. `2024-06-30`
+fn deserialize_date<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let datetime = toml::value::Datetime::deserialize(deserializer)?;
+
+    let date = match (datetime.date, datetime.time) {
+        (Some(date), None) => date,
+        _ => return Err(D::Error::custom(format!("expected a date, got {datetime}"))),
+    };
+
+    NaiveDate::from_ymd_opt(date.year.into(), date.month.into(), date.day.into())
+        .ok_or_else(|| D::Error::custom(format!("invalid date {datetime}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::test::{Outcome, TestGroupResult, TestResult};
+    use crate::test_utils::*;
+
+    use super::*;
+
+    const BASELINE: &str = indoc! {r#"
+        [[expected_failure]]
+        path = "suite > group > failing"
+        reason = "fix is pending"
+        expires = 2024-06-30
+
+        [[expected_failure]]
+        path = "suite > group > passing"
+        reason = "fixed already"
+        expires = 2024-06-30
+
+        [[expected_failure]]
+        path = "suite > group > expired"
+        reason = "should be fixed by now"
+        expires = 2024-01-31
+    "#};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn results() -> Vec<TestSuiteResult> {
+        let test = |name, success| TestResult::builder().name(name).success(success).build();
+
+        vec![TestSuiteResult::builder()
+            .name("suite")
+            .results(vec![TestGroupResult::builder()
+                .name("group")
+                .results(vec![
+                    test("failing", false),
+                    test("passing", true),
+                    test("expired", false),
+                    test("unlisted", true),
+                ])
+                .build()])
+            .build()]
+    }
+
+    #[test]
+    fn from_str() {
+        let baseline: Baseline = BASELINE.parse().unwrap();
+        let entry = &baseline.expected_failures()[0];
+
+        assert_eq!(3, baseline.expected_failures().len());
+        assert_eq!(&TestPath::new("suite", "group", "failing"), entry.path());
+        assert_eq!("fix is pending", entry.reason());
+        assert_eq!(date(2024, 6, 30), entry.expires());
+    }
+
+    #[test]
+    fn from_str_with_empty_file() {
+        let baseline: Baseline = "".parse().unwrap();
+
+        assert!(baseline.expected_failures().is_empty());
+    }
+
+    #[test]
+    fn from_str_with_invalid_path() {
+        let baseline = indoc! {r#"
+            [[expected_failure]]
+            path = "suite > group"
+            reason = "reason"
+            expires = 2024-06-30
+        "#};
+
+        assert!(baseline.parse::<Baseline>().is_err());
+    }
+
+    #[test]
+    fn from_str_with_datetime() {
+        let baseline = indoc! {r#"
+            [[expected_failure]]
+            path = "suite > group > test"
+            reason = "reason"
+            expires = 2024-06-30T12:00:00Z
+        "#};
+
+        assert!(baseline.parse::<Baseline>().is_err());
+    }
+
+    #[test]
+    fn expired() {
+        let baseline: Baseline = BASELINE.parse().unwrap();
+
+        let expired = baseline.expired(date(2024, 2, 1));
+
+        assert_eq!(1, expired.len());
+        assert_eq!(
+            &TestPath::new("suite", "group", "expired"),
+            expired[0].path()
+        );
+    }
+
+    #[test]
+    fn expired_on_expiry_date() {
+        let baseline: Baseline = BASELINE.parse().unwrap();
+
+        assert!(baseline.expired(date(2024, 1, 31)).is_empty());
+    }
+
+    #[test]
+    fn apply() {
+        let baseline: Baseline = BASELINE.parse().unwrap();
+        let mut results = results();
+
+        baseline.apply(&mut results, date(2024, 2, 1));
+
+        let outcomes: Vec<(String, Outcome)> = results[0]
+            .test_results()
+            .map(|(path, result)| (path.test().clone(), result.outcome()))
+            .collect();
+
+        assert_eq!(
+            vec![
+                ("failing".into(), Outcome::ExpectedFailure),
+                ("passing".into(), Outcome::UnexpectedPass),
+                ("expired".into(), Outcome::Failed),
+                ("unlisted".into(), Outcome::Passed),
+            ],
+            outcomes
+        );
+        assert!(!results[0].success());
+    }
+
+    #[test]
+    fn trait_display() {
+        let baseline: Baseline = BASELINE.parse().unwrap();
+
+        assert_eq!(
+            "suite > group > expired (expired on 2024-01-31: should be fixed by now)",
+            baseline.expected_failures()[2].to_string()
+        );
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Baseline>();
+        assert_send::<BaselineError>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Baseline>();
+        assert_sync::<BaselineError>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Baseline>();
+        assert_unpin::<BaselineError>();
+    }
+}
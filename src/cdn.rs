@@ -0,0 +1,243 @@
+//! Content Delivery Networks that serve the Rust project's sites
+//!
+//! Most checks request the same path from every CDN and expect the same behavior. Instead of
+//! writing a test for each CDN, the CDNs are described in the configuration, and the test groups
+//! instantiate each check once for every CDN that serves their service:
+//!
+//! ```toml
+//! [cdns.fastly]
+//! name = "Fastly"
+//! crates_url = "https://fastly-static.crates.io"
+//! releases_url = "https://fastly-static.rust-lang.org"
+//! ```
+//!
+//! Adding another CDN to all test groups is then a change to the configuration. The origins behind
+//! the CDNs, e.g. the S3 buckets, are described in the same way in `[origins]`, so that the checks
+//! can also be run against them directly.
+
+use std::collections::BTreeMap;
+
+use getset::Getters;
+use serde::Deserialize;
+#[cfg(test)]
+use typed_builder::TypedBuilder;
+
+use crate::config::{Validate, Validator};
+use crate::test::Tag;
+
+/// A service that is served by the CDNs
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Service {
+    /// The crates and database dumps on `static.crates.io`
+    Crates,
+
+    /// The Rust releases on `static.rust-lang.org`
+    Releases,
+}
+
+/// A Content Delivery Network that serves the Rust project's sites
+///
+/// The CDN is described by its name and a base URL for each service that it serves. Services without
+/// a URL are not checked on the CDN.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(test, derive(TypedBuilder))]
+pub struct Cdn {
+    /// The name of the CDN, e.g. `Fastly`, which is used in the names of the tests
+    #[cfg_attr(test, builder(setter(into)))]
+    #[getset(get = "pub")]
+    name: String,
+
+    /// The base URL for crates.io, e.g. `https://fastly-static.crates.io`
+    #[serde(default, deserialize_with = "crate::config::deserialize_optional_url")]
+    #[cfg_attr(test, builder(default, setter(strip_option, into)))]
+    #[getset(get = "pub")]
+    crates_url: Option<String>,
+
+    /// The base URL for Rust releases, e.g. `https://fastly-static.rust-lang.org`
+    #[serde(default, deserialize_with = "crate::config::deserialize_optional_url")]
+    #[cfg_attr(test, builder(default, setter(strip_option, into)))]
+    #[getset(get = "pub")]
+    releases_url: Option<String>,
+}
+
+/// A CDN that serves a specific service
+///
+/// Test groups receive an endpoint for every CDN that serves their service, and instantiate each of
+/// their checks once per endpoint.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Getters)]
+pub struct Endpoint {
+    /// The name of the CDN, e.g. `Fastly`
+    #[getset(get = "pub")]
+    name: String,
+
+    /// The tag that selects the tests for the CDN, e.g. `cdn:fastly`
+    #[getset(get = "pub")]
+    tag: Tag,
+
+    /// The base URL of the service on the CDN
+    #[getset(get = "pub")]
+    url: String,
+}
+
+impl Service {
+    /// All services that are served by the CDNs
+    pub const ALL: [Service; 2] = [Service::Crates, Service::Releases];
+
+    /// Return the name of the service, e.g. `crates`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Service::Crates => "crates",
+            Service::Releases => "releases",
+        }
+    }
+}
+
+impl Cdn {
+    /// Return the base URL of the given service, or `None` if the CDN doesn't serve it
+    pub fn url(&self, service: Service) -> Option<&str> {
+        match service {
+            Service::Crates => self.crates_url.as_deref(),
+            Service::Releases => self.releases_url.as_deref(),
+        }
+    }
+}
+
+impl Endpoint {
+    /// Create a new endpoint
+    pub fn new(name: impl Into<String>, tag: Tag, url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            tag,
+            url: url.into(),
+        }
+    }
+
+    /// Check if the endpoint is an origin behind the CDNs, i.e. if it is tagged with `origin:`
+    ///
+    /// Many checks guard against rewrites and redirects that only the CDNs implement. Test groups
+    /// use this to instantiate only the checks that also apply to an origin.
+    pub fn is_origin(&self) -> bool {
+        self.tag.as_str().starts_with("origin:")
+    }
+}
+
+/// Return the endpoints of the CDNs that serve the given service
+///
+/// The key of a CDN in the configuration is passed to `tag` to create its tag, e.g. `cdn:fastly` for
+/// `[cdns.fastly]`. The endpoints are sorted by the key of their CDN.
+pub fn endpoints(
+    cdns: &BTreeMap<String, Cdn>,
+    service: Service,
+    tag: fn(&str) -> Tag,
+) -> Vec<Endpoint> {
+    cdns.iter()
+        .filter_map(|(key, cdn)| {
+            cdn.url(service)
+                .map(|url| Endpoint::new(cdn.name(), tag(key), url))
+        })
+        .collect()
+}
+
+impl Validate for Cdn {
+    fn validate(&self, validator: &mut Validator) {
+        if self.name.is_empty() {
+            validator.problem("name", "expected a name for the CDN");
+        }
+
+        if let Some(url) = &self.crates_url {
+            validator.url("crates_url", url);
+        }
+
+        if let Some(url) = &self.releases_url {
+            validator.url("releases_url", url);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::*;
+
+    use super::*;
+
+    #[test]
+    fn endpoints_for_service() {
+        let cdns = BTreeMap::from([
+            (
+                "fastly".to_string(),
+                Cdn::builder()
+                    .name("Fastly")
+                    .crates_url("https://fastly-static.crates.io")
+                    .releases_url("https://fastly-static.rust-lang.org")
+                    .build(),
+            ),
+            (
+                "origin".to_string(),
+                Cdn::builder()
+                    .name("Origin")
+                    .releases_url("https://origin.rust-lang.org")
+                    .build(),
+            ),
+        ]);
+
+        assert_eq!(
+            vec![Endpoint::new(
+                "Fastly",
+                Tag::cdn("fastly"),
+                "https://fastly-static.crates.io"
+            )],
+            endpoints(&cdns, Service::Crates, Tag::cdn)
+        );
+        assert_eq!(2, endpoints(&cdns, Service::Releases, Tag::cdn).len());
+    }
+
+    #[test]
+    fn endpoints_with_origin_tag() {
+        let origins = BTreeMap::from([(
+            "s3".to_string(),
+            Cdn::builder()
+                .name("S3")
+                .crates_url("https://crates-io.s3.us-west-1.amazonaws.com")
+                .build(),
+        )]);
+
+        let endpoints = endpoints(&origins, Service::Crates, Tag::origin);
+
+        assert_eq!(&Tag::origin("s3"), endpoints[0].tag());
+        assert!(endpoints[0].is_origin());
+        assert!(!Endpoint::new("Fastly", Tag::cdn("fastly"), "https://fastly").is_origin());
+    }
+
+    #[test]
+    fn deserialize_without_url() {
+        let cdn: Cdn = toml::from_str("name = 'Origin'").unwrap();
+
+        assert_eq!(None, cdn.url(Service::Crates));
+    }
+
+    #[test]
+    fn deserialize_with_invalid_url() {
+        assert!(toml::from_str::<Cdn>("name = 'Origin'\ncrates_url = 'ftp://origin'").is_err());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Cdn>();
+        assert_send::<Endpoint>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Cdn>();
+        assert_sync::<Endpoint>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Cdn>();
+        assert_unpin::<Endpoint>();
+    }
+}
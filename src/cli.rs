@@ -1,32 +1,497 @@
 //! Command-line interface to run the smoke tests
 //!
 //! This module implements the command-line interface that can be used to run the smoke tests. See
-//! the `Cli` struct that parses the command-line arguments and options.
+//! the `Cli` struct that parses the command-line arguments and options, and the `Command` enum
+//! with the subcommands that the application supports.
 
-use clap::Parser;
-use getset::CopyGetters;
+use std::net::SocketAddr;
+use std::path::PathBuf;
 
-use crate::environment::Environment;
+use clap::{Parser, Subcommand};
+use getset::{CopyGetters, Getters};
+
+use infra_smoke_test::config::Override;
+use infra_smoke_test::environment::Environment;
+use infra_smoke_test::monitor::Interval;
+use infra_smoke_test::test::{Filter, Format, Tag, TestPath};
 
 /// Smoke Tests for Infrastructure
 ///
 /// This command-line application can be used to run smoke tests against our infrastructure. The
 /// tests confirm that the infrastructure is working as expected and that no regressions have been
 /// introduced.
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, Parser)]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, Getters, Parser)]
 pub struct Cli {
-    /// The environment to run the smoke tests against
-    #[arg(long, value_enum, default_value_t)]
-    #[getset(get_copy = "pub")]
+    /// The command to execute, which defaults to running the smoke tests
+    #[command(subcommand)]
+    #[getset(get = "pub")]
+    command: Option<Command>,
+
+    /// The environment to run the smoke tests against, e.g. `staging`, `production`, or a custom
+    /// environment from the configuration file
+    #[arg(long, default_value_t, global = true)]
+    #[getset(get = "pub")]
     env: Environment,
+
+    /// Print only the failing tests with their full path
+    #[arg(short, long, global = true)]
+    #[getset(get_copy = "pub")]
+    quiet: bool,
+
+    /// Print PASS and FAIL instead of emoji
+    #[arg(long, global = true)]
+    #[getset(get_copy = "pub")]
+    plain: bool,
+
+    /// The format of the results, either `text`, `json`, or `html`
+    #[arg(long, default_value_t, global = true)]
+    #[getset(get_copy = "pub")]
+    format: Format,
+
+    /// Write the results to this file instead of printing them, e.g. `report.html`
+    #[arg(long, value_name = "FILE", global = true)]
+    #[getset(get = "pub")]
+    output: Option<PathBuf>,
+
+    /// Run only the tests with this tag, e.g. `cdn:fastly` (can be repeated)
+    #[arg(long = "tag", value_name = "TAG", global = true)]
+    #[getset(get = "pub")]
+    tags: Vec<Tag>,
+
+    /// Skip the tests with this tag (can be repeated)
+    #[arg(long = "exclude-tag", value_name = "TAG", global = true)]
+    #[getset(get = "pub")]
+    excluded_tags: Vec<Tag>,
+
+    /// Also run the checks directly against the origins behind the CDNs, e.g. the S3 buckets
+    #[arg(long, global = true)]
+    #[getset(get_copy = "pub")]
+    origins: bool,
+
+    /// A configuration file that overrides the built-in configuration for the environment
+    #[arg(long, value_name = "FILE", global = true)]
+    #[getset(get = "pub")]
+    config: Option<PathBuf>,
+
+    /// Override a single configuration value, e.g. `crates.crates_4891.krate=my-crate` (can be
+    /// repeated)
+    #[arg(long = "set", value_name = "KEY=VALUE", global = true)]
+    #[getset(get = "pub")]
+    overrides: Vec<Override>,
+
+    /// Send the success and duration of each test to the StatsD agent at this address after every
+    /// run, e.g. `localhost:8125`
+    #[arg(long, value_name = "ADDRESS", global = true)]
+    #[getset(get = "pub")]
+    statsd: Option<String>,
+
+    /// Append the results of every run to this JSON Lines file, which the `history` subcommand
+    /// reads
+    #[arg(long, value_name = "FILE", global = true)]
+    #[getset(get = "pub")]
+    history: Option<PathBuf>,
+
+    /// A baseline file with the tests that are expected to fail
+    #[arg(long, value_name = "FILE", global = true)]
+    #[getset(get = "pub")]
+    baseline: Option<PathBuf>,
+}
+
+/// Commands that can be executed by the command-line application
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Subcommand)]
+pub enum Command {
+    /// Run the smoke tests (default)
+    Run,
+
+    /// Explain what a test checks and who owns it
+    Explain {
+        /// The path of the test, e.g. "crates.io > Database dumps > Fastly"
+        path: TestPath,
+    },
+
+    /// Check the configuration of the environment for mistakes
+    ValidateConfig,
+
+    /// Run the smoke tests in two environments and report where the results differ
+    Compare {
+        /// The environment to compare, e.g. `staging`
+        #[arg(long)]
+        from: Environment,
+
+        /// The environment to compare with, e.g. `production`
+        #[arg(long)]
+        to: Environment,
+    },
+
+    /// Run the smoke tests in a loop and report the tests whose result changed
+    Monitor {
+        /// The time between two runs, e.g. `30s`, `5m`, or `1h`
+        #[arg(long, default_value = "5m")]
+        interval: Interval,
+
+        /// Serve Prometheus metrics on `/metrics` at this address, e.g. `127.0.0.1:9898`
+        #[arg(long, value_name = "ADDRESS")]
+        metrics: Option<SocketAddr>,
+    },
+
+    /// Run the smoke tests on a schedule and serve a status page with the latest results
+    Serve {
+        /// The address of the status page, e.g. `0.0.0.0:8080`
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        address: SocketAddr,
+
+        /// The time between two runs, e.g. `30s`, `5m`, or `1h`
+        #[arg(long, default_value = "5m")]
+        interval: Interval,
+    },
+
+    /// Rerun the smoke tests until all of them pass, e.g. to block a deploy pipeline
+    Wait {
+        /// How long to wait for the tests to pass before giving up, e.g. `15m`
+        #[arg(long, default_value = "15m")]
+        timeout: Interval,
+
+        /// The time between two runs, e.g. `30s`
+        #[arg(long, default_value = "30s")]
+        interval: Interval,
+    },
+
+    /// Show the pass rate, the last failure, and the flips of each test from the history file
+    History {
+        /// The time window of the statistics, e.g. `12h` or `7d`
+        #[arg(long, default_value = "7d")]
+        window: Interval,
+    },
+
+    /// Compare two runs that were saved with `--format json`
+    Diff {
+        /// The results of the old run, e.g. from before a deploy
+        old: PathBuf,
+
+        /// The results of the new run, e.g. from after a deploy
+        new: PathBuf,
+    },
+
+    /// Request paths from CloudFront and Fastly and report where the responses differ
+    Parity {
+        /// The site whose CDNs are compared, i.e. `crates` or `releases`
+        #[arg(long)]
+        site: String,
+
+        /// The paths to request, e.g. `/db-dump.tar.gz`
+        #[arg(required = true)]
+        paths: Vec<String>,
+    },
+}
+
+impl Cli {
+    /// Return the filter that selects the tests to run
+    pub fn filter(&self) -> Filter {
+        Filter::builder()
+            .tags(self.tags.clone())
+            .excluded_tags(self.excluded_tags.clone())
+            .build()
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use pretty_assertions::assert_eq;
+
     use crate::test_utils::*;
 
     use super::*;
 
+    #[test]
+    fn filter_from_tags() {
+        let cli = Cli::parse_from([
+            "infra-smoke-test",
+            "--tag",
+            "cdn:fastly",
+            "--exclude-tag",
+            "issue:crates.io#4891",
+        ]);
+
+        let expected = Filter::builder()
+            .tags(vec![Tag::cdn("fastly")])
+            .excluded_tags(vec![Tag::issue("crates.io#4891")])
+            .build();
+
+        assert_eq!(expected, cli.filter());
+    }
+
+    #[test]
+    fn command_defaults_to_none() {
+        let cli = Cli::parse_from(["infra-smoke-test", "--env", "production"]);
+
+        assert_eq!(&None, cli.command());
+        assert_eq!(&Environment::Production, cli.env());
+    }
+
+    #[test]
+    fn origins_is_global() {
+        let cli = Cli::parse_from(["infra-smoke-test", "validate-config", "--origins"]);
+
+        assert!(cli.origins());
+    }
+
+    #[test]
+    fn command_monitor_with_default_interval() {
+        let cli = Cli::parse_from(["infra-smoke-test", "monitor"]);
+
+        assert_eq!(
+            &Some(Command::Monitor {
+                interval: "5m".parse().unwrap(),
+                metrics: None,
+            }),
+            cli.command()
+        );
+    }
+
+    #[test]
+    fn command_monitor_with_metrics() {
+        let cli = Cli::parse_from([
+            "infra-smoke-test",
+            "monitor",
+            "--interval",
+            "30s",
+            "--metrics",
+            "127.0.0.1:9898",
+        ]);
+
+        assert_eq!(
+            &Some(Command::Monitor {
+                interval: "30s".parse().unwrap(),
+                metrics: Some("127.0.0.1:9898".parse().unwrap()),
+            }),
+            cli.command()
+        );
+    }
+
+    #[test]
+    fn command_serve() {
+        let cli = Cli::parse_from(["infra-smoke-test", "serve", "--address", "0.0.0.0:3000"]);
+
+        assert_eq!(
+            &Some(Command::Serve {
+                address: "0.0.0.0:3000".parse().unwrap(),
+                interval: "5m".parse().unwrap(),
+            }),
+            cli.command()
+        );
+    }
+
+    #[test]
+    fn command_wait_with_defaults() {
+        let cli = Cli::parse_from(["infra-smoke-test", "wait"]);
+
+        assert_eq!(
+            &Some(Command::Wait {
+                timeout: "15m".parse().unwrap(),
+                interval: "30s".parse().unwrap(),
+            }),
+            cli.command()
+        );
+    }
+
+    #[test]
+    fn command_wait_with_tags() {
+        let cli = Cli::parse_from([
+            "infra-smoke-test",
+            "wait",
+            "--timeout",
+            "10m",
+            "--interval",
+            "1m",
+            "--tag",
+            "issue:crates.io#4891",
+        ]);
+
+        assert_eq!(
+            &Some(Command::Wait {
+                timeout: "10m".parse().unwrap(),
+                interval: "1m".parse().unwrap(),
+            }),
+            cli.command()
+        );
+        assert_eq!(&vec![Tag::issue("crates.io#4891")], cli.tags());
+    }
+
+    #[test]
+    fn statsd_is_global() {
+        let cli = Cli::parse_from(["infra-smoke-test", "monitor", "--statsd", "localhost:8125"]);
+
+        assert_eq!(&Some("localhost:8125".to_string()), cli.statsd());
+    }
+
+    #[test]
+    fn command_history() {
+        let cli = Cli::parse_from([
+            "infra-smoke-test",
+            "history",
+            "--history",
+            "history.jsonl",
+            "--window",
+            "12h",
+        ]);
+
+        assert_eq!(
+            &Some(Command::History {
+                window: "12h".parse().unwrap()
+            }),
+            cli.command()
+        );
+        assert_eq!(&Some(PathBuf::from("history.jsonl")), cli.history());
+    }
+
+    #[test]
+    fn command_diff() {
+        let cli = Cli::parse_from(["infra-smoke-test", "diff", "old.json", "new.json"]);
+
+        assert_eq!(
+            &Some(Command::Diff {
+                old: PathBuf::from("old.json"),
+                new: PathBuf::from("new.json"),
+            }),
+            cli.command()
+        );
+    }
+
+    #[test]
+    fn format_defaults_to_text() {
+        let cli = Cli::parse_from(["infra-smoke-test"]);
+
+        assert_eq!(Format::Text, cli.format());
+    }
+
+    #[test]
+    fn format_html_with_output() {
+        let cli = Cli::parse_from([
+            "infra-smoke-test",
+            "--format",
+            "html",
+            "--output",
+            "report.html",
+        ]);
+
+        assert_eq!(Format::Html, cli.format());
+        assert_eq!(&Some(PathBuf::from("report.html")), cli.output());
+    }
+
+    #[test]
+    fn command_explain() {
+        let cli = Cli::parse_from([
+            "infra-smoke-test",
+            "explain",
+            "crates.io > Database dumps > Fastly",
+        ]);
+
+        let expected = Command::Explain {
+            path: TestPath::new("crates.io", "Database dumps", "Fastly"),
+        };
+
+        assert_eq!(&Some(expected), cli.command());
+    }
+
+    #[test]
+    fn command_validate_config() {
+        let cli = Cli::parse_from(["infra-smoke-test", "validate-config", "--env", "production"]);
+
+        assert_eq!(&Some(Command::ValidateConfig), cli.command());
+        assert_eq!(&Environment::Production, cli.env());
+    }
+
+    #[test]
+    fn command_compare() {
+        let cli = Cli::parse_from([
+            "infra-smoke-test",
+            "compare",
+            "--from",
+            "staging",
+            "--to",
+            "production",
+        ]);
+
+        let expected = Command::Compare {
+            from: Environment::Staging,
+            to: Environment::Production,
+        };
+
+        assert_eq!(&Some(expected), cli.command());
+    }
+
+    #[test]
+    fn command_parity() {
+        let cli = Cli::parse_from([
+            "infra-smoke-test",
+            "parity",
+            "--site",
+            "crates",
+            "/db-dump.tar.gz",
+            "/db-dump.zip",
+        ]);
+
+        let expected = Command::Parity {
+            site: "crates".into(),
+            paths: vec!["/db-dump.tar.gz".into(), "/db-dump.zip".into()],
+        };
+
+        assert_eq!(&Some(expected), cli.command());
+    }
+
+    #[test]
+    fn command_parity_requires_paths() {
+        assert!(Cli::try_parse_from(["infra-smoke-test", "parity", "--site", "crates"]).is_err());
+    }
+
+    #[test]
+    fn global_options_after_command() {
+        let cli = Cli::parse_from(["infra-smoke-test", "run", "--quiet", "--tag", "cdn:fastly"]);
+
+        assert_eq!(&Some(Command::Run), cli.command());
+        assert!(cli.quiet());
+        assert_eq!(&vec![Tag::cdn("fastly")], cli.tags());
+    }
+
+    #[test]
+    fn custom_env() {
+        let cli = Cli::parse_from(["infra-smoke-test", "--env", "local"]);
+
+        assert_eq!(&Environment::Custom("local".into()), cli.env());
+    }
+
+    #[test]
+    fn config() {
+        let cli = Cli::parse_from(["infra-smoke-test", "run", "--config", "config.toml"]);
+
+        assert_eq!(&Some(PathBuf::from("config.toml")), cli.config());
+    }
+
+    #[test]
+    fn overrides() {
+        let cli = Cli::parse_from([
+            "infra-smoke-test",
+            "--set",
+            "cdns.fastly.crates_url=http://127.0.0.1:8080",
+            "--set",
+            "crates.crates_4891.krate=my-crate",
+        ]);
+
+        let expected = vec![
+            Override::new("cdns.fastly.crates_url", "http://127.0.0.1:8080"),
+            Override::new("crates.crates_4891.krate", "my-crate"),
+        ];
+
+        assert_eq!(&expected, cli.overrides());
+    }
+
+    #[test]
+    fn baseline() {
+        let cli = Cli::parse_from(["infra-smoke-test", "--baseline", "baseline.toml"]);
+
+        assert_eq!(&Some(PathBuf::from("baseline.toml")), cli.baseline());
+    }
+
     #[test]
     fn trait_send() {
         assert_send::<Cli>();
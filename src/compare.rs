@@ -0,0 +1,555 @@
+//! Compare the results of two environments
+//!
+//! Staging is supposed to behave exactly like production, so that changes can be tested there
+//! before they are promoted. The comparison runs the same tests in two environments and reports
+//! where they differ: tests that pass in one environment but fail in the other, and responses to
+//! the same logical request that have a different status or different headers.
+//!
+//! Requests are matched by their position within a test, since the hostnames and fixtures of the
+//! environments differ. Headers that change with every request, e.g. `Date` or the request IDs of
+//! the CDNs, are ignored, and redirect targets are compared without their hostnames.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use getset::Getters;
+use reqwest::Url;
+
+use crate::environment::Environment;
+use crate::test::{RecordedResponse, TestPath, TestResult, TestSuiteResult};
+
+/// Headers that differ between any two requests and are therefore not compared
+const IGNORED_HEADERS: &[&str] = &[
+    "age",
+    "content-length",
+    "date",
+    "etag",
+    "expires",
+    "last-modified",
+    "server-timing",
+    "via",
+    "x-amz-cf-id",
+    "x-amz-cf-pop",
+    "x-cache",
+    "x-cache-hits",
+    "x-request-id",
+    "x-served-by",
+    "x-timer",
+];
+
+/// The differences between the results of two environments
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Getters)]
+pub struct Comparison {
+    /// The environment that is compared
+    #[getset(get = "pub")]
+    from: Environment,
+
+    /// The environment that it is compared to
+    #[getset(get = "pub")]
+    to: Environment,
+
+    /// The tests whose results differ, sorted by their path
+    #[getset(get = "pub")]
+    differences: Vec<Difference>,
+}
+
+/// The difference between the results of a test in two environments
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Getters)]
+pub struct Difference {
+    /// The path of the test
+    #[getset(get = "pub")]
+    path: TestPath,
+
+    /// Whether the test passed in the first environment, or `None` if it didn't run there
+    #[getset(get = "pub")]
+    from: Option<bool>,
+
+    /// Whether the test passed in the second environment, or `None` if it didn't run there
+    #[getset(get = "pub")]
+    to: Option<bool>,
+
+    /// The responses that differ between the environments
+    #[getset(get = "pub")]
+    responses: Vec<ResponseDifference>,
+}
+
+/// The difference between the responses to the same logical request in two environments
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Getters)]
+pub struct ResponseDifference {
+    /// The method and path of the request, e.g. `HEAD /db-dump.tar.gz`
+    #[getset(get = "pub")]
+    request: String,
+
+    /// The status of the response in the first environment, or `None` if the request wasn't sent
+    #[getset(get = "pub")]
+    from: Option<u16>,
+
+    /// The status of the response in the second environment, or `None` if the request wasn't sent
+    #[getset(get = "pub")]
+    to: Option<u16>,
+
+    /// The headers that differ between the responses
+    #[getset(get = "pub")]
+    headers: Vec<HeaderDifference>,
+}
+
+/// A header that differs between two responses
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Getters)]
+pub struct HeaderDifference {
+    /// The lowercase name of the header
+    #[getset(get = "pub")]
+    name: String,
+
+    /// The value of the header in the first environment, or `None` if it is missing
+    #[getset(get = "pub")]
+    from: Option<String>,
+
+    /// The value of the header in the second environment, or `None` if it is missing
+    #[getset(get = "pub")]
+    to: Option<String>,
+}
+
+impl Comparison {
+    /// Compare the results of the tests in two environments
+    pub fn new(
+        from: Environment,
+        from_results: &[TestSuiteResult],
+        to: Environment,
+        to_results: &[TestSuiteResult],
+    ) -> Self {
+        let mut tests: BTreeMap<TestPath, (Option<&TestResult>, Option<&TestResult>)> =
+            BTreeMap::new();
+
+        for (path, result) in from_results.iter().flat_map(TestSuiteResult::test_results) {
+            tests.entry(path).or_default().0 = Some(result);
+        }
+
+        for (path, result) in to_results.iter().flat_map(TestSuiteResult::test_results) {
+            tests.entry(path).or_default().1 = Some(result);
+        }
+
+        let differences = tests
+            .into_iter()
+            .filter_map(|(path, (from, to))| Difference::new(path, from, to))
+            .collect();
+
+        Self {
+            from,
+            to,
+            differences,
+        }
+    }
+
+    /// Check if the environments behave the same
+    pub fn is_empty(&self) -> bool {
+        self.differences.is_empty()
+    }
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for difference in &self.differences {
+            writeln!(f, "{}", difference.render(&self.from, &self.to))?;
+        }
+
+        if self.is_empty() {
+            writeln!(f, "No differences between {} and {}", self.from, self.to)
+        } else {
+            writeln!(
+                f,
+                "Found {} difference(s) between {} and {}",
+                self.differences.len(),
+                self.from,
+                self.to
+            )
+        }
+    }
+}
+
+impl Difference {
+    /// Compare the results of a test, and return `None` if they don't differ
+    fn new(path: TestPath, from: Option<&TestResult>, to: Option<&TestResult>) -> Option<Self> {
+        let no_responses = Vec::new();
+        let from_responses = from.map_or(&no_responses, TestResult::responses);
+        let to_responses = to.map_or(&no_responses, TestResult::responses);
+
+        let count = from_responses.len().max(to_responses.len());
+        let responses: Vec<ResponseDifference> = (0..count)
+            .filter_map(|index| {
+                ResponseDifference::new(from_responses.get(index), to_responses.get(index))
+            })
+            .collect();
+
+        let from = from.map(TestResult::success);
+        let to = to.map(TestResult::success);
+
+        if from == to && responses.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            path,
+            from,
+            to,
+            responses,
+        })
+    }
+
+    /// Render the difference with the names of the environments
+    pub fn render(&self, from: &Environment, to: &Environment) -> String {
+        let mut display = format!("{}\n", self.path);
+
+        if self.from != self.to {
+            display.push_str(&format!(
+                "  {from}: {}, {to}: {}\n",
+                describe_result(self.from),
+                describe_result(self.to)
+            ));
+        }
+
+        for response in &self.responses {
+            display.push_str(&format!("  {}\n", response.request));
+
+            if response.from != response.to {
+                display.push_str(&format!(
+                    "    Status: {} in {from}, {} in {to}\n",
+                    describe_status(response.from),
+                    describe_status(response.to)
+                ));
+            }
+
+            for header in &response.headers {
+                display.push_str(&format!(
+                    "    {}: {} in {from}, {} in {to}\n",
+                    header.name,
+                    describe_header(header.from.as_deref()),
+                    describe_header(header.to.as_deref())
+                ));
+            }
+        }
+
+        display
+    }
+}
+
+impl ResponseDifference {
+    /// Compare the responses to the same logical request, and return `None` if they don't differ
+    fn new(from: Option<&RecordedResponse>, to: Option<&RecordedResponse>) -> Option<Self> {
+        let request = from.or(to).map(request_label)?;
+
+        let mut names: Vec<&str> = from
+            .into_iter()
+            .chain(to)
+            .flat_map(|response| response.headers().iter().map(|(name, _)| name.as_str()))
+            .filter(|name| !IGNORED_HEADERS.contains(name))
+            .collect();
+        names.sort();
+        names.dedup();
+
+        let headers: Vec<HeaderDifference> = names
+            .into_iter()
+            .filter_map(|name| {
+                let from = from.and_then(|response| header_value(response, name));
+                let to = to.and_then(|response| header_value(response, name));
+
+                (from != to).then(|| HeaderDifference {
+                    name: name.into(),
+                    from,
+                    to,
+                })
+            })
+            .collect();
+
+        let from = from.map(RecordedResponse::status);
+        let to = to.map(RecordedResponse::status);
+
+        if from == to && headers.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            request,
+            from,
+            to,
+            headers,
+        })
+    }
+}
+
+/// Return the method and path of the request, without the hostname of the environment
+fn request_label(response: &RecordedResponse) -> String {
+    let path = Url::parse(response.url())
+        .map(|url| url.path().to_string())
+        .unwrap_or_else(|_| response.url().clone());
+
+    format!("{} {path}", response.method())
+}
+
+/// Return the value of the header, without the hostname of the environment in a redirect target
+fn header_value(response: &RecordedResponse, name: &str) -> Option<String> {
+    let value = response.header(name)?;
+
+    if name != "location" {
+        return Some(value.into());
+    }
+
+    let location = Url::parse(value)
+        .map(|url| match url.query() {
+            Some(query) => format!("{}?{query}", url.path()),
+            None => url.path().to_string(),
+        })
+        .unwrap_or_else(|_| value.into());
+
+    Some(location)
+}
+
+/// Describe the result of a test
+fn describe_result(success: Option<bool>) -> &'static str {
+    match success {
+        Some(true) => "passed",
+        Some(false) => "failed",
+        None => "not run",
+    }
+}
+
+/// Describe the status of a response
+fn describe_status(status: Option<u16>) -> String {
+    match status {
+        Some(status) => format!("HTTP {status}"),
+        None => "not sent".into(),
+    }
+}
+
+/// Describe the value of a header
+fn describe_header(value: Option<&str>) -> String {
+    match value {
+        Some(value) => format!("'{value}'"),
+        None => "missing".into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::test::TestGroupResult;
+    use crate::test_utils::*;
+
+    use super::*;
+
+    fn response(host: &str, status: u16, headers: &[(&str, &str)]) -> RecordedResponse {
+        RecordedResponse::builder()
+            .method("HEAD")
+            .url(format!("https://{host}/db-dump.tar.gz"))
+            .status(status)
+            .headers(
+                headers
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect(),
+            )
+            .build()
+    }
+
+    fn results(name: &'static str, success: bool, response: RecordedResponse) -> TestSuiteResult {
+        let result = TestResult::builder()
+            .name(name)
+            .success(success)
+            .responses(vec![response])
+            .build();
+
+        TestSuiteResult::builder()
+            .name("crates.io")
+            .results(vec![TestGroupResult::builder()
+                .name("Database dumps")
+                .results(vec![result])
+                .build()])
+            .build()
+    }
+
+    #[test]
+    fn new_without_differences() {
+        let staging = results(
+            "Fastly",
+            true,
+            response("staging", 307, &[("date", "Mon"), ("x-cache", "MISS")]),
+        );
+        let production = results(
+            "Fastly",
+            true,
+            response("production", 307, &[("date", "Tue"), ("x-cache", "HIT")]),
+        );
+
+        let comparison = Comparison::new(
+            Environment::Staging,
+            &[staging],
+            Environment::Production,
+            &[production],
+        );
+
+        assert!(comparison.is_empty());
+        assert_eq!(
+            "No differences between staging and production\n",
+            comparison.to_string()
+        );
+    }
+
+    #[test]
+    fn new_with_differences() {
+        let staging = results(
+            "Fastly",
+            false,
+            response("staging", 404, &[("cache-control", "no-store")]),
+        );
+        let production = results(
+            "Fastly",
+            true,
+            response(
+                "production",
+                307,
+                &[("cache-control", "max-age=60"), ("vary", "Origin")],
+            ),
+        );
+
+        let comparison = Comparison::new(
+            Environment::Staging,
+            &[staging],
+            Environment::Production,
+            &[production],
+        );
+
+        let expected = indoc! {"
+            crates.io > Database dumps > Fastly
+              staging: failed, production: passed
+              HEAD /db-dump.tar.gz
+                Status: HTTP 404 in staging, HTTP 307 in production
+                cache-control: 'no-store' in staging, 'max-age=60' in production
+                vary: missing in staging, 'Origin' in production
+
+            Found 1 difference(s) between staging and production
+        "};
+
+        assert_eq!(1, comparison.differences().len());
+        assert_eq!(expected, comparison.to_string());
+    }
+
+    #[test]
+    fn new_with_redirects() {
+        let staging = results(
+            "Fastly",
+            true,
+            response(
+                "staging",
+                307,
+                &[(
+                    "location",
+                    "https://static.staging.crates.io/db-dump.tar.gz",
+                )],
+            ),
+        );
+        let production = results(
+            "Fastly",
+            true,
+            response(
+                "production",
+                307,
+                &[("location", "https://static.crates.io/db-dump.tar.gz")],
+            ),
+        );
+
+        let comparison = Comparison::new(
+            Environment::Staging,
+            &[staging],
+            Environment::Production,
+            &[production],
+        );
+
+        assert!(comparison.is_empty());
+    }
+
+    #[test]
+    fn new_with_different_redirects() {
+        let staging = results(
+            "Fastly",
+            true,
+            response(
+                "staging",
+                307,
+                &[("location", "https://static.staging.crates.io/db-dump.zip")],
+            ),
+        );
+        let production = results(
+            "Fastly",
+            true,
+            response(
+                "production",
+                307,
+                &[("location", "https://static.crates.io/db-dump.tar.gz?v=1")],
+            ),
+        );
+
+        let comparison = Comparison::new(
+            Environment::Staging,
+            &[staging],
+            Environment::Production,
+            &[production],
+        );
+
+        let expected = indoc! {"
+            crates.io > Database dumps > Fastly
+              HEAD /db-dump.tar.gz
+                location: '/db-dump.zip' in staging, '/db-dump.tar.gz?v=1' in production
+
+            Found 1 difference(s) between staging and production
+        "};
+
+        assert_eq!(expected, comparison.to_string());
+    }
+
+    #[test]
+    fn new_with_missing_test() {
+        let staging = results("Fastly", true, response("staging", 307, &[]));
+        let production = results("CloudFront", true, response("production", 307, &[]));
+
+        let comparison = Comparison::new(
+            Environment::Staging,
+            &[staging],
+            Environment::Production,
+            &[production],
+        );
+
+        let paths: Vec<String> = comparison
+            .differences()
+            .iter()
+            .map(|difference| difference.path().to_string())
+            .collect();
+
+        assert_eq!(
+            vec![
+                "crates.io > Database dumps > CloudFront",
+                "crates.io > Database dumps > Fastly"
+            ],
+            paths
+        );
+        assert_eq!(&None, comparison.differences()[0].from());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Comparison>();
+        assert_send::<Difference>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Comparison>();
+        assert_sync::<Difference>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Comparison>();
+        assert_unpin::<Difference>();
+    }
+}
@@ -0,0 +1,988 @@
+//! Configuration for the smoke tests
+//!
+//! The smoke tests need to know which URLs to request and which crates and releases to use as
+//! fixtures. This information differs between environments, and is read from a configuration file
+//! in the TOML format. The configuration for the staging and production environments is built into
+//! the binary, and can be overridden with a custom configuration file:
+//!
+//! ```toml
+//! [crates.crates_4891]
+//! krate = "rust-cratesio-4891"
+//! version = "0.1.0+1"
+//! ```
+//!
+//! The custom configuration is layered on top of the built-in configuration, so that it only needs
+//! to contain the keys that should change.
+//!
+//! The configuration file can also define custom environments, for example to point the smoke
+//! tests at a local stand-in or at a new staging stack. A custom environment can extend another
+//! environment, and then only needs to contain the keys that differ:
+//!
+//! ```toml
+//! [environments.local]
+//! extends = "staging"
+//!
+//! [environments.local.crates.crates_api]
+//! api_url = "http://localhost:8888/api/v1/summary"
+//! ```
+//!
+//! The configuration for an environment is resolved in the following order, with later layers
+//! overriding earlier ones: the built-in configuration at the root of the chain of extended
+//! environments, the top-level keys of the configuration file, the tables of the environments in
+//! the chain from the root to the selected environment, and finally [`Override`]s for single values
+//! from environment variables and the command line.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use getset::Getters;
+use reqwest::Url;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+use toml::{Table, Value};
+
+use crate::cdn::{self, Cdn, Endpoint, Service};
+use crate::environment::Environment;
+use crate::test::Tag;
+use crate::{crates, discovery, health, issue, parity, releases, rustup, webhook};
+
+pub use self::overrides::{Override, ENV_PREFIX};
+pub use self::validate::{Problem, Validate, Validator};
+
+mod overrides;
+mod validate;
+
+/// The built-in configuration for the staging environment
+const STAGING: &str = include_str!("../../config/staging.toml");
+
+/// The built-in configuration for the production environment
+const PRODUCTION: &str = include_str!("../../config/production.toml");
+
+/// The top-level tables of the configuration, which are the keys of the fields of [`Config`]
+pub(crate) const TABLES: &[&str] = &[
+    "cdns",
+    "discovery",
+    "origins",
+    "health",
+    "parity",
+    "webhook",
+    "issue",
+    "crates",
+    "releases",
+    "rustup",
+];
+
+/// Configuration for the smoke tests
+///
+/// The configuration combines the configurations of the test suites. Each test suite reads its
+/// configuration from the table with its name, e.g. `[crates]`, and passes the nested tables on to
+/// its test groups.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The CDNs that serve the Rust project's sites, keyed by the value of their `cdn:` tag
+    #[getset(get = "pub")]
+    cdns: BTreeMap<String, Cdn>,
+
+    /// Configuration for the discovery of fixtures
+    #[getset(get = "pub")]
+    discovery: discovery::Config,
+
+    /// The origins behind the CDNs, e.g. the S3 buckets, keyed by the value of their `origin:` tag
+    #[getset(get = "pub")]
+    origins: BTreeMap<String, Cdn>,
+
+    /// Configuration for the configuration health checks
+    #[getset(get = "pub")]
+    health: health::Config,
+
+    /// Configuration for the parity checks between the CDNs
+    #[getset(get = "pub")]
+    parity: parity::Config,
+
+    /// Configuration for the notifications about failures and recoveries
+    #[getset(get = "pub")]
+    webhook: webhook::Config,
+
+    /// Configuration for the issue that tracks failing tests, which only production uses
+    #[serde(default)]
+    #[getset(get = "pub")]
+    issue: issue::Config,
+
+    /// Configuration for the crates.io test suite
+    #[getset(get = "pub")]
+    crates: crates::Config,
+
+    /// Configuration for the Rust releases test suite
+    #[getset(get = "pub")]
+    releases: releases::Config,
+
+    /// Configuration for the rustup test suite
+    #[getset(get = "pub")]
+    rustup: rustup::Config,
+}
+
+/// Errors that can occur when loading the configuration
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The configuration file could not be read
+    Read {
+        /// The path of the configuration file
+        path: PathBuf,
+
+        /// The error that occurred while reading the file
+        error: std::io::Error,
+    },
+
+    /// The configuration file is not valid TOML
+    Parse(toml::de::Error),
+
+    /// The environment is neither built in nor defined in the configuration file
+    UnknownEnvironment(Environment),
+
+    /// A key in the configuration is missing or has an invalid value
+    Invalid {
+        /// The dotted path of the offending key, e.g. `cdns.fastly.crates_url`
+        key: String,
+
+        /// A description of the problem
+        message: String,
+    },
+}
+
+impl Config {
+    /// Return the built-in configuration for the given environment
+    ///
+    /// Only staging and production have a built-in configuration. Custom environments must be
+    /// loaded from a configuration file with [`Config::load`].
+    pub fn for_env(env: &Environment) -> Result<Self, ConfigError> {
+        Self::load(env, None, &[])
+    }
+
+    /// Load the configuration for the given environment
+    ///
+    /// The configuration file at the given path is layered on top of the built-in configuration for
+    /// the environment. Keys that are not set in the file keep their built-in values. The overrides
+    /// are applied last, in the given order.
+    pub fn load(
+        env: &Environment,
+        path: Option<&Path>,
+        overrides: &[Override],
+    ) -> Result<Self, ConfigError> {
+        let file = match path {
+            Some(path) => {
+                let file = std::fs::read_to_string(path).map_err(|error| ConfigError::Read {
+                    path: path.into(),
+                    error,
+                })?;
+
+                parse(&file)?
+            }
+            None => Table::new(),
+        };
+
+        Self::from_file(env, file, overrides)
+    }
+
+    /// Resolve the configuration for the given environment from a parsed configuration file
+    fn from_file(
+        env: &Environment,
+        mut file: Table,
+        overrides: &[Override],
+    ) -> Result<Self, ConfigError> {
+        let environments = match file.remove("environments") {
+            Some(Value::Table(environments)) => environments,
+            Some(_) => return Err(invalid("environments", "expected a table")),
+            None => Table::new(),
+        };
+
+        let mut table = resolve(env, &environments, &file, &mut Vec::new())?;
+
+        for r#override in overrides {
+            r#override.apply(&mut table)?;
+        }
+
+        Self::from_table(table)
+    }
+
+    /// Return the endpoints of the CDNs that serve the given service
+    pub fn endpoints(&self, service: Service) -> Vec<Endpoint> {
+        cdn::endpoints(&self.cdns, service, Tag::cdn)
+    }
+
+    /// Return the endpoints of the origins that serve the given service
+    ///
+    /// Running the checks against the origins bypasses the CDNs, which shows whether a failing
+    /// check is caused by the origin or by the edge.
+    pub fn origin_endpoints(&self, service: Service) -> Vec<Endpoint> {
+        cdn::endpoints(&self.origins, service, Tag::origin)
+    }
+
+    /// Check the configuration for mistakes
+    ///
+    /// Besides checking the individual values, the hostnames in the configuration are compared with
+    /// the built-in configuration of the counterpart of the environment. A staging configuration
+    /// must not point at a production hostname, and vice versa. Custom environments have no
+    /// counterpart and are only checked value by value.
+    pub fn lint(&self, env: &Environment) -> Vec<Problem> {
+        let mut validator = Validator::new();
+        self.validate(&mut validator);
+
+        let mut problems = validator.problems().clone();
+
+        let counterpart = match env {
+            Environment::Staging => Some(Environment::Production),
+            Environment::Production => Some(Environment::Staging),
+            Environment::Custom(_) => None,
+        };
+
+        if let Some(counterpart) = counterpart {
+            let own_hosts = builtin_hosts(env);
+            let other_hosts = builtin_hosts(&counterpart);
+
+            for (key, url) in validator.urls() {
+                let Some(host) = url.host_str() else {
+                    continue;
+                };
+
+                if other_hosts.contains(host) && !own_hosts.contains(host) {
+                    problems.push(Problem::new(
+                        key,
+                        format!("points at the {counterpart} hostname '{host}'"),
+                    ));
+                }
+            }
+        }
+
+        problems.sort();
+        problems
+    }
+
+    /// Deserialize the configuration from the given table
+    ///
+    /// The path of the key that failed to deserialize is tracked, so that errors can point the
+    /// user to the offending key.
+    fn from_table(table: Table) -> Result<Self, ConfigError> {
+        serde_path_to_error::deserialize(Value::Table(table)).map_err(|error| {
+            let key = error.path().to_string();
+
+            ConfigError::Invalid {
+                key,
+                message: error.into_inner().message().to_string(),
+            }
+        })
+    }
+}
+
+impl Validate for Config {
+    fn validate(&self, validator: &mut Validator) {
+        for (key, cdn) in &self.cdns {
+            validator.nested(&format!("cdns.{key}"), cdn);
+        }
+
+        validator.nested("discovery", &self.discovery);
+
+        for (key, origin) in &self.origins {
+            validator.nested(&format!("origins.{key}"), origin);
+        }
+
+        validator.nested("health", &self.health);
+        validator.nested("parity", &self.parity);
+        validator.nested("webhook", &self.webhook);
+        validator.nested("issue", &self.issue);
+        validator.nested("crates", &self.crates);
+        validator.nested("releases", &self.releases);
+        validator.nested("rustup", &self.rustup);
+    }
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Read { path, error } => {
+                write!(f, "failed to read '{}': {error}", path.display())
+            }
+            ConfigError::Parse(error) => write!(f, "failed to parse the configuration: {error}"),
+            ConfigError::UnknownEnvironment(env) => write!(
+                f,
+                "unknown environment '{env}', define it in the configuration file under \
+                [environments.{env}]"
+            ),
+            ConfigError::Invalid { key, message } => {
+                write!(f, "invalid configuration at '{key}': {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Return the built-in configuration for the given environment
+fn builtin(env: &Environment) -> Option<&'static str> {
+    match env {
+        Environment::Staging => Some(STAGING),
+        Environment::Production => Some(PRODUCTION),
+        Environment::Custom(_) => None,
+    }
+}
+
+/// Return the hostnames in the built-in configuration of the given environment
+fn builtin_hosts(env: &Environment) -> BTreeSet<String> {
+    let Ok(config) = Config::for_env(env) else {
+        return BTreeSet::new();
+    };
+
+    let mut validator = Validator::new();
+    config.validate(&mut validator);
+
+    validator
+        .urls()
+        .iter()
+        .filter_map(|(_, url)| url.host_str().map(String::from))
+        .collect()
+}
+
+/// Resolve the configuration of an environment and the environments that it extends
+///
+/// The top-level keys of the configuration file are merged into the built-in configuration at the
+/// root of the chain, so that the table of every environment in the chain overrides them. The chain
+/// contains the environments that are currently being resolved, and is used to detect environments
+/// that extend themselves.
+fn resolve(
+    env: &Environment,
+    environments: &Table,
+    top_level: &Table,
+    chain: &mut Vec<Environment>,
+) -> Result<Table, ConfigError> {
+    chain.push(env.clone());
+
+    let section = section(env, environments)?;
+
+    let mut table = match (&section, builtin(env)) {
+        (Some((Some(parent), _)), _) => {
+            if chain.contains(parent) {
+                return Err(invalid(
+                    format!("environments.{env}.extends"),
+                    format!("environment '{parent}' extends itself"),
+                ));
+            }
+
+            resolve(parent, environments, top_level, chain)?
+        }
+        (_, Some(builtin)) => {
+            let mut table = parse(builtin)?;
+            merge(&mut table, top_level.clone());
+            table
+        }
+        (Some(_), None) => top_level.clone(),
+        (None, None) => return Err(ConfigError::UnknownEnvironment(env.clone())),
+    };
+
+    if let Some((_, section)) = section {
+        merge(&mut table, section);
+    }
+
+    Ok(table)
+}
+
+/// Return the environment that the given environment extends and its table in the configuration
+fn section(
+    env: &Environment,
+    environments: &Table,
+) -> Result<Option<(Option<Environment>, Table)>, ConfigError> {
+    let mut section = match environments.get(env.name()) {
+        Some(Value::Table(section)) => section.clone(),
+        Some(_) => return Err(invalid(format!("environments.{env}"), "expected a table")),
+        None => return Ok(None),
+    };
+
+    let parent = match section.remove("extends") {
+        Some(Value::String(parent)) => Some(
+            parent
+                .parse()
+                .map_err(|error| invalid(format!("environments.{env}.extends"), error))?,
+        ),
+        Some(_) => {
+            return Err(invalid(
+                format!("environments.{env}.extends"),
+                "expected the name of an environment",
+            ))
+        }
+        None => None,
+    };
+
+    Ok(Some((parent, section)))
+}
+
+/// Create an error for an invalid key
+fn invalid(key: impl Into<String>, message: impl Into<String>) -> ConfigError {
+    ConfigError::Invalid {
+        key: key.into(),
+        message: message.into(),
+    }
+}
+
+/// Parse a configuration file into a TOML table
+fn parse(config: &str) -> Result<Table, ConfigError> {
+    config.parse().map_err(ConfigError::Parse)
+}
+
+/// Merge the overrides into the base table
+///
+/// Nested tables are merged recursively, while all other values in the overrides replace the values
+/// in the base table.
+fn merge(base: &mut Table, overrides: Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(overrides)) => merge(base, overrides),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Deserialize a URL and check that it is valid
+///
+/// URLs are stored as strings in the configuration of the test groups, but are validated when the
+/// configuration is loaded so that typos are caught before any test runs. Some URLs contain secrets,
+/// e.g. the URL of the webhook, so errors name only the problem and not the URL itself.
+pub(crate) fn deserialize_url<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let url = String::deserialize(deserializer)?;
+
+    match Url::parse(&url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => Ok(url),
+        Ok(parsed) => Err(D::Error::custom(format!(
+            "expected an HTTP URL, got scheme '{}'",
+            parsed.scheme()
+        ))),
+        Err(error) => Err(D::Error::custom(format!("invalid URL: {error}"))),
+    }
+}
+
+/// Deserialize an optional URL and check that it is valid
+///
+/// Use this together with `#[serde(default)]`, so that a missing key deserializes to `None`.
+pub(crate) fn deserialize_optional_url<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_url(deserializer).map(Some)
+}
+
+/// Deserialize an optional secret, e.g. a token
+///
+/// The error of a value with the wrong type would include the value, so it is replaced with an
+/// error that only names the expected type. Use this together with `#[serde(default)]`, so that a
+/// missing key deserializes to `None`.
+pub(crate) fn deserialize_optional_secret<'de, D>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    String::deserialize(deserializer)
+        .map(Some)
+        .map_err(|_| D::Error::custom("expected a string"))
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::*;
+
+    use super::*;
+
+    fn load_with(overrides: &str) -> Result<Config, ConfigError> {
+        load_env_with(&Environment::Staging, overrides)
+    }
+
+    fn load_env_with(env: &Environment, file: &str) -> Result<Config, ConfigError> {
+        Config::from_file(env, parse(file).unwrap(), &[])
+    }
+
+    fn local() -> Environment {
+        Environment::Custom("local".into())
+    }
+
+    #[test]
+    fn for_env_staging() {
+        let config = Config::for_env(&Environment::Staging).unwrap();
+
+        assert_eq!(
+            "https://cloudfront-static.staging.crates.io",
+            config.crates().db_dump().cloudfront_url()
+        );
+    }
+
+    #[test]
+    fn for_env_production() {
+        let config = Config::for_env(&Environment::Production).unwrap();
+
+        assert_eq!(
+            "https://win.rustup.rs",
+            config.rustup().win_rustup_rs().cloudfront_url()
+        );
+    }
+
+    #[test]
+    fn load_without_file() {
+        let config = Config::load(&Environment::Production, None, &[]).unwrap();
+
+        assert_eq!(Config::for_env(&Environment::Production).unwrap(), config);
+    }
+
+    #[test]
+    fn load_with_missing_file() {
+        let error = Config::load(&Environment::Staging, Some(Path::new("missing.toml")), &[]);
+
+        assert!(matches!(error, Err(ConfigError::Read { .. })));
+    }
+
+    #[test]
+    fn overrides_replace_builtin_values() {
+        let config = load_with(indoc! {r#"
+            [crates.crates_4891]
+            krate = "my-crate"
+        "#})
+        .unwrap();
+
+        assert_eq!("my-crate", config.crates().crates_4891().krate());
+        assert_eq!("0.1.0+1", config.crates().crates_4891().version());
+    }
+
+    #[test]
+    fn invalid_url_names_key() {
+        let error = load_with(indoc! {r#"
+            [cdns.fastly]
+            crates_url = "not a url"
+        "#})
+        .unwrap_err();
+
+        assert_eq!(
+            "invalid configuration at 'cdns.fastly.crates_url': invalid URL: relative URL without \
+            a base",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn invalid_webhook_url_hides_secret() {
+        let error = load_with(indoc! {r#"
+            [webhook]
+            url = "https//hooks.example.com/services/secret"
+        "#})
+        .unwrap_err();
+
+        assert_eq!(
+            "invalid configuration at 'webhook.url': invalid URL: relative URL without a base",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn invalid_issue_token_hides_secret() {
+        let error = load_with(indoc! {r#"
+            [issue]
+            token = 1234567890
+        "#})
+        .unwrap_err();
+
+        assert_eq!(
+            "invalid configuration at 'issue.token': expected a string",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn invalid_scheme_names_key() {
+        let error = load_with(indoc! {r#"
+            [rustup.win_rustup_rs]
+            cloudfront_url = "ftp://win.rustup.rs"
+        "#})
+        .unwrap_err();
+
+        assert_eq!(
+            "invalid configuration at 'rustup.win_rustup_rs.cloudfront_url': expected an HTTP URL, \
+            got scheme 'ftp'",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn invalid_type_names_key() {
+        let error = load_with(indoc! {r#"
+            [releases.list_files]
+            release = 2024
+        "#})
+        .unwrap_err();
+
+        assert!(matches!(
+            error,
+            ConfigError::Invalid { key, .. } if key == "releases.list_files.release"
+        ));
+    }
+
+    #[test]
+    fn unknown_key_is_named() {
+        let error = load_with(indoc! {r#"
+            [crates.db_dump]
+            fastly = "https://fastly-static.staging.crates.io"
+        "#})
+        .unwrap_err();
+
+        assert!(error.to_string().contains("unknown field `fastly`"));
+    }
+
+    #[test]
+    fn invalid_toml() {
+        assert!(matches!(parse("[crates"), Err(ConfigError::Parse(_))));
+    }
+
+    #[test]
+    fn custom_environment_extends_builtin() {
+        let config = load_env_with(
+            &local(),
+            indoc! {r#"
+                [environments.local]
+                extends = "staging"
+
+                [environments.local.crates.crates_api]
+                api_url = "http://localhost:8888/api/v1/summary"
+            "#},
+        )
+        .unwrap();
+
+        assert_eq!(
+            "http://localhost:8888/api/v1/summary",
+            config.crates().crates_api().api_url()
+        );
+        assert_eq!(
+            "https://dev-win.rustup.rs",
+            config.rustup().win_rustup_rs().cloudfront_url()
+        );
+    }
+
+    #[test]
+    fn custom_environment_extends_custom_environment() {
+        let config = load_env_with(
+            &Environment::Custom("vendor".into()),
+            indoc! {r#"
+                [environments.local]
+                extends = "production"
+
+                [environments.local.crates.crates_4891]
+                krate = "local-crate"
+
+                [environments.vendor]
+                extends = "local"
+
+                [environments.vendor.crates.crates_4891]
+                version = "1.0.0+vendor"
+            "#},
+        )
+        .unwrap();
+
+        assert_eq!("local-crate", config.crates().crates_4891().krate());
+        assert_eq!("1.0.0+vendor", config.crates().crates_4891().version());
+        assert_eq!(
+            "https://win.rustup.rs",
+            config.rustup().win_rustup_rs().cloudfront_url()
+        );
+    }
+
+    #[test]
+    fn custom_environment_without_extends_requires_all_keys() {
+        let error = load_env_with(
+            &local(),
+            indoc! {r#"
+                [environments.local.crates.crates_api]
+                api_url = "http://localhost:8888/api/v1/summary"
+            "#},
+        )
+        .unwrap_err();
+
+        assert!(matches!(error, ConfigError::Invalid { .. }));
+    }
+
+    #[test]
+    fn environment_keys_override_top_level_keys() {
+        let config = load_env_with(
+            &local(),
+            indoc! {r#"
+                [crates.crates_4891]
+                krate = "top-level"
+                version = "0.0.1+top"
+
+                [environments.local]
+                extends = "staging"
+
+                [environments.local.crates.crates_4891]
+                krate = "local"
+            "#},
+        )
+        .unwrap();
+
+        assert_eq!("local", config.crates().crates_4891().krate());
+        assert_eq!("0.0.1+top", config.crates().crates_4891().version());
+    }
+
+    #[test]
+    fn environment_keys_in_chain_override_top_level_keys() {
+        let config = load_env_with(
+            &Environment::Custom("vendor".into()),
+            indoc! {r#"
+                [crates.crates_4891]
+                krate = "top-level"
+                version = "0.0.1+top"
+
+                [environments.local]
+                extends = "staging"
+
+                [environments.local.crates.crates_4891]
+                krate = "local"
+
+                [environments.vendor]
+                extends = "local"
+
+                [environments.vendor.crates.crates_6164]
+                krate = "vendor"
+            "#},
+        )
+        .unwrap();
+
+        assert_eq!("local", config.crates().crates_4891().krate());
+        assert_eq!("0.0.1+top", config.crates().crates_4891().version());
+        assert_eq!("vendor", config.crates().crates_6164().krate());
+    }
+
+    #[test]
+    fn tables_match_builtin_configuration() {
+        for builtin in [STAGING, PRODUCTION] {
+            let table = parse(builtin).unwrap();
+
+            for key in table.keys() {
+                assert!(TABLES.contains(&key.as_str()), "'{key}' is not in TABLES");
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_environment() {
+        let error = Config::for_env(&local()).unwrap_err();
+
+        assert_eq!(
+            "unknown environment 'local', define it in the configuration file under \
+            [environments.local]",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn cyclic_environments() {
+        let error = load_env_with(
+            &local(),
+            indoc! {r#"
+                [environments.local]
+                extends = "other"
+
+                [environments.other]
+                extends = "local"
+            "#},
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            "invalid configuration at 'environments.other.extends': environment 'local' extends \
+            itself",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn invalid_extends_names_key() {
+        let error = load_env_with(
+            &local(),
+            indoc! {r#"
+                [environments.local]
+                extends = 42
+            "#},
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            error,
+            ConfigError::Invalid { key, .. } if key == "environments.local.extends"
+        ));
+    }
+
+    #[test]
+    fn overrides_take_precedence() {
+        let overrides = [
+            Override::new("cdns.fastly.crates_url", "http://127.0.0.1:8080"),
+            Override::new("crates.crates_4891.krate", "first"),
+            Override::new("crates.crates_4891.krate", "second"),
+        ];
+
+        let config = Config::from_file(
+            &Environment::Staging,
+            parse("[crates.crates_4891]\nkrate = 'file'").unwrap(),
+            &overrides,
+        )
+        .unwrap();
+
+        assert_eq!(
+            "http://127.0.0.1:8080",
+            config.endpoints(Service::Crates)[1].url()
+        );
+        assert_eq!("second", config.crates().crates_4891().krate());
+    }
+
+    #[test]
+    fn override_with_unknown_key_names_key() {
+        let overrides = [Override::new(
+            "crates.crates_4891.fastly",
+            "http://127.0.0.1",
+        )];
+
+        let error = Config::from_file(&Environment::Staging, Table::new(), &overrides).unwrap_err();
+
+        assert!(error.to_string().contains("unknown field `fastly`"));
+    }
+
+    #[test]
+    fn origin_endpoints_are_tagged_as_origins() {
+        let config = Config::for_env(&Environment::Production).unwrap();
+
+        let endpoints = config.origin_endpoints(Service::Crates);
+
+        assert_eq!(1, endpoints.len());
+        assert_eq!(&Tag::origin("s3"), endpoints[0].tag());
+        assert_eq!(
+            "https://crates-io.s3.us-west-1.amazonaws.com",
+            endpoints[0].url()
+        );
+    }
+
+    #[test]
+    fn lint_builtin_configurations() {
+        for env in [Environment::Staging, Environment::Production] {
+            let config = Config::for_env(&env).unwrap();
+
+            assert_eq!(Vec::<Problem>::new(), config.lint(&env));
+        }
+    }
+
+    #[test]
+    fn lint_staging_with_production_hostname() {
+        let config = load_with(indoc! {r#"
+            [crates.db_dump]
+            fastly_url = "https://fastly-static.crates.io"
+        "#})
+        .unwrap();
+
+        assert_eq!(
+            vec![Problem::new(
+                "crates.db_dump.fastly_url",
+                "points at the production hostname 'fastly-static.crates.io'"
+            )],
+            config.lint(&Environment::Staging)
+        );
+    }
+
+    #[test]
+    fn lint_production_with_staging_hostname() {
+        let config = load_env_with(
+            &Environment::Production,
+            indoc! {r#"
+                [rustup.win_rustup_rs]
+                cloudfront_url = "https://dev-win.rustup.rs"
+            "#},
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![Problem::new(
+                "rustup.win_rustup_rs.cloudfront_url",
+                "points at the staging hostname 'dev-win.rustup.rs'"
+            )],
+            config.lint(&Environment::Production)
+        );
+    }
+
+    #[test]
+    fn lint_invalid_values() {
+        let config = load_with(indoc! {r#"
+            [crates.crates_4891]
+            version = "1.0"
+
+            [releases.list_files]
+            release = "2024-02-30"
+
+            [crates.crates_api]
+            api_url = "http://localhost:8888/api/v1/summary"
+        "#})
+        .unwrap();
+
+        let keys: Vec<String> = config
+            .lint(&Environment::Staging)
+            .iter()
+            .map(|problem| problem.key().clone())
+            .collect();
+
+        assert_eq!(
+            vec![
+                "crates.crates_4891.version",
+                "crates.crates_api.api_url",
+                "releases.list_files.release",
+            ],
+            keys
+        );
+    }
+
+    #[test]
+    fn lint_custom_environment_skips_hostname_check() {
+        let config = load_env_with(
+            &local(),
+            indoc! {r#"
+                [environments.local]
+                extends = "production"
+            "#},
+        )
+        .unwrap();
+
+        assert!(config.lint(&local()).is_empty());
+    }
+
+    #[test]
+    fn merge_nested_tables() {
+        let mut base = parse("[a]\nb = 1\nc = 2").unwrap();
+        merge(&mut base, parse("[a]\nc = 3").unwrap());
+
+        assert_eq!(parse("[a]\nb = 1\nc = 3").unwrap(), base);
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Config>();
+        assert_send::<ConfigError>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Config>();
+        assert_sync::<ConfigError>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Config>();
+        assert_unpin::<ConfigError>();
+    }
+}
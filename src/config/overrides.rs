@@ -0,0 +1,340 @@
+//! Overrides for single configuration values
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use getset::Getters;
+use toml::{Table, Value};
+
+use super::{invalid, ConfigError, TABLES};
+
+/// The prefix of environment variables that override configuration values
+pub const ENV_PREFIX: &str = "SMOKE_TESTS_";
+
+/// The separator between the components of a key in an environment variable
+const ENV_SEPARATOR: &str = "__";
+
+/// An override for a single configuration value
+///
+/// Overrides make one-off investigations easier, for example trying a new CDN hostname or a
+/// different fixture crate, without writing a configuration file. They are passed on the command
+/// line in the form `--set cdns.fastly.crates_url=http://127.0.0.1:8080`, or as environment
+/// variables such as `SMOKE_TESTS_CDNS__FASTLY__CRATES_URL=http://127.0.0.1:8080`.
+///
+/// Values are parsed as TOML values when possible, e.g. `42` or `true`, and are otherwise used as
+/// strings.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Getters)]
+pub struct Override {
+    /// The dotted path of the key, e.g. `cdns.fastly.crates_url`
+    #[getset(get = "pub")]
+    key: String,
+
+    /// The new value of the key
+    #[getset(get = "pub")]
+    value: String,
+}
+
+impl Override {
+    /// Create a new override
+    pub fn new(key: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+
+    /// Read the overrides from the environment variables of the process
+    pub fn from_env() -> Result<Vec<Self>, ConfigError> {
+        let vars = std::env::vars_os().filter_map(|(name, value)| {
+            Some((name.into_string().ok()?, value.into_string().ok()?))
+        });
+
+        Self::from_vars(vars)
+    }
+
+    /// Read the overrides from the given environment variables
+    ///
+    /// Only variables that start with [`ENV_PREFIX`] are considered. The rest of the name is
+    /// lowercased, and `__` separates the components of the key. Variables whose first component is
+    /// not a table of the configuration are ignored, so that unrelated variables with the same
+    /// prefix, e.g. `SMOKE_TESTS_BRANCH` in CI, don't break the configuration. The overrides are
+    /// sorted by their key, so that they are applied in a deterministic order.
+    pub fn from_vars(
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<Vec<Self>, ConfigError> {
+        let mut overrides = vars
+            .into_iter()
+            .filter_map(|(name, value)| {
+                let key = name.strip_prefix(ENV_PREFIX)?;
+                let key = key
+                    .split(ENV_SEPARATOR)
+                    .map(str::to_lowercase)
+                    .collect::<Vec<_>>()
+                    .join(".");
+
+                let table = key.split('.').next().unwrap_or_default();
+                if !TABLES.contains(&table) {
+                    return None;
+                }
+
+                Some(match validate_key(&key) {
+                    Ok(()) => Ok(Self::new(key, value)),
+                    Err(message) => Err(invalid(name, message)),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        overrides.sort();
+
+        Ok(overrides)
+    }
+
+    /// Set the value in the given table
+    ///
+    /// Missing tables along the path of the key are created. Whether the key exists is checked when
+    /// the configuration is deserialized.
+    pub(super) fn apply(&self, table: &mut Table) -> Result<(), ConfigError> {
+        let components: Vec<&str> = self.key.split('.').collect();
+        let (last, parents) = components
+            .split_last()
+            .expect("keys have at least one component");
+
+        let mut current = table;
+
+        for (index, component) in parents.iter().enumerate() {
+            let value = current
+                .entry(*component)
+                .or_insert_with(|| Value::Table(Table::new()));
+
+            current = match value {
+                Value::Table(table) => table,
+                _ => {
+                    return Err(invalid(
+                        components[..=index].join("."),
+                        "expected a table, got a value",
+                    ))
+                }
+            };
+        }
+
+        current.insert((*last).into(), self.parse_value());
+
+        Ok(())
+    }
+
+    /// Parse the value as a TOML value, falling back to a string
+    ///
+    /// Dates are kept as strings, since the configuration stores dates like the release date of a
+    /// fixture as strings.
+    fn parse_value(&self) -> Value {
+        let document = format!("value = {}", self.value);
+
+        match document
+            .parse::<Table>()
+            .map(|mut table| table.remove("value"))
+        {
+            Ok(Some(Value::Datetime(_))) | Ok(None) | Err(_) => Value::String(self.value.clone()),
+            Ok(Some(value)) => value,
+        }
+    }
+}
+
+impl Display for Override {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.key, self.value)
+    }
+}
+
+impl FromStr for Override {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected an override in the format 'key=value', got '{s}'"))?;
+
+        let key = key.trim();
+        validate_key(key)?;
+
+        Ok(Self::new(key, value))
+    }
+}
+
+/// Check that the key consists of non-empty components separated by dots
+fn validate_key(key: &str) -> Result<(), String> {
+    let valid = key.split('.').all(|component| {
+        !component.is_empty()
+            && component
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    });
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "expected a key like 'cdns.fastly.crates_url', got '{key}'"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::*;
+
+    use super::*;
+
+    #[test]
+    fn from_vars() {
+        let vars = [
+            ("PATH".to_string(), "/usr/bin".to_string()),
+            (
+                "SMOKE_TESTS_CDNS__FASTLY__CRATES_URL".to_string(),
+                "http://127.0.0.1:8080".to_string(),
+            ),
+            (
+                "SMOKE_TESTS_CRATES__CRATES_4891__KRATE".to_string(),
+                "my-crate".to_string(),
+            ),
+        ];
+
+        let expected = vec![
+            Override::new("cdns.fastly.crates_url", "http://127.0.0.1:8080"),
+            Override::new("crates.crates_4891.krate", "my-crate"),
+        ];
+
+        assert_eq!(expected, Override::from_vars(vars).unwrap());
+    }
+
+    #[test]
+    fn from_vars_ignores_unrelated_variables() {
+        let vars = [
+            ("SMOKE_TESTS_BRANCH".to_string(), "main".to_string()),
+            ("SMOKE_TESTS_RUN__ID".to_string(), "42".to_string()),
+        ];
+
+        assert_eq!(Vec::<Override>::new(), Override::from_vars(vars).unwrap());
+    }
+
+    #[test]
+    fn from_vars_with_invalid_name() {
+        let vars = [("SMOKE_TESTS_CRATES____KRATE".to_string(), "x".to_string())];
+
+        let error = Override::from_vars(vars).unwrap_err();
+
+        assert!(matches!(
+            error,
+            ConfigError::Invalid { key, .. } if key == "SMOKE_TESTS_CRATES____KRATE"
+        ));
+    }
+
+    #[test]
+    fn apply_string() {
+        let mut table = Table::new();
+
+        Override::new("cdns.fastly.crates_url", "http://127.0.0.1:8080")
+            .apply(&mut table)
+            .unwrap();
+
+        let expected: Table = "cdns.fastly.crates_url = 'http://127.0.0.1:8080'"
+            .parse()
+            .unwrap();
+
+        assert_eq!(expected, table);
+    }
+
+    #[test]
+    fn apply_replaces_value() {
+        let mut table: Table = "[a]\nb = 'old'\nc = 'kept'".parse().unwrap();
+
+        Override::new("a.b", "new").apply(&mut table).unwrap();
+
+        assert_eq!(
+            "[a]\nb = 'new'\nc = 'kept'".parse::<Table>().unwrap(),
+            table
+        );
+    }
+
+    #[test]
+    fn apply_keeps_dates_as_strings() {
+        let mut table = Table::new();
+
+        Override::new("release", "2024-09-03")
+            .apply(&mut table)
+            .unwrap();
+
+        assert_eq!(
+            Some(&Value::String("2024-09-03".into())),
+            table.get("release")
+        );
+    }
+
+    #[test]
+    fn apply_parses_toml_values() {
+        let mut table = Table::new();
+
+        Override::new("number", "42").apply(&mut table).unwrap();
+        Override::new("flag", "true").apply(&mut table).unwrap();
+
+        assert_eq!(Some(&Value::Integer(42)), table.get("number"));
+        assert_eq!(Some(&Value::Boolean(true)), table.get("flag"));
+    }
+
+    #[test]
+    fn apply_below_value() {
+        let mut table: Table = "[a]\nb = 'value'".parse().unwrap();
+
+        let error = Override::new("a.b.c", "x").apply(&mut table).unwrap_err();
+
+        assert_eq!(
+            "invalid configuration at 'a.b': expected a table, got a value",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn trait_display() {
+        assert_eq!("a.b=c", Override::new("a.b", "c").to_string());
+    }
+
+    #[test]
+    fn trait_from_str() {
+        let expected = Override::new("cdns.fastly.crates_url", "http://127.0.0.1:8080");
+
+        assert_eq!(
+            Ok(expected),
+            "cdns.fastly.crates_url=http://127.0.0.1:8080".parse()
+        );
+    }
+
+    #[test]
+    fn trait_from_str_keeps_equals_in_value() {
+        let expected = Override::new("a", "b=c");
+
+        assert_eq!(Ok(expected), "a=b=c".parse());
+    }
+
+    #[test]
+    fn trait_from_str_with_invalid_input() {
+        assert!("crates.crates_4891".parse::<Override>().is_err());
+        assert!("=value".parse::<Override>().is_err());
+        assert!("crates..krate=value".parse::<Override>().is_err());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Override>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Override>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Override>();
+    }
+}
@@ -0,0 +1,249 @@
+//! Validation of the configuration
+//!
+//! Loading the configuration only checks that all keys are present and have the right type. The
+//! checks in this module go further and catch copy-paste mistakes, for example a staging
+//! configuration that points at a production hostname, or a fixture version that is not valid
+//! semver. They are run by the `validate-config` subcommand.
+
+use std::fmt::{Display, Formatter};
+
+use chrono::NaiveDate;
+use getset::Getters;
+use reqwest::Url;
+
+/// Validate a configuration
+///
+/// The configuration of each test suite and test group implements this trait, and reports the
+/// values that it wants to have checked to the [`Validator`].
+pub trait Validate {
+    /// Check the values of the configuration with the given validator
+    fn validate(&self, validator: &mut Validator);
+}
+
+/// A problem that was found in the configuration
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Getters)]
+pub struct Problem {
+    /// The dotted path of the offending key, e.g. `cdns.fastly.crates_url`
+    #[getset(get = "pub")]
+    key: String,
+
+    /// A description of the problem
+    #[getset(get = "pub")]
+    message: String,
+}
+
+/// A validator that checks configuration values and collects the problems it finds
+///
+/// The validator keeps track of the path of the table that is being validated, so that problems
+/// name the full key. It also collects the URLs in the configuration, so that they can be compared
+/// with the hostnames of other environments.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters)]
+pub struct Validator {
+    /// The components of the path of the table that is being validated
+    prefix: Vec<String>,
+
+    /// The problems that have been found so far
+    #[getset(get = "pub")]
+    problems: Vec<Problem>,
+
+    /// The URLs that have been found so far together with their keys
+    #[getset(get = "pub")]
+    urls: Vec<(String, Url)>,
+}
+
+impl Problem {
+    /// Create a new problem
+    pub fn new(key: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+impl Validator {
+    /// Create a new validator
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validate a nested table
+    pub fn nested(&mut self, key: &str, validate: &dyn Validate) {
+        self.prefix.push(key.into());
+        validate.validate(self);
+        self.prefix.pop();
+    }
+
+    /// Check that the value is a valid HTTPS URL
+    ///
+    /// Some URLs contain secrets, e.g. the URL of the webhook, so the problems don't include the
+    /// value.
+    pub fn url(&mut self, key: &str, value: &str) {
+        match Url::parse(value) {
+            Ok(url) => {
+                if url.scheme() != "https" {
+                    self.problem(
+                        key,
+                        format!("expected an HTTPS URL, got scheme '{}'", url.scheme()),
+                    );
+                }
+
+                self.urls.push((self.path(key), url));
+            }
+            Err(error) => self.problem(key, format!("invalid URL: {error}")),
+        }
+    }
+
+    /// Check that the value is a valid semantic version
+    pub fn version(&mut self, key: &str, value: &str) {
+        if let Err(error) = semver::Version::parse(value) {
+            self.problem(key, format!("invalid version '{value}': {error}"));
+        }
+    }
+
+    /// Check that the value is a date in the format `YYYY-MM-DD`
+    pub fn date(&mut self, key: &str, value: &str) {
+        if let Err(error) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            self.problem(key, format!("invalid date '{value}': {error}"));
+        }
+    }
+
+    /// Report a problem with the given key
+    pub fn problem(&mut self, key: &str, message: impl Into<String>) {
+        let problem = Problem::new(self.path(key), message);
+        self.problems.push(problem);
+    }
+
+    /// Return the full path of the given key
+    fn path(&self, key: &str) -> String {
+        self.prefix
+            .iter()
+            .map(String::as_str)
+            .chain(std::iter::once(key))
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::*;
+
+    use super::*;
+
+    struct Group;
+
+    impl Validate for Group {
+        fn validate(&self, validator: &mut Validator) {
+            validator.url("url", "http://example.com");
+        }
+    }
+
+    #[test]
+    fn url_with_https() {
+        let mut validator = Validator::new();
+
+        validator.url("url", "https://example.com");
+
+        assert!(validator.problems().is_empty());
+        assert_eq!(1, validator.urls().len());
+    }
+
+    #[test]
+    fn url_with_http() {
+        let mut validator = Validator::new();
+
+        validator.url("url", "http://example.com");
+
+        assert_eq!(
+            vec![Problem::new(
+                "url",
+                "expected an HTTPS URL, got scheme 'http'"
+            )],
+            validator.problems().clone()
+        );
+    }
+
+    #[test]
+    fn url_with_invalid_url() {
+        let mut validator = Validator::new();
+
+        validator.url("url", "example.com");
+
+        assert_eq!(1, validator.problems().len());
+        assert!(validator.urls().is_empty());
+    }
+
+    #[test]
+    fn version() {
+        let mut validator = Validator::new();
+
+        validator.version("valid", "0.12.25+1.3.0");
+        validator.version("invalid", "0.12");
+
+        assert_eq!(1, validator.problems().len());
+        assert_eq!("invalid", validator.problems()[0].key());
+    }
+
+    #[test]
+    fn date() {
+        let mut validator = Validator::new();
+
+        validator.date("valid", "2024-09-03");
+        validator.date("invalid", "2024-13-03");
+
+        assert_eq!(1, validator.problems().len());
+        assert_eq!("invalid", validator.problems()[0].key());
+    }
+
+    #[test]
+    fn nested() {
+        let mut validator = Validator::new();
+
+        validator.nested("suite", &NestedGroup);
+
+        assert_eq!("suite.group.url", validator.problems()[0].key());
+        assert_eq!("suite.group.url", validator.urls()[0].0);
+    }
+
+    struct NestedGroup;
+
+    impl Validate for NestedGroup {
+        fn validate(&self, validator: &mut Validator) {
+            validator.nested("group", &Group);
+        }
+    }
+
+    #[test]
+    fn trait_display() {
+        let problem = Problem::new("a.b", "message");
+
+        assert_eq!("a.b: message", problem.to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Problem>();
+        assert_send::<Validator>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Problem>();
+        assert_sync::<Validator>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Problem>();
+        assert_unpin::<Validator>();
+    }
+}
@@ -0,0 +1,68 @@
+//! Configuration for the crates.io test suite
+
+use getset::Getters;
+use serde::Deserialize;
+
+use crate::config::{Validate, Validator};
+
+use super::{crates_4891, crates_6164, crates_api, crates_index, db_dump};
+
+/// Configuration for the crates.io test suite
+///
+/// The configuration combines the configurations of the test groups in the suite. It is read from
+/// the `[crates]` table of the configuration file.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Configuration to test rust-lang/crates.io#4891
+    #[getset(get = "pub(crate)")]
+    crates_4891: crates_4891::Config,
+
+    /// Configuration to test rust-lang/crates.io#6164
+    #[getset(get = "pub(crate)")]
+    crates_6164: crates_6164::Config,
+
+    /// Configuration to test the crates.io API
+    #[getset(get = "pub(crate)")]
+    crates_api: crates_api::Config,
+
+    /// Configuration to test the index domain
+    #[getset(get = "pub(crate)")]
+    crates_index: crates_index::Config,
+
+    /// Configuration to test the database dump
+    #[getset(get = "pub(crate)")]
+    db_dump: db_dump::Config,
+}
+
+impl Validate for Config {
+    fn validate(&self, validator: &mut Validator) {
+        validator.nested("crates_4891", &self.crates_4891);
+        validator.nested("crates_6164", &self.crates_6164);
+        validator.nested("crates_api", &self.crates_api);
+        validator.nested("crates_index", &self.crates_index);
+        validator.nested("db_dump", &self.db_dump);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::*;
+
+    use super::*;
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Config>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Config>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Config>();
+    }
+}
@@ -1,17 +1,19 @@
 //! Configuration to test rust-lang/crates.io#4891
 
 use getset::Getters;
+use serde::Deserialize;
 #[cfg(test)]
 use typed_builder::TypedBuilder;
 
-use crate::environment::Environment;
+use crate::config::{Validate, Validator};
 
 /// Configuration to test rust-lang/crates.io#4891
 ///
 /// The smoke tests try to access a crate with a `+` character in its version on all the different
 /// Content Delivery Networks. The configuration provides a crate in the different environments that
-/// can be used for the tests as well as the URLs for the CDNs.
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters)]
+/// can be used for the tests. The URLs of the CDNs are configured in the `[cdns]` table.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters, Deserialize)]
+#[serde(deny_unknown_fields)]
 #[cfg_attr(test, derive(TypedBuilder))]
 pub struct Config {
     /// The name of the crate
@@ -21,42 +23,36 @@ pub struct Config {
     /// The version with the `+` character
     #[getset(get = "pub")]
     version: String,
-
-    /// The URL for the CloudFront CDN
-    #[getset(get = "pub")]
-    cloudfront_url: String,
-
-    /// The URL for the Fastly CDN
-    #[getset(get = "pub")]
-    fastly_url: String,
 }
 
-impl Config {
-    /// Return the configuration for the given environment
-    pub fn for_env(env: Environment) -> Self {
-        match env {
-            Environment::Staging => Self {
-                krate: "rust-cratesio-4891".into(),
-                version: "0.1.0+1".into(),
-                cloudfront_url: "https://cloudfront-static.staging.crates.io".into(),
-                fastly_url: "https://fastly-static.staging.crates.io".into(),
-            },
-            Environment::Production => Self {
-                krate: "libgit2-sys".into(),
-                version: "0.12.25+1.3.0".into(),
-                cloudfront_url: "https://cloudfront-static.crates.io".into(),
-                fastly_url: "https://fastly-static.crates.io".into(),
-            },
-        }
+impl Validate for Config {
+    fn validate(&self, validator: &mut Validator) {
+        validator.version("version", &self.version);
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use pretty_assertions::assert_eq;
+
     use crate::test_utils::*;
 
     use super::*;
 
+    #[test]
+    fn validate() {
+        let config = Config::builder()
+            .krate("rust-cratesio-4891".into())
+            .version("0.1+1".into())
+            .build();
+
+        let mut validator = Validator::new();
+        config.validate(&mut validator);
+
+        assert_eq!(1, validator.problems().len());
+        assert_eq!("version", validator.problems()[0].key());
+    }
+
     #[test]
     fn trait_send() {
         assert_send::<Config>();
@@ -6,28 +6,22 @@
 use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 
-use async_trait::async_trait;
-use reqwest::StatusCode;
-use tokio::task::JoinSet;
-
-use crate::environment::Environment;
-use crate::test::{Test, TestGroup, TestGroupResult, TestResult};
-
-use self::cloudfront_encoded::CloudfrontEncoded;
-use self::cloudfront_space::CloudfrontSpace;
-use self::cloudfront_unencoded::CloudfrontUnencoded;
-use self::config::Config;
-use self::fastly_encoded::FastlyEncoded;
-use self::fastly_space::FastlySpace;
-use self::fastly_unencoded::FastlyUnencoded;
-
-mod cloudfront_encoded;
-mod cloudfront_space;
-mod cloudfront_unencoded;
+use reqwest::{Client, StatusCode};
+
+use crate::cdn::Endpoint;
+use crate::http_client::send;
+use crate::test::{Metadata, Tag, Test, TestGroup, TestResult};
+
+use self::encoded::Encoded;
+use self::space::Space;
+use self::unencoded::Unencoded;
+
+pub use self::config::Config;
+
 mod config;
-mod fastly_encoded;
-mod fastly_space;
-mod fastly_unencoded;
+mod encoded;
+mod space;
+mod unencoded;
 
 /// The name of the test group
 const NAME: &str = "rust-lang/crates.io#4891 - Encoded + character";
@@ -42,13 +36,17 @@ const NAME: &str = "rust-lang/crates.io#4891 - Encoded + character";
 pub struct Crates4891 {
     /// Configuration for the test group
     config: Arc<Config>,
+
+    /// The CDNs that serve crates.io
+    cdns: Vec<Endpoint>,
 }
 
 impl Crates4891 {
-    /// Create a new instance of the test group
-    pub fn new(env: Environment) -> Self {
+    /// Create a new instance of the test group that checks the given CDNs
+    pub fn new(config: Config, cdns: Vec<Endpoint>) -> Self {
         Self {
-            config: Arc::new(Config::for_env(env)),
+            config: Arc::new(config),
+            cdns,
         }
     }
 }
@@ -59,29 +57,47 @@ impl Display for Crates4891 {
     }
 }
 
-#[async_trait]
 impl TestGroup for Crates4891 {
-    async fn run(&self) -> TestGroupResult {
-        let tests: Vec<Box<dyn Test>> = vec![
-            Box::new(CloudfrontEncoded::new(self.config.clone())),
-            Box::new(CloudfrontUnencoded::new(self.config.clone())),
-            Box::new(CloudfrontSpace::new(self.config.clone())),
-            Box::new(FastlyEncoded::new(self.config.clone())),
-            Box::new(FastlyUnencoded::new(self.config.clone())),
-            Box::new(FastlySpace::new(self.config.clone())),
-        ];
+    fn name(&self) -> &'static str {
+        NAME
+    }
 
-        let mut js = JoinSet::new();
-        for test in tests {
-            js.spawn(async move { test.run().await });
-        }
+    fn metadata(&self) -> Metadata {
+        Metadata::builder()
+            .description(
+                "Requests that encoded the `+` character in the version of a crate used \
+                to receive HTTP 403 Forbidden, because the `+` character has a special meaning in \
+                S3. The CDNs must rewrite these URLs so that the crate can be downloaded.",
+            )
+            .team("infra")
+            .issue("https://github.com/rust-lang/crates.io/issues/4891")
+            .build()
+    }
 
-        let results = js.join_all().await;
+    fn tags(&self) -> Vec<Tag> {
+        vec![
+            Tag::issue("crates.io#4891"),
+            Tag::service("static.crates.io"),
+        ]
+    }
 
-        TestGroupResult::builder()
-            .name(NAME)
-            .results(results)
-            .build()
+    fn tests(&self) -> Vec<Box<dyn Test>> {
+        self.cdns
+            .iter()
+            .flat_map(|cdn| -> Vec<Box<dyn Test>> {
+                // Only the CDNs rewrite the encoded `+` and the space, so the origins can only serve
+                // the unencoded URL
+                if cdn.is_origin() {
+                    return vec![Box::new(Unencoded::new(cdn.clone(), self.config.clone()))];
+                }
+
+                vec![
+                    Box::new(Encoded::new(cdn.clone(), self.config.clone())),
+                    Box::new(Unencoded::new(cdn.clone(), self.config.clone())),
+                    Box::new(Space::new(cdn.clone(), self.config.clone())),
+                ]
+            })
+            .collect()
     }
 }
 
@@ -92,11 +108,11 @@ impl TestGroup for Crates4891 {
 /// status code does not match the expected status code, the test will return an unsuccessful
 /// `TestResult`.
 async fn request_url_and_expect_status(
-    name: &'static str,
+    name: &str,
     url: &str,
     expected_status: StatusCode,
 ) -> TestResult {
-    let response = match reqwest::get(url).await {
+    let response = match send(Client::new().get(url)).await {
         Ok(response) => response,
         Err(error) => {
             return TestResult::builder()
@@ -130,22 +146,68 @@ mod tests {
 
     use super::*;
 
-    pub async fn setup(krate: &'static str, version: &'static str) -> (ServerGuard, Config) {
+    pub async fn setup(
+        krate: &'static str,
+        version: &'static str,
+    ) -> (ServerGuard, Endpoint, Config) {
         let server = mockito::Server::new_async().await;
 
+        let endpoint = Endpoint::new("Fastly", Tag::cdn("fastly"), server.url());
+
         let config = Config::builder()
             .krate(krate.into())
             .version(version.into())
-            .cloudfront_url(server.url())
-            .fastly_url(server.url())
             .build();
 
-        (server, config)
+        (server, endpoint, config)
+    }
+
+    #[test]
+    fn tests_for_every_cdn() {
+        let cdns = vec![
+            Endpoint::new("CloudFront", Tag::cdn("cloudfront"), "https://cloudfront"),
+            Endpoint::new("Fastly", Tag::cdn("fastly"), "https://fastly"),
+        ];
+
+        let crates_4891 = Crates4891::new(Config::default(), cdns);
+
+        let names: Vec<String> = crates_4891
+            .tests()
+            .iter()
+            .map(|test| test.name().to_string())
+            .collect();
+
+        assert_eq!(
+            vec![
+                "CloudFront encoded",
+                "CloudFront unencoded",
+                "CloudFront with space",
+                "Fastly encoded",
+                "Fastly unencoded",
+                "Fastly with space",
+            ],
+            names
+        );
+    }
+
+    #[test]
+    fn tests_for_origin() {
+        let cdns = vec![Endpoint::new("S3", Tag::origin("s3"), "https://s3")];
+
+        let crates_4891 = Crates4891::new(Config::default(), cdns);
+
+        let names: Vec<String> = crates_4891
+            .tests()
+            .iter()
+            .map(|test| test.name().to_string())
+            .collect();
+
+        assert_eq!(vec!["S3 unencoded"], names);
     }
 
     #[test]
     fn trait_display() {
-        let crates_4891 = Crates4891::new(Environment::Staging);
+        let crates_4891 = Crates4891::new(Config::default(), Vec::new());
 
         assert_eq!(
             "rust-lang/crates.io#4891 - Encoded + character",
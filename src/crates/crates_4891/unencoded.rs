@@ -0,0 +1,147 @@
+//! Test the CDNs with an unencoded URL
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use reqwest::StatusCode;
+
+use crate::cdn::Endpoint;
+use crate::crates::utils::crate_url;
+use crate::test::{Metadata, Tag, Test, TestResult};
+
+use super::config::Config;
+use super::request_url_and_expect_status;
+
+/// Test a CDN with an unencoded URL
+///
+/// This test requests a URL with an un-encoded `+` character from a CDN. The test expects the CDN
+/// to return an HTTP 200 OK response. The test is instantiated for every CDN that serves
+/// crates.io.
+pub struct Unencoded {
+    /// The name of the test, which includes the name of the CDN
+    name: String,
+
+    /// The CDN to request
+    endpoint: Endpoint,
+
+    /// Configuration for this test
+    config: Arc<Config>,
+}
+
+impl Unencoded {
+    /// Create a new instance of the test for the given CDN
+    pub fn new(endpoint: Endpoint, config: Arc<Config>) -> Self {
+        Self {
+            name: format!("{} unencoded", endpoint.name()),
+            endpoint,
+            config,
+        }
+    }
+}
+
+#[async_trait]
+impl Test for Unencoded {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn metadata(&self) -> Metadata {
+        Metadata::builder()
+            .description(
+                "Request a crate with an unencoded `+` character in its version from the \
+                CDN and expect HTTP 200 OK.",
+            )
+            .build()
+    }
+
+    fn tags(&self) -> Vec<Tag> {
+        vec![self.endpoint.tag().clone()]
+    }
+
+    async fn run(&self) -> TestResult {
+        let url = crate_url(
+            self.endpoint.url(),
+            self.config.krate(),
+            self.config.version(),
+        );
+
+        request_url_and_expect_status(&self.name, &url, StatusCode::OK).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::crates::crates_4891::tests::setup;
+    use crate::test_utils::*;
+
+    use super::*;
+
+    const KRATE: &str = "rust-cratesio-4891";
+    const VERSION: &str = "0.1.0+1";
+
+    #[tokio::test]
+    async fn succeeds_with_http_200_response() {
+        let (mut server, endpoint, config) = setup(KRATE, VERSION).await;
+
+        let mock = server
+            .mock(
+                "GET",
+                format!("/crates/{KRATE}/{KRATE}-{VERSION}.crate").as_str(),
+            )
+            .with_status(200)
+            .create();
+
+        let result = Unencoded::new(endpoint, Arc::new(config)).run().await;
+
+        // Assert that the mock was called
+        mock.assert();
+
+        assert!(result.success());
+    }
+
+    #[tokio::test]
+    async fn fails_with_other_http_responses() {
+        let (mut server, endpoint, config) = setup(KRATE, VERSION).await;
+
+        let mock = server
+            .mock(
+                "GET",
+                format!("/crates/{KRATE}/{KRATE}-{VERSION}.crate").as_str(),
+            )
+            .with_status(403)
+            .create();
+
+        let result = Unencoded::new(endpoint, Arc::new(config)).run().await;
+
+        // Assert that the mock was called
+        mock.assert();
+
+        assert!(!result.success());
+    }
+
+    #[test]
+    fn name_includes_cdn() {
+        let endpoint = Endpoint::new("Fastly", Tag::cdn("fastly"), "https://fastly");
+        let test = Unencoded::new(endpoint, Arc::new(Config::default()));
+
+        assert_eq!("Fastly unencoded", test.name());
+        assert_eq!(vec![Tag::cdn("fastly")], test.tags());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Unencoded>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Unencoded>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Unencoded>();
+    }
+}
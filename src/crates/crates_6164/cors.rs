@@ -0,0 +1,131 @@
+//! Test the CORS headers on a CDN
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::cdn::Endpoint;
+use crate::crates::utils::crate_url;
+use crate::test::{Metadata, Tag, Test, TestResult};
+
+use super::config::Config;
+use super::request_url_and_expect_cors_header;
+
+/// Test the CORS headers on a CDN
+///
+/// This test requests a crate from a CDN and expects the response to have the correct CORS headers.
+/// The test is named after the CDN, and is instantiated for every CDN that serves crates.io.
+pub struct Cors {
+    /// The CDN to request
+    endpoint: Endpoint,
+
+    /// Configuration for this test
+    config: Arc<Config>,
+}
+
+impl Cors {
+    /// Create a new instance of the test for the given CDN
+    pub fn new(endpoint: Endpoint, config: Arc<Config>) -> Self {
+        Self { endpoint, config }
+    }
+}
+
+#[async_trait]
+impl Test for Cors {
+    fn name(&self) -> &str {
+        self.endpoint.name()
+    }
+
+    fn metadata(&self) -> Metadata {
+        Metadata::builder()
+            .description(
+                "Download a crate from the CDN and expect the `Access-Control-Allow-Origin` \
+                header to be set to `*`.",
+            )
+            .build()
+    }
+
+    fn tags(&self) -> Vec<Tag> {
+        vec![self.endpoint.tag().clone()]
+    }
+
+    async fn run(&self) -> TestResult {
+        let url = crate_url(
+            self.endpoint.url(),
+            self.config.krate(),
+            self.config.version(),
+        );
+
+        request_url_and_expect_cors_header(self.endpoint.name(), &url).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::crates::crates_6164::tests::setup;
+    use crate::test_utils::*;
+
+    use super::*;
+
+    const KRATE: &str = "crates-6164";
+    const VERSION: &str = "1.0.0";
+
+    #[tokio::test]
+    async fn succeeds_with_cors_header() {
+        let (mut server, endpoint, config) = setup(KRATE, VERSION).await;
+
+        let mock = server
+            .mock(
+                "GET",
+                format!("/crates/{KRATE}/{KRATE}-{VERSION}.crate").as_str(),
+            )
+            .with_status(200)
+            .with_header("Access-Control-Allow-Origin", "*")
+            .create();
+
+        let result = Cors::new(endpoint, Arc::new(config)).run().await;
+
+        // Assert that the mock was called
+        mock.assert();
+
+        assert_eq!("Fastly", result.name());
+        assert!(result.success());
+    }
+
+    #[tokio::test]
+    async fn fails_without_cors_header() {
+        let (mut server, endpoint, config) = setup(KRATE, VERSION).await;
+
+        let mock = server
+            .mock(
+                "GET",
+                format!("/crates/{KRATE}/{KRATE}-{VERSION}.crate").as_str(),
+            )
+            .with_status(200)
+            .create();
+
+        let result = Cors::new(endpoint, Arc::new(config)).run().await;
+
+        // Assert that the mock was called
+        mock.assert();
+
+        assert!(!result.success());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Cors>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Cors>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Cors>();
+    }
+}
@@ -6,21 +6,17 @@
 use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 
-use async_trait::async_trait;
 use reqwest::header::{HeaderMap, HeaderValue};
-use tokio::task::JoinSet;
 
-use crate::environment::Environment;
-use crate::http_client::custom_http_client;
-use crate::test::{Test, TestGroup, TestGroupResult, TestResult};
+use crate::cdn::Endpoint;
+use crate::http_client::{custom_http_client, send};
+use crate::test::{Metadata, Tag, Test, TestGroup, TestResult};
 
-pub use self::cloudfront::CloudFront;
 pub use self::config::Config;
-pub use self::fastly::Fastly;
+pub use self::cors::Cors;
 
-mod cloudfront;
 mod config;
-mod fastly;
+mod cors;
 
 /// The name of the test group
 const NAME: &str = "rust-lang/crates.io#6164 - CORS headers";
@@ -34,13 +30,17 @@ const NAME: &str = "rust-lang/crates.io#6164 - CORS headers";
 pub struct Crates6164 {
     /// Configuration for the test group
     config: Arc<Config>,
+
+    /// The CDNs that serve crates.io
+    cdns: Vec<Endpoint>,
 }
 
 impl Crates6164 {
-    /// Create a new instance of the test group
-    pub fn new(env: Environment) -> Self {
+    /// Create a new instance of the test group that checks the given CDNs
+    pub fn new(config: Config, cdns: Vec<Endpoint>) -> Self {
         Self {
-            config: Arc::new(Config::for_env(env)),
+            config: Arc::new(config),
+            cdns,
         }
     }
 }
@@ -51,25 +51,35 @@ impl Display for Crates6164 {
     }
 }
 
-#[async_trait]
 impl TestGroup for Crates6164 {
-    async fn run(&self) -> TestGroupResult {
-        let tests: Vec<Box<dyn Test>> = vec![
-            Box::new(CloudFront::new(self.config.clone())),
-            Box::new(Fastly::new(self.config.clone())),
-        ];
+    fn name(&self) -> &'static str {
+        NAME
+    }
 
-        let mut js = JoinSet::new();
-        for test in tests {
-            js.spawn(async move { test.run().await });
-        }
+    fn metadata(&self) -> Metadata {
+        Metadata::builder()
+            .description(
+                "The Fastly service for `static.crates.io` did not always set the \
+                `Access-Control-Allow-Origin` header. Crate downloads must allow requests from any \
+                origin.",
+            )
+            .team("infra")
+            .issue("https://github.com/rust-lang/crates.io/issues/6164")
+            .build()
+    }
 
-        let results = js.join_all().await;
+    fn tags(&self) -> Vec<Tag> {
+        vec![
+            Tag::issue("crates.io#6164"),
+            Tag::service("static.crates.io"),
+        ]
+    }
 
-        TestGroupResult::builder()
-            .name(NAME)
-            .results(results)
-            .build()
+    fn tests(&self) -> Vec<Box<dyn Test>> {
+        self.cdns
+            .iter()
+            .map(|cdn| Box::new(Cors::new(cdn.clone(), self.config.clone())) as Box<dyn Test>)
+            .collect()
     }
 }
 
@@ -77,21 +87,20 @@ impl TestGroup for Crates6164 {
 ///
 /// This function sends a GET request to the given URL and expects the response to have the
 /// `Access-Control-Allow-Origin` header set.
-async fn request_url_and_expect_cors_header(name: &'static str, url: &str) -> TestResult {
+async fn request_url_and_expect_cors_header(name: &str, url: &str) -> TestResult {
     let mut headers = HeaderMap::new();
     headers.insert(
         "Origin",
         HeaderValue::from_str("https://example.com").expect("failed to parse header value"),
     );
 
-    let response = match custom_http_client()
+    let request = custom_http_client()
         .default_headers(headers)
         .build()
         .expect("failed to build reqwest client")
-        .get(url)
-        .send()
-        .await
-    {
+        .get(url);
+
+    let response = match send(request).await {
         Ok(response) => response,
         Err(error) => {
             return TestResult::builder()
@@ -129,22 +138,43 @@ mod tests {
 
     use super::*;
 
-    pub async fn setup(krate: &'static str, version: &'static str) -> (ServerGuard, Config) {
+    pub async fn setup(
+        krate: &'static str,
+        version: &'static str,
+    ) -> (ServerGuard, Endpoint, Config) {
         let server = mockito::Server::new_async().await;
 
+        let endpoint = Endpoint::new("Fastly", Tag::cdn("fastly"), server.url());
+
         let config = Config::builder()
             .krate(krate.into())
             .version(version.into())
-            .cloudfront_url(server.url())
-            .fastly_url(server.url())
             .build();
 
-        (server, config)
+        (server, endpoint, config)
+    }
+
+    #[test]
+    fn tests_for_every_cdn() {
+        let cdns = vec![
+            Endpoint::new("CloudFront", Tag::cdn("cloudfront"), "https://cloudfront"),
+            Endpoint::new("Fastly", Tag::cdn("fastly"), "https://fastly"),
+        ];
+
+        let crates_6164 = Crates6164::new(Config::default(), cdns);
+
+        let names: Vec<String> = crates_6164
+            .tests()
+            .iter()
+            .map(|test| test.name().to_string())
+            .collect();
+
+        assert_eq!(vec!["CloudFront", "Fastly"], names);
     }
 
     #[test]
     fn trait_display() {
-        let crates_6164 = Crates6164::new(Environment::Staging);
+        let crates_6164 = Crates6164::new(Config::default(), Vec::new());
 
         assert_eq!(
             "rust-lang/crates.io#6164 - CORS headers",
@@ -4,8 +4,8 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 
-use crate::http_client::custom_http_client;
-use crate::test::{Test, TestResult};
+use crate::http_client::{custom_http_client, send};
+use crate::test::{Metadata, Test, TestResult};
 
 use super::config::Config;
 
@@ -27,14 +27,26 @@ impl ApiHealth {
 
 #[async_trait]
 impl Test for ApiHealth {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn metadata(&self) -> Metadata {
+        Metadata::builder()
+            .description(
+                "Request the summary endpoint of the crates.io API and expect a \
+                successful response.",
+            )
+            .build()
+    }
+
     async fn run(&self) -> TestResult {
-        let response = match custom_http_client()
+        let request = custom_http_client()
             .build()
             .expect("failed to build reqwest client")
-            .get(self.config.api_url())
-            .send()
-            .await
-        {
+            .get(self.config.api_url());
+
+        let response = match send(request).await {
             Ok(response) => response,
             Err(error) => {
                 return TestResult::builder()
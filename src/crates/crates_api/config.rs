@@ -1,33 +1,26 @@
 //! Configuration for crates.io API tests
 
 use getset::Getters;
+use serde::Deserialize;
 #[cfg(test)]
 use typed_builder::TypedBuilder;
 
-use crate::environment::Environment;
+use crate::config::{Validate, Validator};
 
 /// Configuration for crates.io API tests
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters)]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters, Deserialize)]
+#[serde(deny_unknown_fields)]
 #[cfg_attr(test, derive(TypedBuilder))]
 pub struct Config {
     /// The URL for the crates.io API
+    #[serde(deserialize_with = "crate::config::deserialize_url")]
     #[getset(get = "pub")]
     api_url: String,
 }
 
-impl Config {
-    /// Return the configuration for the given environment
-    pub fn for_env(env: Environment) -> Self {
-        // Use the summary endpoint as a simple health check
-        const PATH: &str = "/api/v1/summary";
-        match env {
-            Environment::Staging => Self {
-                api_url: format!("https://staging.crates.io{PATH}"),
-            },
-            Environment::Production => Self {
-                api_url: format!("https://crates.io{PATH}"),
-            },
-        }
+impl Validate for Config {
+    fn validate(&self, validator: &mut Validator) {
+        validator.url("api_url", &self.api_url);
     }
 }
 
@@ -1,33 +1,26 @@
 //! Configuration for index domain tests
 
 use getset::Getters;
+use serde::Deserialize;
 #[cfg(test)]
 use typed_builder::TypedBuilder;
 
-use crate::environment::Environment;
+use crate::config::{Validate, Validator};
 
 /// Configuration for index domain tests
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters)]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters, Deserialize)]
+#[serde(deny_unknown_fields)]
 #[cfg_attr(test, derive(TypedBuilder))]
 pub struct Config {
     /// The URL for the index.crates.io domain
+    #[serde(deserialize_with = "crate::config::deserialize_url")]
     #[getset(get = "pub")]
     index_url: String,
 }
 
-impl Config {
-    /// Return the configuration for the given environment
-    pub fn for_env(env: Environment) -> Self {
-        // Path to request a crate that exists in both staging and production.
-        const URL: &str = "/an/si/ansi_term";
-        match env {
-            Environment::Staging => Self {
-                index_url: format!("https://index.staging.crates.io{URL}"),
-            },
-            Environment::Production => Self {
-                index_url: format!("https://index.crates.io{URL}"),
-            },
-        }
+impl Validate for Config {
+    fn validate(&self, validator: &mut Validator) {
+        validator.url("index_url", &self.index_url);
     }
 }
 
@@ -4,8 +4,8 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 
-use crate::http_client::custom_http_client;
-use crate::test::{Test, TestResult};
+use crate::http_client::{custom_http_client, send};
+use crate::test::{Metadata, Test, TestResult};
 
 use super::config::Config;
 
@@ -27,14 +27,23 @@ impl IndexCratesIo {
 
 #[async_trait]
 impl Test for IndexCratesIo {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn metadata(&self) -> Metadata {
+        Metadata::builder()
+            .description("Request a crate from the sparse index and expect a successful response.")
+            .build()
+    }
+
     async fn run(&self) -> TestResult {
-        let response = match custom_http_client()
+        let request = custom_http_client()
             .build()
             .expect("failed to build reqwest client")
-            .head(self.config.index_url())
-            .send()
-            .await
-        {
+            .head(self.config.index_url());
+
+        let response = match send(request).await {
             Ok(response) => response,
             Err(error) => {
                 return TestResult::builder()
@@ -3,11 +3,7 @@
 use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 
-use async_trait::async_trait;
-use tokio::task::JoinSet;
-
-use crate::environment::Environment;
-use crate::test::{Test, TestGroup, TestGroupResult};
+use crate::test::{Metadata, Tag, Test, TestGroup};
 
 pub use self::config::Config;
 pub use self::index_crates_io::IndexCratesIo;
@@ -27,9 +23,9 @@ pub struct CratesIndex {
 
 impl CratesIndex {
     /// Create a new instance of the test group
-    pub fn new(env: Environment) -> Self {
+    pub fn new(config: Config) -> Self {
         Self {
-            config: Arc::new(Config::for_env(env)),
+            config: Arc::new(config),
         }
     }
 }
@@ -40,22 +36,24 @@ impl Display for CratesIndex {
     }
 }
 
-#[async_trait]
 impl TestGroup for CratesIndex {
-    async fn run(&self) -> TestGroupResult {
-        let tests: Vec<Box<dyn Test>> = vec![Box::new(IndexCratesIo::new(self.config.clone()))];
+    fn name(&self) -> &'static str {
+        GROUP_NAME
+    }
 
-        let mut js = JoinSet::new();
-        for test in tests {
-            js.spawn(async move { test.run().await });
-        }
+    fn metadata(&self) -> Metadata {
+        Metadata::builder()
+            .description("The sparse index at `index.crates.io` must be accessible.")
+            .team("infra")
+            .build()
+    }
 
-        let results = js.join_all().await;
+    fn tags(&self) -> Vec<Tag> {
+        vec![Tag::service("index.crates.io")]
+    }
 
-        TestGroupResult::builder()
-            .name(GROUP_NAME)
-            .results(results)
-            .build()
+    fn tests(&self) -> Vec<Box<dyn Test>> {
+        vec![Box::new(IndexCratesIo::new(self.config.clone()))]
     }
 }
 
@@ -69,7 +67,7 @@ mod tests {
 
     #[test]
     fn trait_display() {
-        let crates_index = CratesIndex::new(Environment::Staging);
+        let crates_index = CratesIndex::new(Config::default());
 
         assert_eq!("Index domains", crates_index.to_string());
     }
@@ -5,8 +5,8 @@ use std::sync::Arc;
 use async_trait::async_trait;
 
 use crate::crates::db_dump::ARTIFACTS;
-use crate::http_client::custom_http_client;
-use crate::test::{Test, TestResult};
+use crate::http_client::{custom_http_client, send};
+use crate::test::{Metadata, Tag, Test, TestResult};
 
 use super::config::Config;
 
@@ -29,13 +29,12 @@ impl CloudFront {
 
     /// Request the given path and expect a successful response
     async fn request_path_and_expect_success(&self, path: &str) -> TestResult {
-        let response = match custom_http_client()
+        let request = custom_http_client()
             .build()
             .expect("failed to build reqwest client")
-            .head(format!("{}/{}", self.config.cloudfront_url(), path))
-            .send()
-            .await
-        {
+            .head(format!("{}/{}", self.config.cloudfront_url(), path));
+
+        let response = match send(request).await {
             Ok(response) => response,
             Err(error) => {
                 return TestResult::builder()
@@ -63,6 +62,23 @@ impl CloudFront {
 
 #[async_trait]
 impl Test for CloudFront {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn metadata(&self) -> Metadata {
+        Metadata::builder()
+            .description(
+                "Request the database dumps from CloudFront and expect a successful \
+                response.",
+            )
+            .build()
+    }
+
+    fn tags(&self) -> Vec<Tag> {
+        vec![Tag::cdn("cloudfront")]
+    }
+
     async fn run(&self) -> TestResult {
         let mut results = Vec::with_capacity(ARTIFACTS.len());
 
@@ -1,40 +1,35 @@
 //! Configuration to test the database dump
 
 use getset::Getters;
+use serde::Deserialize;
 #[cfg(test)]
 use typed_builder::TypedBuilder;
 
-use crate::environment::Environment;
+use crate::config::{Validate, Validator};
 
 /// Configuration to test the database dump
 ///
 /// The smoke tests request the database dump from Fastly and CloudFront and check for the correct
 /// response.
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters)]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters, Deserialize)]
+#[serde(deny_unknown_fields)]
 #[cfg_attr(test, derive(TypedBuilder))]
 pub struct Config {
     /// The URL for the CloudFront CDN
+    #[serde(deserialize_with = "crate::config::deserialize_url")]
     #[getset(get = "pub")]
     cloudfront_url: String,
 
     /// The URL for the Fastly CDN
+    #[serde(deserialize_with = "crate::config::deserialize_url")]
     #[getset(get = "pub")]
     fastly_url: String,
 }
 
-impl Config {
-    /// Return the configuration for the given environment
-    pub fn for_env(env: Environment) -> Self {
-        match env {
-            Environment::Staging => Self {
-                cloudfront_url: "https://cloudfront-static.staging.crates.io".into(),
-                fastly_url: "https://fastly-static.staging.crates.io".into(),
-            },
-            Environment::Production => Self {
-                cloudfront_url: "https://cloudfront-static.crates.io".into(),
-                fastly_url: "https://fastly-static.crates.io".into(),
-            },
-        }
+impl Validate for Config {
+    fn validate(&self, validator: &mut Validator) {
+        validator.url("cloudfront_url", &self.cloudfront_url);
+        validator.url("fastly_url", &self.fastly_url);
     }
 }
 
@@ -7,8 +7,8 @@ use reqwest::redirect::Policy;
 
 use crate::assertion::{is_redirect, redirects_to};
 use crate::crates::db_dump::ARTIFACTS;
-use crate::http_client::custom_http_client;
-use crate::test::{Test, TestResult};
+use crate::http_client::{custom_http_client, send};
+use crate::test::{Metadata, Tag, Test, TestResult};
 
 use super::config::Config;
 
@@ -31,15 +31,14 @@ impl Fastly {
 
     /// Request the given path and expect a redirect to CloudFront
     async fn request_and_expect_redirect(&self, path: &str) -> TestResult {
-        let response = match custom_http_client()
+        let request = custom_http_client()
             // Don't follow the redirect, we want to check the redirect location
             .redirect(Policy::none())
             .build()
             .expect("failed to build reqwest client")
-            .head(format!("{}/{}", self.config.fastly_url(), path))
-            .send()
-            .await
-        {
+            .head(format!("{}/{}", self.config.fastly_url(), path));
+
+        let response = match send(request).await {
             Ok(response) => response,
             Err(error) => {
                 return TestResult::builder()
@@ -70,6 +69,23 @@ impl Fastly {
 
 #[async_trait]
 impl Test for Fastly {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn metadata(&self) -> Metadata {
+        Metadata::builder()
+            .description(
+                "Request the database dumps from Fastly and expect a redirect to \
+                CloudFront.",
+            )
+            .build()
+    }
+
+    fn tags(&self) -> Vec<Tag> {
+        vec![Tag::cdn("fastly")]
+    }
+
     async fn run(&self) -> TestResult {
         let mut results = Vec::with_capacity(ARTIFACTS.len());
 
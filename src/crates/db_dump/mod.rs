@@ -6,11 +6,7 @@
 use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 
-use async_trait::async_trait;
-use tokio::task::JoinSet;
-
-use crate::environment::Environment;
-use crate::test::{Test, TestGroup, TestGroupResult};
+use crate::test::{Metadata, Tag, Test, TestGroup};
 
 pub use self::cloudfront::CloudFront;
 pub use self::config::Config;
@@ -38,9 +34,9 @@ pub struct DbDump {
 
 impl DbDump {
     /// Create a new instance of the test group
-    pub fn new(env: Environment) -> Self {
+    pub fn new(config: Config) -> Self {
         Self {
-            config: Arc::new(Config::for_env(env)),
+            config: Arc::new(config),
         }
     }
 }
@@ -51,25 +47,30 @@ impl Display for DbDump {
     }
 }
 
-#[async_trait]
 impl TestGroup for DbDump {
-    async fn run(&self) -> TestGroupResult {
-        let tests: Vec<Box<dyn Test>> = vec![
-            Box::new(CloudFront::new(self.config.clone())),
-            Box::new(Fastly::new(self.config.clone())),
-        ];
+    fn name(&self) -> &'static str {
+        NAME
+    }
 
-        let mut js = JoinSet::new();
-        for test in tests {
-            js.spawn(async move { test.run().await });
-        }
+    fn metadata(&self) -> Metadata {
+        Metadata::builder()
+            .description(
+                "The database dumps are too large to be served by Fastly's Compute \
+                platform. Fastly must redirect them to CloudFront, which serves them.",
+            )
+            .team("infra")
+            .build()
+    }
 
-        let results = js.join_all().await;
+    fn tags(&self) -> Vec<Tag> {
+        vec![Tag::service("static.crates.io")]
+    }
 
-        TestGroupResult::builder()
-            .name(NAME)
-            .results(results)
-            .build()
+    fn tests(&self) -> Vec<Box<dyn Test>> {
+        vec![
+            Box::new(CloudFront::new(self.config.clone())),
+            Box::new(Fastly::new(self.config.clone())),
+        ]
     }
 }
 
@@ -83,7 +84,7 @@ mod tests {
 
     #[test]
     fn trait_display() {
-        let db_dump = DbDump::new(Environment::Staging);
+        let db_dump = DbDump::new(Config::default());
 
         assert_eq!("Database dumps", db_dump.to_string());
     }
@@ -2,11 +2,8 @@
 
 use std::fmt::{Display, Formatter};
 
-use async_trait::async_trait;
-use tokio::task::JoinSet;
-
-use crate::environment::Environment;
-use crate::test::{TestGroup, TestSuite, TestSuiteResult};
+use crate::cdn::Endpoint;
+use crate::test::{TestGroup, TestSuite};
 
 use self::crates_4891::Crates4891;
 use self::crates_6164::Crates6164;
@@ -14,6 +11,9 @@ use self::crates_api::CratesApi;
 use self::crates_index::CratesIndex;
 use self::db_dump::DbDump;
 
+pub use self::config::Config;
+
+mod config;
 mod crates_4891;
 mod crates_6164;
 mod crates_api;
@@ -26,16 +26,19 @@ mod utils;
 /// This test suite implements the smoke tests for crates.io, mostly importantly its Content
 /// Delivery Network. The tests ensure that prior bugs in the configuration are not reintroduced,
 /// and that CloudFront and Fastly behave the same.
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
 pub struct Crates {
-    /// The environment to run the tests in
-    env: Environment,
+    /// The configuration for the test suite
+    config: Config,
+
+    /// The CDNs that serve crates.io
+    cdns: Vec<Endpoint>,
 }
 
 impl Crates {
-    /// Creates a new instance of the test suite
-    pub fn new(env: Environment) -> Self {
-        Self { env }
+    /// Creates a new instance of the test suite that checks the given CDNs
+    pub fn new(config: Config, cdns: Vec<Endpoint>) -> Self {
+        Self { config, cdns }
     }
 }
 
@@ -45,28 +48,25 @@ impl Display for Crates {
     }
 }
 
-#[async_trait]
 impl TestSuite for Crates {
-    async fn run(&self) -> TestSuiteResult {
-        let groups: Vec<Box<dyn TestGroup>> = vec![
-            Box::new(Crates4891::new(self.env)),
-            Box::new(Crates6164::new(self.env)),
-            Box::new(CratesApi::new(self.env)),
-            Box::new(CratesIndex::new(self.env)),
-            Box::new(DbDump::new(self.env)),
-        ];
-
-        let mut js = JoinSet::new();
-        for group in groups {
-            js.spawn(async move { group.run().await });
-        }
-
-        let results = js.join_all().await;
-
-        TestSuiteResult::builder()
-            .name("crates.io")
-            .results(results)
-            .build()
+    fn name(&self) -> &'static str {
+        "crates.io"
+    }
+
+    fn groups(&self) -> Vec<Box<dyn TestGroup>> {
+        vec![
+            Box::new(Crates4891::new(
+                self.config.crates_4891().clone(),
+                self.cdns.clone(),
+            )),
+            Box::new(Crates6164::new(
+                self.config.crates_6164().clone(),
+                self.cdns.clone(),
+            )),
+            Box::new(CratesApi::new(self.config.crates_api().clone())),
+            Box::new(CratesIndex::new(self.config.crates_index().clone())),
+            Box::new(DbDump::new(self.config.db_dump().clone())),
+        ]
     }
 }
 
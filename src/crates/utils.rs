@@ -7,7 +7,7 @@
 ///
 /// # Example
 ///
-/// ```rust
+/// ```rust,ignore
 /// use crates::utils::crate_url;
 ///
 /// let base_url = "https://example.com";
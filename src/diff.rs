@@ -0,0 +1,323 @@
+//! Compare two saved runs
+//!
+//! Before and after a change to the configuration of a CDN, the results of the smoke tests can be
+//! saved with `--format json`. The diff of the two runs lists the tests that started or stopped
+//! failing, the tests that were added or removed, and the tests that still fail but with a
+//! different message. Failures that the baseline expected are not counted as failing.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use getset::Getters;
+
+use crate::test::{Outcome, SavedRun, SavedTest, TestPath};
+
+/// The differences between two saved runs
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters)]
+pub struct Diff {
+    /// The tests that passed in the old run and fail in the new run
+    #[getset(get = "pub")]
+    newly_failing: Vec<SavedTest>,
+
+    /// The tests that failed in the old run and pass in the new run
+    #[getset(get = "pub")]
+    newly_passing: Vec<SavedTest>,
+
+    /// The tests that only exist in the new run
+    #[getset(get = "pub")]
+    added: Vec<SavedTest>,
+
+    /// The tests that only exist in the old run
+    #[getset(get = "pub")]
+    removed: Vec<SavedTest>,
+
+    /// The tests that fail in both runs but with a different message, as pairs of old and new
+    #[getset(get = "pub")]
+    changed_messages: Vec<(SavedTest, SavedTest)>,
+}
+
+impl Diff {
+    /// Compare the old run with the new run
+    ///
+    /// The differences are sorted by the path of the test.
+    pub fn new(old: &SavedRun, new: &SavedRun) -> Self {
+        let mut tests: BTreeMap<&TestPath, (Option<&SavedTest>, Option<&SavedTest>)> =
+            BTreeMap::new();
+
+        for test in old.tests() {
+            tests.entry(test.path()).or_default().0 = Some(test);
+        }
+
+        for test in new.tests() {
+            tests.entry(test.path()).or_default().1 = Some(test);
+        }
+
+        let mut diff = Self::default();
+
+        for (old, new) in tests.into_values() {
+            match (old, new) {
+                (Some(old), None) => diff.removed.push(old.clone()),
+                (None, Some(new)) => diff.added.push(new.clone()),
+                (Some(old), Some(new)) => match (is_failure(old), is_failure(new)) {
+                    (false, true) => diff.newly_failing.push(new.clone()),
+                    (true, false) => diff.newly_passing.push(new.clone()),
+                    (true, true) if old.message() != new.message() => {
+                        diff.changed_messages.push((old.clone(), new.clone()))
+                    }
+                    _ => {}
+                },
+                (None, None) => {}
+            }
+        }
+
+        diff
+    }
+
+    /// Compare the old run with the new run, if both tested the same environment
+    ///
+    /// Runs of different environments differ in their fixtures, so their differences would not
+    /// point to a regression.
+    pub fn between(old: &SavedRun, new: &SavedRun) -> Result<Self, String> {
+        if old.environment() != new.environment() {
+            return Err(format!(
+                "cannot compare a run of '{}' with a run of '{}'",
+                old.environment(),
+                new.environment()
+            ));
+        }
+
+        Ok(Self::new(old, new))
+    }
+
+    /// Return the number of tests that differ between the runs
+    pub fn len(&self) -> usize {
+        self.newly_failing.len()
+            + self.newly_passing.len()
+            + self.added.len()
+            + self.removed.len()
+            + self.changed_messages.len()
+    }
+
+    /// Check if the runs have the same results
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Check if the new run has regressed, i.e. if a test started failing
+    pub fn has_regressions(&self) -> bool {
+        !self.newly_failing.is_empty() || self.added.iter().any(is_failure)
+    }
+}
+
+impl Display for Diff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write_section(f, "Newly failing", &self.newly_failing)?;
+        write_section(f, "Newly passing", &self.newly_passing)?;
+        write_section(f, "Added", &self.added)?;
+        write_section(f, "Removed", &self.removed)?;
+
+        if !self.changed_messages.is_empty() {
+            writeln!(f, "Changed failure messages:")?;
+
+            for (old, new) in &self.changed_messages {
+                writeln!(f, "  {}", old.path())?;
+                writeln!(f, "    old: {}", describe_message(old))?;
+                writeln!(f, "    new: {}", describe_message(new))?;
+            }
+
+            writeln!(f)?;
+        }
+
+        if self.is_empty() {
+            writeln!(f, "No differences between the runs")
+        } else {
+            writeln!(f, "Found {} difference(s) between the runs", self.len())
+        }
+    }
+}
+
+/// Write a section with a list of tests, or nothing if the list is empty
+fn write_section(f: &mut Formatter<'_>, title: &str, tests: &[SavedTest]) -> std::fmt::Result {
+    if tests.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(f, "{title}:")?;
+
+    for test in tests {
+        let result = if test.success() { "passed" } else { "failed" };
+
+        match test.message() {
+            Some(message) => writeln!(f, "  {} ({result}: {message})", test.path())?,
+            None => writeln!(f, "  {} ({result})", test.path())?,
+        }
+    }
+
+    writeln!(f)
+}
+
+/// Check if a test failed unexpectedly, i.e. without an entry in the baseline
+fn is_failure(test: &SavedTest) -> bool {
+    test.outcome() == Outcome::Failed
+}
+
+/// Describe the message of a test
+fn describe_message(test: &SavedTest) -> String {
+    match test.message() {
+        Some(message) => format!("'{message}'"),
+        None => "none".into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::environment::Environment;
+    use crate::test::{TestGroupResult, TestResult, TestSuiteResult};
+    use crate::test_utils::*;
+
+    use super::*;
+
+    fn run(tests: &[(&str, bool, Option<&str>)]) -> SavedRun {
+        run_with_baseline(tests, &[])
+    }
+
+    fn run_with_baseline(tests: &[(&str, bool, Option<&str>)], baseline: &[&str]) -> SavedRun {
+        let results = tests
+            .iter()
+            .map(|(name, success, message)| {
+                TestResult::builder()
+                    .name(*name)
+                    .success(*success)
+                    .message(message.map(String::from))
+                    .expected_failure(baseline.contains(name).then(|| "known failure".to_string()))
+                    .build()
+            })
+            .collect();
+
+        let results = vec![TestSuiteResult::builder()
+            .name("crates.io")
+            .results(vec![TestGroupResult::builder()
+                .name("Database dumps")
+                .results(results)
+                .build()])
+            .build()];
+
+        let time = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+
+        SavedRun::new(&Environment::Staging, &results, time)
+    }
+
+    #[test]
+    fn new_without_differences() {
+        let old = run(&[("Fastly", true, None)]);
+
+        let diff = Diff::new(&old, &old);
+
+        assert!(diff.is_empty());
+        assert_eq!("No differences between the runs\n", diff.to_string());
+    }
+
+    #[test]
+    fn new_with_differences() {
+        let old = run(&[
+            ("CloudFront", true, None),
+            ("Fastly", false, Some("HTTP 404")),
+            ("Origin", false, Some("HTTP 403")),
+            ("S3", true, None),
+        ]);
+        let new = run(&[
+            ("CloudFront", false, Some("HTTP 500")),
+            ("Edgio", true, None),
+            ("Fastly", true, None),
+            ("Origin", false, Some("HTTP 404")),
+        ]);
+
+        let diff = Diff::new(&old, &new);
+
+        let expected = indoc! {"
+            Newly failing:
+              crates.io > Database dumps > CloudFront (failed: HTTP 500)
+
+            Newly passing:
+              crates.io > Database dumps > Fastly (passed)
+
+            Added:
+              crates.io > Database dumps > Edgio (passed)
+
+            Removed:
+              crates.io > Database dumps > S3 (passed)
+
+            Changed failure messages:
+              crates.io > Database dumps > Origin
+                old: 'HTTP 403'
+                new: 'HTTP 404'
+
+            Found 5 difference(s) between the runs
+        "};
+
+        assert_eq!(expected, diff.to_string());
+        assert!(diff.has_regressions());
+    }
+
+    #[test]
+    fn has_regressions_with_added_failure() {
+        let old = run(&[]);
+        let new = run(&[("Fastly", false, None)]);
+
+        assert!(Diff::new(&old, &new).has_regressions());
+        assert!(!Diff::new(&new, &old).has_regressions());
+    }
+
+    #[test]
+    fn has_regressions_without_expected_failures() {
+        let old = run(&[("Fastly", true, None), ("S3", true, None)]);
+        let new = run_with_baseline(
+            &[
+                ("Edgio", false, Some("HTTP 404")),
+                ("Fastly", false, Some("HTTP 404")),
+                ("S3", true, None),
+            ],
+            &["Edgio", "Fastly"],
+        );
+
+        let diff = Diff::new(&old, &new);
+
+        assert!(diff.newly_failing().is_empty());
+        assert!(!diff.has_regressions());
+    }
+
+    #[test]
+    fn between_runs_of_different_environments() {
+        let old = run(&[("Fastly", true, None)]);
+        let new = SavedRun::new(
+            &Environment::Production,
+            &[],
+            Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap(),
+        );
+
+        assert_eq!(
+            Err("cannot compare a run of 'staging' with a run of 'production'".to_string()),
+            Diff::between(&old, &new)
+        );
+        assert!(Diff::between(&old, &old).is_ok());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Diff>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Diff>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Diff>();
+    }
+}
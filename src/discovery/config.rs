@@ -0,0 +1,63 @@
+//! Configuration for the discovery of fixtures
+
+use getset::{CopyGetters, Getters};
+use serde::Deserialize;
+#[cfg(test)]
+use typed_builder::TypedBuilder;
+
+use crate::config::{Validate, Validator};
+
+/// Configuration for the discovery of fixtures
+///
+/// The discovery requests the channel manifest to find the latest release, and the sparse index to
+/// find the latest versions of the fixture crates. It can be disabled to always use the static
+/// fixtures from the configuration.
+#[derive(
+    Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, CopyGetters, Getters, Deserialize,
+)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(test, derive(TypedBuilder))]
+pub struct Config {
+    /// Whether fixtures are discovered at runtime
+    #[getset(get_copy = "pub")]
+    enabled: bool,
+
+    /// The URL of the channel manifest, e.g. `https://static.rust-lang.org/dist/channel-rust-stable.toml`
+    #[serde(deserialize_with = "crate::config::deserialize_url")]
+    #[getset(get = "pub")]
+    channel_manifest_url: String,
+
+    /// The URL of the sparse index, e.g. `https://index.crates.io`
+    #[serde(deserialize_with = "crate::config::deserialize_url")]
+    #[getset(get = "pub")]
+    index_url: String,
+}
+
+impl Validate for Config {
+    fn validate(&self, validator: &mut Validator) {
+        validator.url("channel_manifest_url", &self.channel_manifest_url);
+        validator.url("index_url", &self.index_url);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::*;
+
+    use super::*;
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Config>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Config>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Config>();
+    }
+}
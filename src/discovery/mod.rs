@@ -0,0 +1,436 @@
+//! Discovery of fixtures at runtime
+//!
+//! Some tests need fixtures that eventually disappear, for example a release that is old enough to
+//! be removed from the CDN, or a crate version that gets yanked. Instead of only relying on the
+//! static fixtures in the configuration, the discovery picks fresh fixtures before the tests run:
+//!
+//! - The latest release date from the channel manifest
+//! - The latest version of the crates_4891 fixture crate with `+` build metadata
+//! - The latest stable version of the crates_6164 fixture crate
+//!
+//! The discovered fixtures are turned into [`Override`]s. When the discovery of a fixture fails, the
+//! static value from the configuration is used instead.
+//!
+//! The static fixtures are still checked by the health suite, so [`load`] keeps the configuration
+//! as it was before the discovered fixtures were applied.
+
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+use chrono::NaiveDate;
+use getset::{CopyGetters, Getters};
+use reqwest::Client;
+use semver::Version;
+
+use crate::config::{ConfigError, Override};
+use crate::environment::Environment;
+use crate::http_client::{custom_http_client, send};
+use crate::sparse_index;
+
+pub use self::config::Config;
+
+mod config;
+
+/// The key of the release date for the list-files.html tests
+const RELEASE_KEY: &str = "releases.list_files.release";
+
+/// The key of the crate version for the rust-lang/crates.io#4891 tests
+const CRATES_4891_VERSION_KEY: &str = "crates.crates_4891.version";
+
+/// The key of the crate version for the rust-lang/crates.io#6164 tests
+const CRATES_6164_VERSION_KEY: &str = "crates.crates_6164.version";
+
+/// A fixture that was discovered at runtime
+///
+/// The fixture contains the configuration key that it replaces, and either the discovered value or
+/// the reason why the discovery failed.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, Getters)]
+pub struct Fixture {
+    /// The dotted path of the configuration key, e.g. `releases.list_files.release`
+    #[getset(get_copy = "pub")]
+    key: &'static str,
+
+    /// The discovered value, or the reason why the discovery failed
+    #[getset(get = "pub")]
+    result: Result<String, String>,
+}
+
+/// A configuration with the discovered fixtures applied
+///
+/// The configuration as it was loaded is kept next to it, so that the static fixtures can still be
+/// checked while the tests run against the discovered ones.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Getters)]
+pub struct Discovered {
+    /// The configuration with the static fixtures
+    #[getset(get = "pub")]
+    static_config: crate::config::Config,
+
+    /// The configuration with the discovered fixtures
+    #[getset(get = "pub")]
+    config: crate::config::Config,
+
+    /// The fixtures that were discovered
+    #[getset(get = "pub")]
+    fixtures: Vec<Fixture>,
+}
+
+impl Discovered {
+    /// Wrap a configuration for which no fixtures were discovered
+    pub fn without_discovery(config: crate::config::Config) -> Self {
+        Self {
+            static_config: config.clone(),
+            config,
+            fixtures: Vec::new(),
+        }
+    }
+}
+
+impl Fixture {
+    /// Create a new fixture
+    pub fn new(key: &'static str, result: Result<String, String>) -> Self {
+        Self { key, result }
+    }
+
+    /// Return an override for the discovered value, or `None` if the discovery failed
+    pub fn to_override(&self) -> Option<Override> {
+        self.result
+            .as_ref()
+            .ok()
+            .map(|value| Override::new(self.key, value))
+    }
+}
+
+impl Display for Fixture {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.result {
+            Ok(value) => write!(f, "Discovered {} = {value}", self.key),
+            Err(error) => write!(
+                f,
+                "Failed to discover {}, using the static value: {error}",
+                self.key
+            ),
+        }
+    }
+}
+
+/// Discover fresh fixtures for the given configuration
+///
+/// Returns an empty list if the discovery is disabled.
+pub async fn discover(config: &crate::config::Config) -> Vec<Fixture> {
+    let discovery = config.discovery();
+
+    if !discovery.enabled() {
+        return Vec::new();
+    }
+
+    let client = match custom_http_client().build() {
+        Ok(client) => client,
+        Err(error) => {
+            return [
+                RELEASE_KEY,
+                CRATES_4891_VERSION_KEY,
+                CRATES_6164_VERSION_KEY,
+            ]
+            .into_iter()
+            .map(|key| Fixture::new(key, Err(error.to_string())))
+            .collect();
+        }
+    };
+
+    let crates = config.crates();
+
+    let (release, crates_4891, crates_6164) = tokio::join!(
+        latest_release(&client, discovery.channel_manifest_url()),
+        latest_version(
+            &client,
+            discovery.index_url(),
+            crates.crates_4891().krate(),
+            |version| !version.build.is_empty(),
+        ),
+        latest_version(
+            &client,
+            discovery.index_url(),
+            crates.crates_6164().krate(),
+            |version| version.pre.is_empty(),
+        ),
+    );
+
+    vec![
+        Fixture::new(RELEASE_KEY, release),
+        Fixture::new(CRATES_4891_VERSION_KEY, crates_4891),
+        Fixture::new(CRATES_6164_VERSION_KEY, crates_6164),
+    ]
+}
+
+/// Load the configuration and apply the fixtures that were discovered for it
+///
+/// The discovered fixtures are applied before the given overrides, so that explicit overrides take
+/// precedence over discovered fixtures.
+pub async fn load(
+    env: &Environment,
+    path: Option<&Path>,
+    overrides: &[Override],
+) -> Result<Discovered, ConfigError> {
+    let static_config = crate::config::Config::load(env, path, overrides)?;
+    let fixtures = discover(&static_config).await;
+
+    let overrides: Vec<Override> = fixtures
+        .iter()
+        .filter_map(Fixture::to_override)
+        .chain(overrides.iter().cloned())
+        .collect();
+
+    let config = crate::config::Config::load(env, path, &overrides)?;
+
+    Ok(Discovered {
+        static_config,
+        config,
+        fixtures,
+    })
+}
+
+/// Return the date of the release in the channel manifest
+async fn latest_release(client: &Client, manifest_url: &str) -> Result<String, String> {
+    let manifest: toml::Table = fetch(client, manifest_url)
+        .await?
+        .parse()
+        .map_err(|error| format!("failed to parse the channel manifest: {error}"))?;
+
+    let date = manifest
+        .get("date")
+        .and_then(|date| date.as_str())
+        .ok_or("expected the channel manifest to contain a date")?;
+
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|error| format!("invalid date '{date}' in the channel manifest: {error}"))?;
+
+    Ok(date.into())
+}
+
+/// Return the latest version of the crate that is not yanked and matches the predicate
+async fn latest_version(
+    client: &Client,
+    index_url: &str,
+    krate: &str,
+    predicate: impl Fn(&Version) -> bool,
+) -> Result<String, String> {
+    sparse_index::entries(client, index_url, krate)
+        .await?
+        .iter()
+        .filter(|entry| !entry.yanked())
+        .filter_map(|entry| Version::parse(entry.vers()).ok())
+        .filter(|version| predicate(version))
+        .max()
+        .map(|version| version.to_string())
+        .ok_or_else(|| format!("no matching version of {krate} found in the index"))
+}
+
+/// Request the URL and return the body of the response
+async fn fetch(client: &Client, url: &str) -> Result<String, String> {
+    send(client.get(url))
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|error| error.to_string())?
+        .text()
+        .await
+        .map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::*;
+
+    use super::*;
+
+    fn overrides(server: &mockito::Server) -> [Override; 4] {
+        [
+            Override::new(
+                "discovery.channel_manifest_url",
+                format!("{}/dist/channel-rust-stable.toml", server.url()),
+            ),
+            Override::new("discovery.index_url", server.url()),
+            Override::new("crates.crates_4891.krate", "libgit2-sys"),
+            Override::new("crates.crates_6164.krate", "axum"),
+        ]
+    }
+
+    async fn config(server: &mockito::Server) -> crate::config::Config {
+        crate::config::Config::load(&Environment::Staging, None, &overrides(server)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn discover_fixtures() {
+        let mut server = mockito::Server::new_async().await;
+
+        let manifest = server
+            .mock("GET", "/dist/channel-rust-stable.toml")
+            .match_header("User-Agent", "rust-lang/infra-smoke-tests")
+            .with_body("manifest-version = \"2\"\ndate = \"2024-10-17\"\n")
+            .create_async()
+            .await;
+        let libgit2_sys = server
+            .mock("GET", "/li/bg/libgit2-sys")
+            .match_header("User-Agent", "rust-lang/infra-smoke-tests")
+            .with_body(indoc! {r#"
+                {"name":"libgit2-sys","vers":"0.12.25+1.3.0","yanked":false}
+                {"name":"libgit2-sys","vers":"0.17.0+1.8.1","yanked":false}
+                {"name":"libgit2-sys","vers":"0.18.0+1.9.0","yanked":true}
+                {"name":"libgit2-sys","vers":"0.19.0","yanked":false}
+            "#})
+            .create_async()
+            .await;
+        let axum = server
+            .mock("GET", "/ax/um/axum")
+            .with_body(indoc! {r#"
+                {"name":"axum","vers":"0.6.10","yanked":false}
+                {"name":"axum","vers":"0.7.5","yanked":false}
+                {"name":"axum","vers":"0.8.0-rc.1","yanked":false}
+            "#})
+            .create_async()
+            .await;
+
+        let fixtures = discover(&config(&server).await).await;
+
+        manifest.assert_async().await;
+        libgit2_sys.assert_async().await;
+        axum.assert_async().await;
+
+        assert_eq!(
+            vec![
+                Fixture::new(RELEASE_KEY, Ok("2024-10-17".into())),
+                Fixture::new(CRATES_4891_VERSION_KEY, Ok("0.17.0+1.8.1".into())),
+                Fixture::new(CRATES_6164_VERSION_KEY, Ok("0.7.5".into())),
+            ],
+            fixtures
+        );
+    }
+
+    #[tokio::test]
+    async fn discover_falls_back_on_failure() {
+        let mut server = mockito::Server::new_async().await;
+
+        server
+            .mock("GET", "/dist/channel-rust-stable.toml")
+            .with_status(404)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/li/bg/libgit2-sys")
+            .with_body("{\"name\":\"libgit2-sys\",\"vers\":\"0.19.0\",\"yanked\":false}\n")
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/ax/um/axum")
+            .with_body("not json")
+            .create_async()
+            .await;
+
+        let fixtures = discover(&config(&server).await).await;
+
+        assert!(fixtures.iter().all(|fixture| fixture.result().is_err()));
+        assert!(fixtures
+            .iter()
+            .all(|fixture| fixture.to_override().is_none()));
+        assert_eq!(
+            &Err("no matching version of libgit2-sys found in the index".into()),
+            fixtures[1].result()
+        );
+    }
+
+    #[tokio::test]
+    async fn discover_when_disabled() {
+        let config = crate::config::Config::load(
+            &Environment::Staging,
+            None,
+            &[Override::new("discovery.enabled", "false")],
+        )
+        .unwrap();
+
+        assert!(discover(&config).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn load_keeps_static_config() {
+        let mut server = mockito::Server::new_async().await;
+
+        server
+            .mock("GET", "/dist/channel-rust-stable.toml")
+            .with_status(404)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/li/bg/libgit2-sys")
+            .with_body("{\"name\":\"libgit2-sys\",\"vers\":\"0.17.0+1.8.1\",\"yanked\":false}\n")
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/ax/um/axum")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let discovered = load(&Environment::Staging, None, &overrides(&server))
+            .await
+            .unwrap();
+
+        assert_eq!(3, discovered.fixtures().len());
+        assert_eq!(
+            "0.17.0+1.8.1",
+            discovered.config().crates().crates_4891().version()
+        );
+        assert_eq!(
+            config(&server).await.crates().crates_4891().version(),
+            discovered.static_config().crates().crates_4891().version()
+        );
+        assert_eq!(
+            discovered.static_config().releases(),
+            discovered.config().releases()
+        );
+    }
+
+    #[test]
+    fn to_override() {
+        let fixture = Fixture::new(RELEASE_KEY, Ok("2024-10-17".into()));
+
+        assert_eq!(
+            Some(Override::new(RELEASE_KEY, "2024-10-17")),
+            fixture.to_override()
+        );
+    }
+
+    #[test]
+    fn trait_display() {
+        let discovered = Fixture::new(RELEASE_KEY, Ok("2024-10-17".into()));
+        let failed = Fixture::new(RELEASE_KEY, Err("404".into()));
+
+        assert_eq!(
+            "Discovered releases.list_files.release = 2024-10-17",
+            discovered.to_string()
+        );
+        assert_eq!(
+            "Failed to discover releases.list_files.release, using the static value: 404",
+            failed.to_string()
+        );
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Fixture>();
+        assert_send::<Discovered>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Fixture>();
+        assert_sync::<Discovered>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Fixture>();
+        assert_unpin::<Discovered>();
+    }
+}
@@ -1,13 +1,15 @@
 //! Environments that can be tested by the smoke tests
 
-use clap::ValueEnum;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 /// Environments that can be tested by the smoke tests
 ///
 /// This enum represents the environments that can be tested by the smoke tests. Each environment
-/// requires its own configuration and has its own set of expectations, and thus requires its own
-/// implementation.
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, ValueEnum)]
+/// requires its own configuration and has its own set of expectations. The configuration for
+/// staging and production is built into the binary, while custom environments, e.g. `local` or a
+/// new staging stack, are defined in a configuration file.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
 pub enum Environment {
     /// The staging environment
     #[default]
@@ -15,10 +17,52 @@ pub enum Environment {
 
     /// The production environment
     Production,
+
+    /// A custom environment that is defined in a configuration file
+    Custom(String),
+}
+
+impl Environment {
+    /// Return the name of the environment
+    pub fn name(&self) -> &str {
+        match self {
+            Environment::Staging => "staging",
+            Environment::Production => "production",
+            Environment::Custom(name) => name,
+        }
+    }
+}
+
+impl Display for Environment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Environment {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+        match name {
+            "staging" => Ok(Environment::Staging),
+            "production" => Ok(Environment::Production),
+            _ if valid => Ok(Environment::Custom(name.into())),
+            _ => Err(format!(
+                "expected an environment name with only letters, digits, '-', and '_', got '{name}'"
+            )),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use pretty_assertions::assert_eq;
+
     use crate::test_utils::*;
 
     use super::*;
@@ -28,6 +72,29 @@ mod tests {
         assert_eq!(Environment::Staging, Environment::default());
     }
 
+    #[test]
+    fn trait_display() {
+        assert_eq!("production", Environment::Production.to_string());
+        assert_eq!("local", Environment::Custom("local".into()).to_string());
+    }
+
+    #[test]
+    fn trait_from_str() {
+        assert_eq!(Ok(Environment::Staging), "staging".parse());
+        assert_eq!(Ok(Environment::Production), "production".parse());
+        assert_eq!(
+            Ok(Environment::Custom("new-staging".into())),
+            "new-staging".parse()
+        );
+    }
+
+    #[test]
+    fn trait_from_str_with_invalid_name() {
+        assert!("".parse::<Environment>().is_err());
+        assert!("my env".parse::<Environment>().is_err());
+        assert!("a.b".parse::<Environment>().is_err());
+    }
+
     #[test]
     fn trait_send() {
         assert_send::<Environment>();
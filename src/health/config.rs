@@ -0,0 +1,48 @@
+//! Configuration for the configuration health checks
+
+use getset::CopyGetters;
+use serde::Deserialize;
+
+use crate::config::{Validate, Validator};
+
+/// Configuration for the configuration health checks
+///
+/// The health checks flag fixtures that have gone stale. The configuration is read from the
+/// `[health]` table of the configuration file.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, CopyGetters, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The maximum age of the release fixture in days
+    #[getset(get_copy = "pub")]
+    max_release_age_days: u32,
+}
+
+impl Validate for Config {
+    fn validate(&self, validator: &mut Validator) {
+        if self.max_release_age_days == 0 {
+            validator.problem("max_release_age_days", "expected a positive number of days");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::*;
+
+    use super::*;
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Config>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Config>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Config>();
+    }
+}
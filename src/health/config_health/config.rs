@@ -0,0 +1,116 @@
+//! Configuration to check the health of the fixtures
+
+use getset::{CopyGetters, Getters};
+#[cfg(test)]
+use typed_builder::TypedBuilder;
+
+/// Configuration to check the health of the fixtures
+///
+/// The configuration collects the fixtures from the configurations of the other test suites, so that
+/// they can be checked in one place.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, CopyGetters, Getters)]
+#[cfg_attr(test, derive(TypedBuilder))]
+pub struct Config {
+    /// The URL of the sparse index
+    #[getset(get = "pub")]
+    index_url: String,
+
+    /// The maximum age of the release fixture in days
+    #[getset(get_copy = "pub")]
+    max_release_age_days: u32,
+
+    /// The date of the release fixture
+    #[getset(get = "pub")]
+    release: String,
+
+    /// The crate fixture for rust-lang/crates.io#4891
+    #[getset(get = "pub")]
+    crates_4891: CrateFixture,
+
+    /// The crate fixture for rust-lang/crates.io#6164
+    #[getset(get = "pub")]
+    crates_6164: CrateFixture,
+}
+
+/// A crate and version that are used as a fixture
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters)]
+pub struct CrateFixture {
+    /// The name of the crate
+    #[getset(get = "pub")]
+    krate: String,
+
+    /// The version of the crate
+    #[getset(get = "pub")]
+    version: String,
+}
+
+impl Config {
+    /// Collect the fixtures from the given configuration
+    pub fn new(config: &crate::config::Config) -> Self {
+        let crates = config.crates();
+
+        Self {
+            index_url: config.discovery().index_url().clone(),
+            max_release_age_days: config.health().max_release_age_days(),
+            release: config.releases().list_files().release().clone(),
+            crates_4891: CrateFixture::new(
+                crates.crates_4891().krate(),
+                crates.crates_4891().version(),
+            ),
+            crates_6164: CrateFixture::new(
+                crates.crates_6164().krate(),
+                crates.crates_6164().version(),
+            ),
+        }
+    }
+}
+
+impl CrateFixture {
+    /// Create a new crate fixture
+    pub fn new(krate: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            krate: krate.into(),
+            version: version.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::environment::Environment;
+    use crate::test_utils::*;
+
+    use super::*;
+
+    #[test]
+    fn new_collects_fixtures() {
+        let config = crate::config::Config::for_env(&Environment::Production).unwrap();
+
+        let health = Config::new(&config);
+
+        assert_eq!("https://index.crates.io", health.index_url());
+        assert_eq!("2026-10-01", health.release());
+        assert_eq!(
+            &CrateFixture::new("libgit2-sys", "0.12.25+1.3.0"),
+            health.crates_4891()
+        );
+        assert_eq!(&CrateFixture::new("axum", "0.6.10"), health.crates_6164());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Config>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Config>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Config>();
+    }
+}
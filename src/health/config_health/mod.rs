@@ -0,0 +1,110 @@
+//! Check the health of the fixtures in the configuration
+//!
+//! This module checks the fixtures themselves rather than the infrastructure, so that a stale
+//! fixture shows up as its own finding instead of a confusing HTTP 404 in another test group.
+
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+use crate::health::Health;
+use crate::test::{Metadata, Tag, Test, TestGroup};
+
+pub use self::config::Config;
+pub use self::release_age::ReleaseAge;
+pub use self::yanked_version::YankedVersion;
+
+mod config;
+mod release_age;
+mod yanked_version;
+
+/// The name of the test group
+const NAME: &str = "Configuration health";
+
+/// Check the health of the fixtures in the configuration
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct ConfigHealth {
+    /// Configuration for the test group
+    config: Arc<Config>,
+}
+
+impl ConfigHealth {
+    /// Create a new instance of the test group
+    pub fn new(config: Config) -> Self {
+        Self {
+            config: Arc::new(config),
+        }
+    }
+}
+
+impl Display for ConfigHealth {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{NAME}")
+    }
+}
+
+impl TestGroup for ConfigHealth {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn metadata(&self) -> Metadata {
+        Metadata::builder()
+            .description(
+                "The fixtures in the configuration must not be stale. A stale fixture makes \
+                other tests fail with confusing errors, e.g. an HTTP 404 for a removed release.",
+            )
+            .team("infra")
+            .build()
+    }
+
+    fn tags(&self) -> Vec<Tag> {
+        vec![Health::tag()]
+    }
+
+    fn tests(&self) -> Vec<Box<dyn Test>> {
+        vec![
+            Box::new(ReleaseAge::new(self.config.clone())),
+            Box::new(YankedVersion::new(
+                "rust-lang/crates.io#4891 crate",
+                self.config.crates_4891().clone(),
+                self.config.clone(),
+            )),
+            Box::new(YankedVersion::new(
+                "rust-lang/crates.io#6164 crate",
+                self.config.crates_6164().clone(),
+                self.config.clone(),
+            )),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::*;
+
+    use super::*;
+
+    #[test]
+    fn trait_display() {
+        let config_health = ConfigHealth::new(Config::default());
+
+        assert_eq!("Configuration health", config_health.to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<ConfigHealth>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<ConfigHealth>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<ConfigHealth>();
+    }
+}
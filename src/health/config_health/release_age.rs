@@ -0,0 +1,136 @@
+//! Test that the release fixture is recent enough
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+use crate::test::{Metadata, Test, TestResult};
+
+use super::config::Config;
+
+/// The name of the test
+const NAME: &str = "Release date";
+
+/// Test that the release fixture is recent enough
+///
+/// Old releases are eventually removed from the CDN, at which point the tests for `list-files.html`
+/// fail with an HTTP 404. This test flags the release fixture before that happens.
+pub struct ReleaseAge {
+    /// Configuration for this test
+    config: Arc<Config>,
+}
+
+impl ReleaseAge {
+    /// Create a new instance of the test
+    pub fn new(config: Arc<Config>) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl Test for ReleaseAge {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn metadata(&self) -> Metadata {
+        Metadata::builder()
+            .description(
+                "Check that the release date in `releases.list_files.release` is not older \
+                than `health.max_release_age_days`.",
+            )
+            .build()
+    }
+
+    async fn run(&self) -> TestResult {
+        check_release_age(
+            self.config.release(),
+            self.config.max_release_age_days(),
+            chrono::Local::now().date_naive(),
+        )
+    }
+}
+
+/// Check that the release is not older than the maximum age on the given day
+fn check_release_age(release: &str, max_age_days: u32, today: NaiveDate) -> TestResult {
+    let test_result = TestResult::builder().name(NAME).success(false);
+
+    let date = match NaiveDate::parse_from_str(release, "%Y-%m-%d") {
+        Ok(date) => date,
+        Err(error) => {
+            return test_result
+                .message(Some(format!("Invalid release date '{release}': {error}")))
+                .build();
+        }
+    };
+
+    let age = (today - date).num_days();
+
+    if age > i64::from(max_age_days) {
+        return test_result
+            .message(Some(format!(
+                "Release {release} is {age} days old, which is older than the maximum of \
+                {max_age_days} days"
+            )))
+            .build();
+    }
+
+    TestResult::builder().name(NAME).success(true).build()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::*;
+
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 10, 1).unwrap()
+    }
+
+    #[test]
+    fn succeeds_with_recent_release() {
+        let result = check_release_age("2024-09-11", 30, today());
+
+        assert!(result.success());
+    }
+
+    #[test]
+    fn fails_with_old_release() {
+        let result = check_release_age("2024-06-01", 30, today());
+
+        assert!(!result.success());
+        assert_eq!(
+            &Some(
+                "Release 2024-06-01 is 122 days old, which is older than the maximum of 30 days"
+                    .into()
+            ),
+            result.message()
+        );
+    }
+
+    #[test]
+    fn fails_with_invalid_release() {
+        let result = check_release_age("stable", 30, today());
+
+        assert!(!result.success());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<ReleaseAge>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<ReleaseAge>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<ReleaseAge>();
+    }
+}
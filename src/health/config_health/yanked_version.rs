@@ -0,0 +1,180 @@
+//! Test that a crate fixture has not been yanked
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::http_client::custom_http_client;
+use crate::sparse_index;
+use crate::test::{Metadata, Test, TestResult};
+
+use super::config::{Config, CrateFixture};
+
+/// Test that a crate fixture has not been yanked
+///
+/// Yanked versions can still be downloaded, but may eventually be deleted. This test flags crate
+/// fixtures that have been yanked or removed from the index, so that they can be replaced before
+/// the tests that use them fail with a confusing HTTP 404.
+pub struct YankedVersion {
+    /// The name of the test
+    name: &'static str,
+
+    /// The crate fixture to check
+    fixture: CrateFixture,
+
+    /// Configuration for this test
+    config: Arc<Config>,
+}
+
+impl YankedVersion {
+    /// Create a new instance of the test
+    pub fn new(name: &'static str, fixture: CrateFixture, config: Arc<Config>) -> Self {
+        Self {
+            name,
+            fixture,
+            config,
+        }
+    }
+}
+
+#[async_trait]
+impl Test for YankedVersion {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn metadata(&self) -> Metadata {
+        Metadata::builder()
+            .description(
+                "Look up the crate fixture in the sparse index and expect its version to exist \
+                and not be yanked.",
+            )
+            .build()
+    }
+
+    async fn run(&self) -> TestResult {
+        let test_result = TestResult::builder().name(self.name).success(false);
+
+        let client = custom_http_client()
+            .build()
+            .expect("failed to build reqwest client");
+
+        let krate = self.fixture.krate();
+        let version = self.fixture.version();
+
+        let entries = match sparse_index::entries(&client, self.config.index_url(), krate).await {
+            Ok(entries) => entries,
+            Err(error) => return test_result.message(Some(error)).build(),
+        };
+
+        match entries.iter().find(|entry| entry.vers() == version) {
+            None => test_result
+                .message(Some(format!(
+                    "Version {version} of {krate} is not in the index"
+                )))
+                .build(),
+            Some(entry) if entry.yanked() => test_result
+                .message(Some(format!(
+                    "Version {version} of {krate} has been yanked"
+                )))
+                .build(),
+            Some(_) => TestResult::builder().name(self.name).success(true).build(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockito::ServerGuard;
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::*;
+
+    use super::*;
+
+    async fn setup() -> (ServerGuard, YankedVersion) {
+        let server = mockito::Server::new_async().await;
+
+        let config = Config::builder()
+            .index_url(server.url())
+            .max_release_age_days(30)
+            .release("2024-09-11".into())
+            .crates_4891(CrateFixture::default())
+            .crates_6164(CrateFixture::default())
+            .build();
+
+        let test = YankedVersion::new(
+            "axum",
+            CrateFixture::new("axum", "0.6.10"),
+            Arc::new(config),
+        );
+
+        (server, test)
+    }
+
+    #[tokio::test]
+    async fn succeeds_with_available_version() {
+        let (mut server, test) = setup().await;
+
+        let mock = server
+            .mock("GET", "/ax/um/axum")
+            .with_body("{\"name\":\"axum\",\"vers\":\"0.6.10\",\"yanked\":false}\n")
+            .create();
+
+        let result = test.run().await;
+
+        mock.assert();
+        assert!(result.success());
+    }
+
+    #[tokio::test]
+    async fn fails_with_yanked_version() {
+        let (mut server, test) = setup().await;
+
+        server
+            .mock("GET", "/ax/um/axum")
+            .with_body("{\"name\":\"axum\",\"vers\":\"0.6.10\",\"yanked\":true}\n")
+            .create();
+
+        let result = test.run().await;
+
+        assert!(!result.success());
+        assert_eq!(
+            &Some("Version 0.6.10 of axum has been yanked".into()),
+            result.message()
+        );
+    }
+
+    #[tokio::test]
+    async fn fails_with_missing_version() {
+        let (mut server, test) = setup().await;
+
+        server
+            .mock("GET", "/ax/um/axum")
+            .with_body("{\"name\":\"axum\",\"vers\":\"0.7.0\",\"yanked\":false}\n")
+            .create();
+
+        let result = test.run().await;
+
+        assert!(!result.success());
+        assert_eq!(
+            &Some("Version 0.6.10 of axum is not in the index".into()),
+            result.message()
+        );
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<YankedVersion>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<YankedVersion>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<YankedVersion>();
+    }
+}
@@ -0,0 +1,148 @@
+//! Health checks for the configuration
+//!
+//! This test suite checks the configuration of the smoke tests rather than the infrastructure. It
+//! flags fixtures that have gone stale, for example a release that is too old or a crate version
+//! that has been yanked.
+
+use std::fmt::{Display, Formatter};
+
+use crate::health::config_health::ConfigHealth;
+use crate::test::{Tag, TestGroup, TestSuite};
+
+pub use self::config::Config;
+
+mod config;
+mod config_health;
+
+/// Health checks for the configuration
+///
+/// The test suite collects the fixtures from the configurations of the other test suites, and
+/// checks that they are still usable.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct Health {
+    /// The fixtures to check
+    config: config_health::Config,
+}
+
+impl Health {
+    /// The name of the test suite
+    pub const NAME: &'static str = "Configuration";
+
+    /// Return the tag that selects the health checks, i.e. `config:health`
+    pub fn tag() -> Tag {
+        Tag::new("config:health")
+    }
+
+    /// Creates a new instance of the test suite
+    pub fn new(config: &crate::config::Config) -> Self {
+        Self {
+            config: config_health::Config::new(config),
+        }
+    }
+}
+
+impl Display for Health {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", Self::NAME)
+    }
+}
+
+impl TestSuite for Health {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn groups(&self) -> Vec<Box<dyn TestGroup>> {
+        vec![Box::new(ConfigHealth::new(self.config.clone()))]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::config::Override;
+    use crate::environment::Environment;
+    use crate::test::Filter;
+    use crate::test_utils::*;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn reports_yanked_static_version_with_discovery() {
+        let mut server = mockito::Server::new_async().await;
+
+        server
+            .mock("GET", "/dist/channel-rust-stable.toml")
+            .with_body("manifest-version = \"2\"\ndate = \"2024-10-17\"\n")
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/ru/st/rust-cratesio-4891")
+            .with_body(indoc! {r#"
+                {"name":"rust-cratesio-4891","vers":"0.1.0+1","yanked":true}
+                {"name":"rust-cratesio-4891","vers":"0.2.0+1","yanked":false}
+            "#})
+            .expect_at_least(2)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/cr/os/crossbeam")
+            .with_body("{\"name\":\"crossbeam\",\"vers\":\"0.2.10\",\"yanked\":false}\n")
+            .create_async()
+            .await;
+
+        let overrides = [
+            Override::new(
+                "discovery.channel_manifest_url",
+                format!("{}/dist/channel-rust-stable.toml", server.url()),
+            ),
+            Override::new("discovery.index_url", server.url()),
+        ];
+
+        let discovered = crate::discovery::load(&Environment::Staging, None, &overrides)
+            .await
+            .unwrap();
+        assert_eq!(
+            "0.2.0+1",
+            discovered.config().crates().crates_4891().version()
+        );
+
+        let result = Health::new(discovered.static_config())
+            .run(&Filter::default())
+            .await;
+        let (_, yanked) = result
+            .test_results()
+            .find(|(path, _)| path.test() == "rust-lang/crates.io#4891 crate")
+            .unwrap();
+
+        assert!(!yanked.success());
+        assert_eq!(
+            &Some("Version 0.1.0+1 of rust-cratesio-4891 has been yanked".to_string()),
+            yanked.message()
+        );
+    }
+
+    #[test]
+    fn trait_display() {
+        let health = Health::default();
+
+        assert_eq!("Configuration", health.to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Health>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Health>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Health>();
+    }
+}
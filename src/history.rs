@@ -0,0 +1,460 @@
+//! A history of the results of past runs
+//!
+//! A single failure of a check doesn't tell whether the check is flaky or whether something broke.
+//! To tell them apart, the results of every run can be appended to a history file in the JSON Lines
+//! format, with one entry per test and run:
+//!
+//! ```json
+//! {"time":"2024-06-01T12:00:00Z","environment":"staging","path":"crates.io > Database dumps > Fastly","success":true,"message":null,"duration_ms":132}
+//! ```
+//!
+//! The statistics for a time window show for each test how often it passed, when it failed last,
+//! and how often it flipped between passing and failing. A flaky check flips often, while a
+//! regression fails consistently from some point on.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+
+use crate::environment::Environment;
+use crate::test::{TestPath, TestSuiteResult};
+
+/// A history of the results of past runs
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters)]
+pub struct History {
+    /// The entries in the history, in the order in which they were recorded
+    #[getset(get = "pub")]
+    entries: Vec<Entry>,
+}
+
+/// The result of a test in a single run
+#[derive(
+    Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, Getters, Deserialize, Serialize,
+)]
+pub struct Entry {
+    /// When the run finished
+    #[getset(get_copy = "pub")]
+    time: DateTime<Utc>,
+
+    /// The name of the environment that was tested
+    #[getset(get = "pub")]
+    environment: String,
+
+    /// The path of the test
+    #[getset(get = "pub")]
+    path: TestPath,
+
+    /// Whether the test passed
+    #[getset(get_copy = "pub")]
+    success: bool,
+
+    /// The message of the result, which usually explains why the test failed
+    #[getset(get = "pub")]
+    message: Option<String>,
+
+    /// How long the test took to run in milliseconds
+    #[getset(get_copy = "pub")]
+    duration_ms: u64,
+}
+
+/// The statistics of a test over a time window
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, Getters)]
+pub struct Statistics {
+    /// The path of the test
+    #[getset(get = "pub")]
+    path: TestPath,
+
+    /// The number of runs in the time window
+    #[getset(get_copy = "pub")]
+    runs: usize,
+
+    /// The number of runs in which the test passed
+    #[getset(get_copy = "pub")]
+    passes: usize,
+
+    /// The last failure in the time window, with its message
+    #[getset(get = "pub")]
+    last_failure: Option<(DateTime<Utc>, Option<String>)>,
+
+    /// How often the test changed between passing and failing
+    #[getset(get_copy = "pub")]
+    flips: usize,
+}
+
+/// Errors that can occur when reading or writing the history
+#[derive(Debug)]
+pub enum HistoryError {
+    /// The history file could not be read
+    Read(std::io::Error),
+
+    /// The history file could not be written
+    Write(std::io::Error),
+
+    /// A line of the history file is not a valid entry
+    Parse {
+        /// The number of the line, starting at 1
+        line: usize,
+
+        /// The error that occurred while parsing the line
+        error: serde_json::Error,
+    },
+}
+
+impl History {
+    /// Load the history from the file at the given path
+    pub fn load(path: &Path) -> Result<Self, HistoryError> {
+        std::fs::read_to_string(path)
+            .map_err(HistoryError::Read)?
+            .parse()
+    }
+
+    /// Append the entries to the history file at the given path, creating it if necessary
+    pub fn append(path: &Path, entries: &[Entry]) -> Result<(), HistoryError> {
+        let mut lines = String::new();
+
+        for entry in entries {
+            let line =
+                serde_json::to_string(entry).map_err(|error| HistoryError::Write(error.into()))?;
+
+            lines.push_str(&line);
+            lines.push('\n');
+        }
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(lines.as_bytes()))
+            .map_err(HistoryError::Write)
+    }
+
+    /// Return the statistics of the tests in the environment over the window that ends now
+    ///
+    /// A window that reaches back further than time can be represented covers the whole history.
+    pub fn statistics_within(
+        &self,
+        env: &Environment,
+        window: Duration,
+        now: DateTime<Utc>,
+    ) -> Vec<Statistics> {
+        let since = chrono::Duration::from_std(window)
+            .ok()
+            .and_then(|window| now.checked_sub_signed(window))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+
+        self.statistics(env, since)
+    }
+
+    /// Return the statistics of the tests in the environment since the given time
+    ///
+    /// The statistics are sorted by the path of the test.
+    pub fn statistics(&self, env: &Environment, since: DateTime<Utc>) -> Vec<Statistics> {
+        let mut entries: Vec<&Entry> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.environment == env.name() && entry.time >= since)
+            .collect();
+        entries.sort_by_key(|entry| entry.time);
+
+        let mut tests: BTreeMap<&TestPath, Vec<&Entry>> = BTreeMap::new();
+        for entry in entries {
+            tests.entry(&entry.path).or_default().push(entry);
+        }
+
+        tests
+            .into_iter()
+            .map(|(path, entries)| Statistics {
+                path: path.clone(),
+                runs: entries.len(),
+                passes: entries.iter().filter(|entry| entry.success).count(),
+                last_failure: entries
+                    .iter()
+                    .rev()
+                    .find(|entry| !entry.success)
+                    .map(|entry| (entry.time, entry.message.clone())),
+                flips: entries
+                    .windows(2)
+                    .filter(|pair| pair[0].success != pair[1].success)
+                    .count(),
+            })
+            .collect()
+    }
+}
+
+impl FromStr for History {
+    type Err = HistoryError;
+
+    /// Parse the history from JSON Lines
+    ///
+    /// Every entry is appended with a trailing newline, so a last line without one was cut off while
+    /// it was written, e.g. because the process was killed. Such a line is skipped with a warning if
+    /// it can't be parsed, while any other malformed line is an error.
+    fn from_str(history: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = history.lines().collect();
+        let truncated = !history.ends_with('\n');
+
+        let mut entries = Vec::with_capacity(lines.len());
+
+        for (index, line) in lines.iter().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            match serde_json::from_str(line) {
+                Ok(entry) => entries.push(entry),
+                Err(error) if truncated && index + 1 == lines.len() => eprintln!(
+                    "Warning: skipping the incomplete line {} of the history: {error}",
+                    index + 1
+                ),
+                Err(error) => {
+                    return Err(HistoryError::Parse {
+                        line: index + 1,
+                        error,
+                    })
+                }
+            }
+        }
+
+        Ok(Self { entries })
+    }
+}
+
+impl Entry {
+    /// Create the entries for the results of a run
+    pub fn from_results(
+        env: &Environment,
+        results: &[TestSuiteResult],
+        time: DateTime<Utc>,
+    ) -> Vec<Self> {
+        results
+            .iter()
+            .flat_map(TestSuiteResult::test_results)
+            .map(|(path, result)| Self {
+                time,
+                environment: env.name().into(),
+                path,
+                success: result.success(),
+                message: result.message().clone(),
+                duration_ms: u64::try_from(result.duration().as_millis()).unwrap_or(u64::MAX),
+            })
+            .collect()
+    }
+}
+
+impl Statistics {
+    /// Return the share of runs in which the test passed, between 0 and 1
+    pub fn pass_rate(&self) -> f64 {
+        if self.runs == 0 {
+            return 0.0;
+        }
+
+        self.passes as f64 / self.runs as f64
+    }
+}
+
+impl Display for Statistics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.path)?;
+        writeln!(
+            f,
+            "  Pass rate: {:.1}% ({}/{} runs)",
+            self.pass_rate() * 100.0,
+            self.passes,
+            self.runs
+        )?;
+
+        match &self.last_failure {
+            Some((time, message)) => {
+                let time = time.to_rfc3339_opts(SecondsFormat::Secs, true);
+
+                match message {
+                    Some(message) => writeln!(f, "  Last failure: {time} ({message})")?,
+                    None => writeln!(f, "  Last failure: {time}")?,
+                }
+            }
+            None => writeln!(f, "  Last failure: none")?,
+        }
+
+        writeln!(f, "  Flips: {}", self.flips)
+    }
+}
+
+impl Display for HistoryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HistoryError::Read(error) => write!(f, "failed to read the history: {error}"),
+            HistoryError::Write(error) => write!(f, "failed to write the history: {error}"),
+            HistoryError::Parse { line, error } => {
+                write!(f, "failed to parse line {line} of the history: {error}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for HistoryError {}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::test::{TestGroupResult, TestResult};
+    use crate::test_utils::*;
+
+    use super::*;
+
+    fn time(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 6, 1, hour, 0, 0).unwrap()
+    }
+
+    fn results(success: bool) -> Vec<TestSuiteResult> {
+        let result = TestResult::builder()
+            .name("Fastly")
+            .success(success)
+            .message((!success).then(|| "HTTP 404".to_string()))
+            .duration(Duration::from_millis(132))
+            .build();
+
+        vec![TestSuiteResult::builder()
+            .name("crates.io")
+            .results(vec![TestGroupResult::builder()
+                .name("Database dumps")
+                .results(vec![result])
+                .build()])
+            .build()]
+    }
+
+    fn history(runs: &[(u32, bool)]) -> History {
+        let entries = runs
+            .iter()
+            .flat_map(|(hour, success)| {
+                Entry::from_results(&Environment::Staging, &results(*success), time(*hour))
+            })
+            .collect();
+
+        History { entries }
+    }
+
+    #[test]
+    fn entry_serializes_to_json_line() {
+        let entries = Entry::from_results(&Environment::Staging, &results(true), time(12));
+
+        assert_eq!(
+            r#"{"time":"2024-06-01T12:00:00Z","environment":"staging","path":"crates.io > Database dumps > Fastly","success":true,"message":null,"duration_ms":132}"#,
+            serde_json::to_string(&entries[0]).unwrap()
+        );
+    }
+
+    #[test]
+    fn append_and_load() {
+        let path = std::env::temp_dir().join(format!(
+            "infra-smoke-test-history-{}.jsonl",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let first = Entry::from_results(&Environment::Staging, &results(true), time(1));
+        let second = Entry::from_results(&Environment::Staging, &results(false), time(2));
+
+        History::append(&path, &first).unwrap();
+        History::append(&path, &second).unwrap();
+
+        let history = History::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(&[first, second].concat(), history.entries());
+    }
+
+    #[test]
+    fn parse_error_names_line() {
+        let error = "\n{}\n".parse::<History>().unwrap_err();
+
+        assert!(error
+            .to_string()
+            .starts_with("failed to parse line 2 of the history"));
+    }
+
+    #[test]
+    fn parse_skips_incomplete_last_line() {
+        let entries = Entry::from_results(&Environment::Staging, &results(true), time(1));
+        let line = serde_json::to_string(&entries[0]).unwrap();
+
+        let history = format!("{line}\n{}", &line[..line.len() / 2])
+            .parse::<History>()
+            .unwrap();
+
+        assert_eq!(&entries, history.entries());
+    }
+
+    #[test]
+    fn statistics_for_flaky_test() {
+        let history = history(&[(1, true), (2, false), (3, true), (4, false), (5, true)]);
+
+        let statistics = history.statistics(&Environment::Staging, time(0));
+
+        let expected = indoc! {"
+            crates.io > Database dumps > Fastly
+              Pass rate: 60.0% (3/5 runs)
+              Last failure: 2024-06-01T04:00:00Z (HTTP 404)
+              Flips: 4
+        "};
+
+        assert_eq!(1, statistics.len());
+        assert_eq!(expected, statistics[0].to_string());
+    }
+
+    #[test]
+    fn statistics_within_window_and_environment() {
+        let mut history = history(&[(1, false), (2, true), (3, true)]);
+        history.entries.extend(Entry::from_results(
+            &Environment::Production,
+            &results(false),
+            time(3),
+        ));
+
+        let statistics = history.statistics(&Environment::Staging, time(2));
+
+        assert_eq!(2, statistics[0].runs());
+        assert_eq!(&None, statistics[0].last_failure());
+        assert_eq!(0, statistics[0].flips());
+    }
+
+    #[test]
+    fn statistics_within_window() {
+        let history = history(&[(1, false), (2, true), (3, true)]);
+
+        let recent =
+            history.statistics_within(&Environment::Staging, Duration::from_secs(3600), time(3));
+        let all = history.statistics_within(&Environment::Staging, Duration::MAX, time(3));
+
+        assert_eq!(2, recent[0].runs());
+        assert_eq!(3, all[0].runs());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<History>();
+        assert_send::<Statistics>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<History>();
+        assert_sync::<Statistics>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<History>();
+        assert_unpin::<Statistics>();
+    }
+}
@@ -1,18 +1,86 @@
 //! Shared http client builder
+//!
+//! Tests send their requests with [`send`], which records the responses of the test that is
+//! currently running. The recorded responses are used to compare environments with each other.
+use std::cell::RefCell;
+use std::future::Future;
+use std::time::Duration;
+
 use reqwest::{
     header::{self, HeaderMap, HeaderValue},
-    ClientBuilder,
+    ClientBuilder, RequestBuilder, Response,
 };
 
+use crate::test::recorded_response::headers_from_map;
+use crate::test::{RecordedResponse, TestResult};
+
+/// The time that a client has to connect to a server
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The time that a request may take, from connecting to reading the end of the response body
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+tokio::task_local! {
+    /// The responses that have been recorded by the test that is running in the current task
+    static RESPONSES: RefCell<Vec<RecordedResponse>>;
+}
+
 /// Create a pre-configured ClientBuilder
 ///
 /// This function returns a reqwest::ClientBuilder that has been pre-configured with default headers.
 /// Specifically, it sets the `User-Agent` header so that requests from the test suite can be more easily filtered and inspected in the request logs.
+/// It also sets timeouts, so that a server that never responds fails the request instead of stalling the run, e.g. every iteration of the monitor.
 pub fn custom_http_client() -> ClientBuilder {
-    reqwest::ClientBuilder::new().default_headers(HeaderMap::from_iter([(
-        header::USER_AGENT,
-        USER_AGENT_HEADER,
-    )]))
+    client_with_timeouts(CONNECT_TIMEOUT, REQUEST_TIMEOUT)
+}
+
+/// Create a pre-configured ClientBuilder with the given timeouts
+fn client_with_timeouts(connect: Duration, request: Duration) -> ClientBuilder {
+    reqwest::ClientBuilder::new()
+        .default_headers(HeaderMap::from_iter([(
+            header::USER_AGENT,
+            USER_AGENT_HEADER,
+        )]))
+        .connect_timeout(connect)
+        .timeout(request)
+}
+
+/// Send the request and record its response
+///
+/// The response is recorded if the request is sent from within [`record`], and is otherwise only
+/// returned to the caller.
+pub async fn send(request: RequestBuilder) -> reqwest::Result<Response> {
+    let (client, request) = request.build_split();
+    let request = request?;
+
+    let method = request.method().to_string();
+    let url = request.url().to_string();
+
+    let response = client.execute(request).await?;
+
+    let recorded = RecordedResponse::builder()
+        .method(method)
+        .url(url)
+        .status(response.status().as_u16())
+        .headers(headers_from_map(response.headers()))
+        .build();
+
+    // Requests outside of a recording, e.g. during the discovery of fixtures, are not recorded
+    let _ = RESPONSES.try_with(|responses| responses.borrow_mut().push(recorded));
+
+    Ok(response)
+}
+
+/// Run the test and attach the responses that it received to its result
+pub async fn record(test: impl Future<Output = TestResult>) -> TestResult {
+    RESPONSES
+        .scope(RefCell::new(Vec::new()), async {
+            let result = test.await;
+            let responses = RESPONSES.with(|responses| responses.take());
+
+            result.with_responses(responses)
+        })
+        .await
 }
 
 /// User-Agent used for all tests
@@ -20,8 +88,70 @@ const USER_AGENT_HEADER: HeaderValue = HeaderValue::from_static("rust-lang/infra
 
 #[cfg(test)]
 mod tests {
+    use pretty_assertions::assert_eq;
+
     use super::*;
 
+    #[tokio::test]
+    async fn record_responses() {
+        let mut server = mockito::Server::new_async().await;
+
+        server
+            .mock("HEAD", "/path")
+            .with_status(307)
+            .with_header("X-Cache", "MISS")
+            .create();
+
+        let url = format!("{}/path", server.url());
+
+        let result = record(async {
+            let client = custom_http_client().build().unwrap();
+            send(client.head(&url)).await.unwrap();
+
+            TestResult::builder().name("name").success(true).build()
+        })
+        .await;
+
+        assert_eq!(1, result.responses().len());
+        assert_eq!("HEAD", result.responses()[0].method());
+        assert_eq!(&url, result.responses()[0].url());
+        assert_eq!(307, result.responses()[0].status());
+        assert_eq!(Some("MISS"), result.responses()[0].header("x-cache"));
+    }
+
+    #[tokio::test]
+    async fn send_without_recording() {
+        let mut server = mockito::Server::new_async().await;
+
+        server.mock("GET", "/").create();
+
+        let client = custom_http_client().build().unwrap();
+
+        assert!(send(client.get(server.url())).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn send_times_out_without_response() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+
+        // Accept the connection but never respond to the request
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            drop(stream);
+        });
+
+        let client = client_with_timeouts(CONNECT_TIMEOUT, Duration::from_millis(100))
+            .build()
+            .unwrap();
+
+        let error = send(client.get(&url)).await.unwrap_err();
+        server.abort();
+
+        assert!(error.is_timeout());
+    }
+
     #[tokio::test]
     async fn default_headers_are_addative() {
         let client = custom_http_client()
@@ -0,0 +1,247 @@
+//! A minimal HTTP server for the endpoints of the long-running modes
+//!
+//! The monitor serves Prometheus metrics and the status page serves HTML and JSON. Both only need
+//! to answer a handful of `GET` requests, so instead of pulling in a web framework, this module
+//! reads the request line, asks a handler for the response, and closes the connection.
+
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// The maximum size of a request
+const MAX_REQUEST_SIZE: usize = 8192;
+
+/// The time that a client has to send its request before the connection is closed
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The time to wait before accepting connections again after accepting one failed
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+/// The status line for requests with a method other than `GET`
+const METHOD_NOT_ALLOWED: &str = "405 Method Not Allowed";
+
+/// A response to a request
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub(crate) struct Response {
+    /// The status line of the response, e.g. `200 OK`
+    status: &'static str,
+
+    /// The content type of the body
+    content_type: &'static str,
+
+    /// The body of the response
+    body: String,
+}
+
+impl Response {
+    /// Create a successful response with the given content type and body
+    pub(crate) fn ok(content_type: &'static str, body: String) -> Self {
+        Self {
+            status: "200 OK",
+            content_type,
+            body,
+        }
+    }
+
+    /// Create a response for a path that doesn't exist
+    pub(crate) fn not_found() -> Self {
+        Self {
+            status: "404 Not Found",
+            content_type: "text/plain; charset=utf-8",
+            body: "Not Found\n".into(),
+        }
+    }
+
+    /// Create a response for a request with a method other than `GET`
+    fn method_not_allowed() -> Self {
+        Self {
+            status: METHOD_NOT_ALLOWED,
+            content_type: "text/plain; charset=utf-8",
+            body: "Method Not Allowed\n".into(),
+        }
+    }
+}
+
+/// Serve requests with the handler for as long as the future is polled
+///
+/// The handler is called with the path of each `GET` request, while other methods are answered with
+/// HTTP 405. Each connection is handled in its own task, and closed after the response has been
+/// sent. Clients that don't send their request within a few seconds are disconnected. Errors while
+/// accepting a connection, e.g. when the process has run out of file descriptors, only affect that
+/// connection, so they are printed as a warning and the server keeps accepting connections.
+pub(crate) async fn serve<H>(listener: TcpListener, handler: H)
+where
+    H: Fn(&str) -> Response + Clone + Send + 'static,
+{
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(error) => {
+                eprintln!("Warning: failed to accept a connection: {error}");
+                tokio::time::sleep(ACCEPT_BACKOFF).await;
+                continue;
+            }
+        };
+        let handler = handler.clone();
+
+        tokio::spawn(async move {
+            // Errors of individual connections only affect that request, so they are ignored
+            let _ = handle(stream, handler, READ_TIMEOUT).await;
+        });
+    }
+}
+
+/// Read a request from the stream and write the response of the handler
+///
+/// Returns an error without a response if the request hasn't been read within the timeout.
+async fn handle<H>(mut stream: TcpStream, handler: H, timeout: Duration) -> std::io::Result<()>
+where
+    H: Fn(&str) -> Response,
+{
+    let request = tokio::time::timeout(timeout, read_request(&mut stream))
+        .await
+        .map_err(|_| std::io::Error::from(std::io::ErrorKind::TimedOut))??;
+
+    let request = String::from_utf8_lossy(&request);
+    let request_line = request.lines().next().unwrap_or_default();
+
+    let response = match request_line.split_whitespace().take(2).collect::<Vec<_>>()[..] {
+        ["GET", path] => handler(path),
+        [_, _] => Response::method_not_allowed(),
+        _ => Response::not_found(),
+    };
+
+    let allow = if response.status == METHOD_NOT_ALLOWED {
+        "Allow: GET\r\n"
+    } else {
+        ""
+    };
+
+    let head = format!(
+        "HTTP/1.1 {}\r\n\
+        Content-Type: {}\r\n\
+        Content-Length: {}\r\n\
+        {allow}\
+        Connection: close\r\n\r\n",
+        response.status,
+        response.content_type,
+        response.body.len()
+    );
+
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(response.body.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Read the head of a request from the stream, up to the maximum size of a request
+async fn read_request(stream: &mut TcpStream) -> std::io::Result<Vec<u8>> {
+    let mut request = Vec::new();
+    let mut buffer = [0; 1024];
+
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = stream.read(&mut buffer).await?;
+
+        if read == 0 || request.len() + read > MAX_REQUEST_SIZE {
+            break;
+        }
+
+        request.extend_from_slice(&buffer[..read]);
+    }
+
+    Ok(request)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::*;
+
+    use super::*;
+
+    fn handler(path: &str) -> Response {
+        match path {
+            "/hello" => Response::ok("text/plain; charset=utf-8", "Hello\n".into()),
+            _ => Response::not_found(),
+        }
+    }
+
+    #[tokio::test]
+    async fn serve_with_handler() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        tokio::spawn(serve(listener, handler));
+
+        let response = reqwest::get(format!("http://{address}/hello"))
+            .await
+            .unwrap();
+
+        assert_eq!(200, response.status().as_u16());
+        assert_eq!(
+            "text/plain; charset=utf-8",
+            response.headers()["Content-Type"]
+        );
+        assert_eq!("Hello\n", response.text().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn serve_not_found() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        tokio::spawn(serve(listener, handler));
+
+        let response = reqwest::get(format!("http://{address}/")).await.unwrap();
+
+        assert_eq!(404, response.status().as_u16());
+    }
+
+    #[tokio::test]
+    async fn serve_method_not_allowed() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        tokio::spawn(serve(listener, handler));
+
+        let response = reqwest::Client::new()
+            .post(format!("http://{address}/hello"))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(405, response.status().as_u16());
+        assert_eq!("GET", response.headers()["Allow"]);
+    }
+
+    #[tokio::test]
+    async fn handle_times_out_idle_connection() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let _client = TcpStream::connect(address).await.unwrap();
+        let (stream, _) = listener.accept().await.unwrap();
+
+        let error = handle(stream, handler, Duration::from_millis(50))
+            .await
+            .unwrap_err();
+
+        assert_eq!(std::io::ErrorKind::TimedOut, error.kind());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Response>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Response>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Response>();
+    }
+}
@@ -0,0 +1,148 @@
+//! Configuration for the issue tracker integration
+
+use getset::{CopyGetters, Getters};
+use serde::Deserialize;
+#[cfg(test)]
+use typed_builder::TypedBuilder;
+
+use crate::config::{Validate, Validator};
+
+/// Configuration for the issue tracker integration
+///
+/// The configuration is read from the `[issue]` table of the configuration file. Issues are only
+/// opened if both a repository and a token are configured. The token is a secret and is usually
+/// passed in as an environment variable, e.g. `SMOKE_TESTS_ISSUE__TOKEN`.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, Getters, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(test, derive(TypedBuilder))]
+pub struct Config {
+    /// The base URL of the GitHub-compatible REST API
+    #[serde(
+        default = "default_api_url",
+        deserialize_with = "crate::config::deserialize_url"
+    )]
+    #[cfg_attr(test, builder(default = default_api_url(), setter(into)))]
+    #[getset(get = "pub")]
+    api_url: String,
+
+    /// The repository in which the issue is opened, e.g. `rust-lang/infra-team`
+    #[serde(default)]
+    #[cfg_attr(test, builder(default, setter(strip_option, into)))]
+    #[getset(get = "pub")]
+    repository: Option<String>,
+
+    /// The token that is used to authenticate with the API
+    #[serde(
+        default,
+        deserialize_with = "crate::config::deserialize_optional_secret"
+    )]
+    #[cfg_attr(test, builder(default, setter(strip_option, into)))]
+    #[getset(get = "pub")]
+    token: Option<String>,
+
+    /// The title of the issue, which is also used to find the open issue
+    #[serde(default = "default_title")]
+    #[cfg_attr(test, builder(default = default_title(), setter(into)))]
+    #[getset(get = "pub")]
+    title: String,
+
+    /// The labels that are added to a new issue
+    #[serde(default)]
+    #[cfg_attr(test, builder(default))]
+    #[getset(get = "pub")]
+    labels: Vec<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            api_url: default_api_url(),
+            repository: None,
+            token: None,
+            title: default_title(),
+            labels: Vec::new(),
+        }
+    }
+}
+
+/// Return the default base URL of the API
+fn default_api_url() -> String {
+    "https://api.github.com".into()
+}
+
+/// Return the default title of the issue
+fn default_title() -> String {
+    "Smoke tests are failing".into()
+}
+
+impl Validate for Config {
+    fn validate(&self, validator: &mut Validator) {
+        validator.url("api_url", &self.api_url);
+
+        if let Some(repository) = &self.repository {
+            let parts: Vec<&str> = repository.split('/').collect();
+
+            if parts.len() != 2 || parts.iter().any(|part| part.is_empty()) {
+                validator.problem(
+                    "repository",
+                    format!("expected a repository like 'owner/name', got '{repository}'"),
+                );
+            }
+        }
+
+        if self.title.trim().is_empty() {
+            validator.problem("title", "expected a title for the issue");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::*;
+
+    use super::*;
+
+    #[test]
+    fn deserialize_with_defaults() {
+        let config: Config = toml::from_str("").unwrap();
+
+        assert_eq!("https://api.github.com", config.api_url());
+        assert_eq!(&None, config.repository());
+        assert_eq!(&None, config.token());
+        assert_eq!("Smoke tests are failing", config.title());
+    }
+
+    #[test]
+    fn default_matches_empty_table() {
+        let config: Config = toml::from_str("").unwrap();
+
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn validate_repository_without_owner() {
+        let config = Config::builder().repository("infra-team").build();
+
+        let mut validator = Validator::new();
+        config.validate(&mut validator);
+
+        assert_eq!("repository", validator.problems()[0].key());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Config>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Config>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Config>();
+    }
+}
@@ -0,0 +1,593 @@
+//! Issues for failing smoke tests
+//!
+//! Failures in production should be tracked where the infrastructure team already tracks its work.
+//! After a run in production, the issue tracker looks for the open issue with the configured title
+//! and labels in a repository on GitHub or on another server with a GitHub-compatible REST API:
+//!
+//! - If tests failed and no issue is open, it opens one that lists the failing tests.
+//! - If tests failed and the issue is open, it comments on the issue with the latest failures.
+//! - If all tests passed and the issue is open, it comments that the tests recovered and closes it.
+//!
+//! Tests that are listed in the baseline of known failures are not reported.
+
+use std::fmt::{Display, Formatter};
+
+use reqwest::{Client, Method, Url};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::environment::Environment;
+use crate::http_client::custom_http_client;
+use crate::side_channels::warn_on_error;
+use crate::test::{Filter, TestSuiteResult};
+use crate::webhook::Notification;
+
+pub use self::config::Config;
+
+mod config;
+
+/// The number of issues that are requested per page when looking for the open issue
+const PER_PAGE: usize = 100;
+
+/// A client that opens, updates, and closes the issue for failing smoke tests
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct IssueTracker {
+    /// Configuration for the issue tracker
+    config: Config,
+}
+
+/// The change that the issue tracker made after a run
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Action {
+    /// Nothing was changed, because all tests passed and no issue was open
+    Nothing,
+
+    /// A new issue with the given number was opened
+    Opened(u64),
+
+    /// The open issue with the given number was commented on
+    Commented(u64),
+
+    /// The open issue with the given number was closed
+    Closed(u64),
+}
+
+/// An issue as returned by the API
+#[derive(Deserialize)]
+struct RemoteIssue {
+    /// The number of the issue in the repository
+    number: u64,
+
+    /// The title of the issue
+    title: String,
+
+    /// Set if the issue is a pull request, which the API lists together with issues
+    #[serde(default)]
+    pull_request: Option<Value>,
+}
+
+impl IssueTracker {
+    /// Create a new issue tracker with the given configuration
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    /// Check if a repository and a token are configured, i.e. if issues are updated at all
+    pub fn is_enabled(&self) -> bool {
+        self.config.repository().is_some() && self.config.token().is_some()
+    }
+
+    /// Update the issue after a run of the tests that the filter selected
+    ///
+    /// The issue lists the failures of a full run, so runs that only selected some of the tests
+    /// don't update it. Otherwise, passing tests could close the issue while the tests that failed
+    /// were not run at all. If the issue can't be updated, a warning is printed and nothing is done.
+    pub async fn report(
+        &self,
+        env: &Environment,
+        filter: &Filter,
+        results: &[TestSuiteResult],
+    ) -> Action {
+        if !filter.is_empty() {
+            return Action::Nothing;
+        }
+
+        match self.update(env, results).await {
+            Ok(action) => action,
+            Err(error) => {
+                warn_on_error(Err(error), "failed to update the issue");
+                Action::Nothing
+            }
+        }
+    }
+
+    /// Open, comment on, or close the issue depending on the results of the run
+    ///
+    /// Only runs in production are tracked, so nothing happens in any other environment.
+    pub async fn update(
+        &self,
+        env: &Environment,
+        results: &[TestSuiteResult],
+    ) -> Result<Action, String> {
+        if !self.is_enabled() || *env != Environment::Production {
+            return Ok(Action::Nothing);
+        }
+
+        let client = custom_http_client()
+            .build()
+            .map_err(|error| error.to_string())?;
+
+        let notification = Notification::from_results(env.clone(), results);
+        let open_issue = self.find_open_issue(&client).await?;
+
+        match (notification.is_empty(), open_issue) {
+            (true, None) => Ok(Action::Nothing),
+            (true, Some(number)) => {
+                let body = format!("All smoke tests pass again in **{env}**.");
+                self.comment(&client, number, &body).await?;
+
+                self.send::<Value>(
+                    &client,
+                    Method::PATCH,
+                    &format!("issues/{number}"),
+                    &[],
+                    Some(json!({ "state": "closed" })),
+                )
+                .await?;
+
+                Ok(Action::Closed(number))
+            }
+            (false, None) => {
+                let issue: RemoteIssue = self
+                    .send(
+                        &client,
+                        Method::POST,
+                        "issues",
+                        &[],
+                        Some(json!({
+                            "title": self.config.title(),
+                            "body": render(&notification),
+                            "labels": self.config.labels(),
+                        })),
+                    )
+                    .await?;
+
+                Ok(Action::Opened(issue.number))
+            }
+            (false, Some(number)) => {
+                self.comment(&client, number, &render(&notification))
+                    .await?;
+
+                Ok(Action::Commented(number))
+            }
+        }
+    }
+
+    /// Return the number of the open issue with the configured title, if there is one
+    ///
+    /// Only issues with all of the configured labels are listed, and the pages of the list are
+    /// requested until the issue is found or the last page has been read.
+    async fn find_open_issue(&self, client: &Client) -> Result<Option<u64>, String> {
+        let per_page = PER_PAGE.to_string();
+        let labels = self.config.labels().join(",");
+
+        for page in 1.. {
+            let page = page.to_string();
+            let mut query = vec![
+                ("state", "open"),
+                ("per_page", per_page.as_str()),
+                ("page", page.as_str()),
+            ];
+
+            if !labels.is_empty() {
+                query.push(("labels", labels.as_str()));
+            }
+
+            let issues: Vec<RemoteIssue> = self
+                .send(client, Method::GET, "issues", &query, None)
+                .await?;
+            let last_page = issues.len() < PER_PAGE;
+
+            let open_issue = issues
+                .into_iter()
+                .find(|issue| issue.pull_request.is_none() && &issue.title == self.config.title());
+
+            if let Some(issue) = open_issue {
+                return Ok(Some(issue.number));
+            }
+
+            if last_page {
+                break;
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Comment on the issue with the given number
+    async fn comment(&self, client: &Client, number: u64, body: &str) -> Result<(), String> {
+        self.send::<Value>(
+            client,
+            Method::POST,
+            &format!("issues/{number}/comments"),
+            &[],
+            Some(json!({ "body": body })),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    /// Send a request to the given path below the repository and parse the response
+    async fn send<T: DeserializeOwned>(
+        &self,
+        client: &Client,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<Value>,
+    ) -> Result<T, String> {
+        let mut url = Url::parse(&format!(
+            "{}/repos/{}/{path}",
+            self.config.api_url().trim_end_matches('/'),
+            self.config.repository().as_deref().unwrap_or_default()
+        ))
+        .map_err(|error| error.to_string())?;
+
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+
+        let mut request = client
+            .request(method.clone(), url.clone())
+            .bearer_auth(self.config.token().as_deref().unwrap_or_default())
+            .header("Accept", "application/vnd.github+json");
+
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|error| error.without_url().to_string())?;
+
+        if !response.status().is_success() {
+            return Err(format!(
+                "issue tracker responded with HTTP {} to {method} {url}",
+                response.status()
+            ));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|error| error.without_url().to_string())
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Nothing => write!(f, "No issue to update"),
+            Action::Opened(number) => write!(f, "Opened issue #{number}"),
+            Action::Commented(number) => write!(f, "Commented on issue #{number}"),
+            Action::Closed(number) => write!(f, "Closed issue #{number}"),
+        }
+    }
+}
+
+/// Render the failing tests as the Markdown body of an issue or a comment
+fn render(notification: &Notification) -> String {
+    let mut body = format!(
+        "{} smoke test(s) failing in **{}**:\n\n",
+        notification.failures().len(),
+        notification.env()
+    );
+
+    for (path, message) in notification.failures() {
+        match message {
+            Some(message) => body.push_str(&format!("- `{path}`: {message}\n")),
+            None => body.push_str(&format!("- `{path}`\n")),
+        }
+    }
+
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use mockito::{Matcher, Server, ServerGuard};
+    use pretty_assertions::assert_eq;
+
+    use crate::test::{Tag, TestGroupResult, TestResult};
+    use crate::test_utils::*;
+
+    use super::*;
+
+    const BODY: &str = "1 smoke test(s) failing in **production**:\n\n\
+        - `crates.io > Database dumps > Fastly`: HTTP 404\n";
+
+    fn results(success: bool) -> Vec<TestSuiteResult> {
+        let result = TestResult::builder()
+            .name("Fastly")
+            .success(success)
+            .message((!success).then(|| "HTTP 404".to_string()))
+            .build();
+
+        vec![TestSuiteResult::builder()
+            .name("crates.io")
+            .results(vec![TestGroupResult::builder()
+                .name("Database dumps")
+                .results(vec![result])
+                .build()])
+            .build()]
+    }
+
+    fn tracker(server: &ServerGuard) -> IssueTracker {
+        IssueTracker::new(
+            Config::builder()
+                .api_url(server.url())
+                .repository("rust-lang/infra-team")
+                .token("secret")
+                .labels(vec!["A-smoke-tests".into()])
+                .build(),
+        )
+    }
+
+    async fn list_issues(server: &mut ServerGuard, issues: Value) -> mockito::Mock {
+        server
+            .mock("GET", "/repos/rust-lang/infra-team/issues")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("state".into(), "open".into()),
+                Matcher::UrlEncoded("per_page".into(), "100".into()),
+                Matcher::UrlEncoded("page".into(), "1".into()),
+                Matcher::UrlEncoded("labels".into(), "A-smoke-tests".into()),
+            ]))
+            .match_header("Authorization", "Bearer secret")
+            .with_body(issues.to_string())
+            .create_async()
+            .await
+    }
+
+    #[tokio::test]
+    async fn update_opens_issue() {
+        let mut server = Server::new_async().await;
+
+        let list = list_issues(
+            &mut server,
+            json!([{ "number": 3, "title": "Smoke tests are failing", "pull_request": {} }]),
+        )
+        .await;
+        let open = server
+            .mock("POST", "/repos/rust-lang/infra-team/issues")
+            .match_body(Matcher::Json(json!({
+                "title": "Smoke tests are failing",
+                "body": BODY,
+                "labels": ["A-smoke-tests"],
+            })))
+            .with_status(201)
+            .with_body(r#"{"number": 42, "title": "Smoke tests are failing"}"#)
+            .create_async()
+            .await;
+
+        let action = tracker(&server)
+            .update(&Environment::Production, &results(false))
+            .await;
+
+        assert_eq!(Ok(Action::Opened(42)), action);
+        list.assert_async().await;
+        open.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn update_comments_on_open_issue() {
+        let mut server = Server::new_async().await;
+
+        list_issues(
+            &mut server,
+            json!([{ "number": 42, "title": "Smoke tests are failing" }]),
+        )
+        .await;
+        let comment = server
+            .mock("POST", "/repos/rust-lang/infra-team/issues/42/comments")
+            .match_body(Matcher::Json(json!({ "body": BODY })))
+            .with_status(201)
+            .with_body("{}")
+            .create_async()
+            .await;
+
+        let action = tracker(&server)
+            .update(&Environment::Production, &results(false))
+            .await;
+
+        assert_eq!(Ok(Action::Commented(42)), action);
+        comment.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn update_closes_issue_after_recovery() {
+        let mut server = Server::new_async().await;
+
+        list_issues(
+            &mut server,
+            json!([{ "number": 42, "title": "Smoke tests are failing" }]),
+        )
+        .await;
+        let comment = server
+            .mock("POST", "/repos/rust-lang/infra-team/issues/42/comments")
+            .match_body(Matcher::Json(json!({
+                "body": "All smoke tests pass again in **production**."
+            })))
+            .with_status(201)
+            .with_body("{}")
+            .create_async()
+            .await;
+        let close = server
+            .mock("PATCH", "/repos/rust-lang/infra-team/issues/42")
+            .match_body(Matcher::Json(json!({ "state": "closed" })))
+            .with_body("{}")
+            .create_async()
+            .await;
+
+        let action = tracker(&server)
+            .update(&Environment::Production, &results(true))
+            .await;
+
+        assert_eq!(Ok(Action::Closed(42)), action);
+        comment.assert_async().await;
+        close.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn update_finds_issue_on_later_page() {
+        let mut server = Server::new_async().await;
+
+        let other: Vec<Value> = (1..=100)
+            .map(|number| json!({ "number": number, "title": "Another issue" }))
+            .collect();
+
+        list_issues(&mut server, Value::Array(other)).await;
+        server
+            .mock("GET", "/repos/rust-lang/infra-team/issues")
+            .match_query(Matcher::UrlEncoded("page".into(), "2".into()))
+            .with_body(json!([{ "number": 142, "title": "Smoke tests are failing" }]).to_string())
+            .create_async()
+            .await;
+        let comment = server
+            .mock("POST", "/repos/rust-lang/infra-team/issues/142/comments")
+            .with_status(201)
+            .with_body("{}")
+            .create_async()
+            .await;
+
+        let action = tracker(&server)
+            .update(&Environment::Production, &results(false))
+            .await;
+
+        assert_eq!(Ok(Action::Commented(142)), action);
+        comment.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn report_skips_filtered_runs() {
+        let mut server = Server::new_async().await;
+
+        let list = server
+            .mock("GET", Matcher::Any)
+            .expect(0)
+            .create_async()
+            .await;
+
+        let filter = Filter::builder().tags(vec![Tag::cdn("fastly")]).build();
+        let action = tracker(&server)
+            .report(&Environment::Production, &filter, &results(true))
+            .await;
+
+        assert_eq!(Action::Nothing, action);
+        list.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn report_ignores_errors() {
+        let mut server = Server::new_async().await;
+
+        server
+            .mock("GET", "/repos/rust-lang/infra-team/issues")
+            .match_query(Matcher::Any)
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let action = tracker(&server)
+            .report(
+                &Environment::Production,
+                &Filter::default(),
+                &results(false),
+            )
+            .await;
+
+        assert_eq!(Action::Nothing, action);
+    }
+
+    #[tokio::test]
+    async fn update_outside_production() {
+        let mut server = Server::new_async().await;
+
+        let list = server
+            .mock("GET", Matcher::Any)
+            .expect(0)
+            .create_async()
+            .await;
+
+        let action = tracker(&server)
+            .update(&Environment::Staging, &results(false))
+            .await;
+
+        assert_eq!(Ok(Action::Nothing), action);
+        list.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn update_without_failures_or_issue() {
+        let mut server = Server::new_async().await;
+
+        list_issues(&mut server, json!([])).await;
+
+        let action = tracker(&server)
+            .update(&Environment::Production, &results(true))
+            .await;
+
+        assert_eq!(Ok(Action::Nothing), action);
+    }
+
+    #[tokio::test]
+    async fn update_with_error_status() {
+        let mut server = Server::new_async().await;
+
+        server
+            .mock("GET", Matcher::Any)
+            .with_status(401)
+            .create_async()
+            .await;
+
+        let error = tracker(&server)
+            .update(&Environment::Production, &results(false))
+            .await
+            .unwrap_err();
+
+        assert!(error.starts_with("issue tracker responded with HTTP 401 Unauthorized to GET"));
+    }
+
+    #[tokio::test]
+    async fn update_without_token() {
+        let tracker =
+            IssueTracker::new(Config::builder().repository("rust-lang/infra-team").build());
+
+        assert!(!tracker.is_enabled());
+        assert_eq!(
+            Ok(Action::Nothing),
+            tracker
+                .update(&Environment::Production, &results(false))
+                .await
+        );
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<IssueTracker>();
+        assert_send::<Action>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<IssueTracker>();
+        assert_sync::<Action>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<IssueTracker>();
+        assert_unpin::<Action>();
+    }
+}
@@ -0,0 +1,47 @@
+//! Smoke tests for the infrastructure of the Rust project
+//!
+//! This library implements smoke tests for the cloud infrastructure of the Rust project. The tests
+//! confirm that the infrastructure is working as expected and that no regressions have been
+//! introduced.
+//!
+//! The library provides the building blocks to write and run smoke tests. Tests implement the
+//! [`Test`](test::Test) trait and are organized in [`TestGroup`](test::TestGroup)s, which in turn
+//! are organized in [`TestSuite`](test::TestSuite)s. The [`Runner`](runner::Runner) runs a set of
+//! test suites and collects their results. Other crates can implement their own test suites and
+//! register them with the runner alongside the suites that are shipped with this crate.
+
+// Make it easier for future generations to maintain this code base by documenting it.
+#![warn(clippy::missing_docs_in_private_items)]
+
+pub mod assertion;
+pub mod baseline;
+pub mod cdn;
+pub mod compare;
+pub mod config;
+pub mod diff;
+pub mod discovery;
+pub mod environment;
+pub mod history;
+pub mod http_client;
+pub(crate) mod http_server;
+pub mod issue;
+pub mod metrics;
+pub mod monitor;
+pub mod parity;
+pub mod runner;
+pub mod side_channels;
+pub(crate) mod sparse_index;
+pub mod statsd;
+pub mod status;
+pub mod test;
+pub mod wait;
+pub mod webhook;
+
+// Test suites
+pub mod crates;
+pub mod health;
+pub mod releases;
+pub mod rustup;
+
+#[cfg(test)]
+mod test_utils;
@@ -2,30 +2,45 @@
 //!
 //! This command-line application can be used to run smoke tests against the cloud infrastructure of
 //! the Rust project. The tests confirm that the infrastructure is working as expected and that no
-//! regressions have been introduced.
+//! regressions have been introduced. The tests and the modes that run them are implemented in the
+//! library of this crate, while the binary only parses the command-line arguments, dispatches to
+//! the selected mode, and prints its results.
 
 // Make it easier for future generations to maintain this code base by documenting it.
 #![warn(clippy::missing_docs_in_private_items)]
 
+use std::net::SocketAddr;
+use std::path::Path;
+
+use chrono::Utc;
 use clap::Parser;
-use tokio::task::JoinSet;
+use tokio::net::TcpListener;
 
-use crate::cli::Cli;
-use crate::crates::Crates;
-use crate::releases::Releases;
-use crate::rustup::Rustup;
-use crate::test::TestSuite;
+use infra_smoke_test::baseline::Baseline;
+use infra_smoke_test::cdn::{Endpoint, Service};
+use infra_smoke_test::compare::Comparison;
+use infra_smoke_test::config::{Config, ConfigError, Override};
+use infra_smoke_test::crates::Crates;
+use infra_smoke_test::diff::Diff;
+use infra_smoke_test::discovery::{self, Discovered};
+use infra_smoke_test::environment::Environment;
+use infra_smoke_test::health::Health;
+use infra_smoke_test::history::History;
+use infra_smoke_test::issue::{Action, IssueTracker};
+use infra_smoke_test::monitor::{self, Interval};
+use infra_smoke_test::parity::Parity;
+use infra_smoke_test::releases::Releases;
+use infra_smoke_test::runner::Runner;
+use infra_smoke_test::rustup::Rustup;
+use infra_smoke_test::side_channels::SideChannels;
+use infra_smoke_test::status;
+use infra_smoke_test::test::{Report, SavedRun, Style, TestPath, TestSuiteResult};
+use infra_smoke_test::wait::{self, WaitError};
+use infra_smoke_test::webhook::Webhook;
 
-mod assertion;
-mod cli;
-mod environment;
-mod http_client;
-mod test;
+use crate::cli::{Cli, Command};
 
-// Test suites
-mod crates;
-mod releases;
-mod rustup;
+mod cli;
 
 #[cfg(test)]
 mod test_utils;
@@ -34,27 +49,439 @@ mod test_utils;
 async fn main() {
     let cli = Cli::parse();
 
-    let tests: Vec<Box<dyn TestSuite>> = vec![
-        Box::new(Crates::new(cli.env())),
-        Box::new(Releases::new(cli.env())),
-        Box::new(Rustup::new(cli.env())),
-    ];
+    match cli.command() {
+        None | Some(Command::Run) => {
+            let config = load_config_or_exit(&cli, cli.env()).await;
+            let issues = IssueTracker::new(config.config().issue().clone());
+            run(&cli, runner(&cli, &config), &issues).await;
+        }
+        Some(Command::Explain { path }) => {
+            let config = load_config_or_exit(&cli, cli.env()).await;
+            explain(&runner(&cli, &config), path);
+        }
+        Some(Command::ValidateConfig) => {
+            let config = load_config_or_exit(&cli, cli.env()).await;
+            validate_config(&cli, config.config());
+        }
+        Some(Command::Compare { from, to }) => compare(&cli, from, to).await,
+        Some(Command::Monitor { interval, metrics }) => {
+            let config = load_config_or_exit(&cli, cli.env()).await;
+            let webhook = Webhook::new(config.config().webhook().clone());
+            monitor(&cli, runner(&cli, &config), &webhook, interval, *metrics).await;
+        }
+        Some(Command::Serve { address, interval }) => {
+            let config = load_config_or_exit(&cli, cli.env()).await;
+            serve(&cli, runner(&cli, &config), *address, interval).await;
+        }
+        Some(Command::Wait { timeout, interval }) => {
+            let config = load_config_or_exit(&cli, cli.env()).await;
+            wait(&cli, runner(&cli, &config), timeout, interval).await;
+        }
+        Some(Command::History { window }) => history(&cli, window),
+        Some(Command::Diff { old, new }) => diff(old, new),
+        Some(Command::Parity { site, paths }) => {
+            let config = load_config_or_exit(&cli, cli.env()).await;
+            parity(config.config(), site, paths).await;
+        }
+    }
+}
+
+/// Create a runner with the test suites for the given configuration
+///
+/// The health suite checks the static fixtures, while the other suites run against the discovered
+/// ones.
+fn runner(cli: &Cli, discovered: &Discovered) -> Runner {
+    let config = discovered.config();
+
+    let runner = Runner::new()
+        .suite(Crates::new(
+            config.crates().clone(),
+            endpoints(cli, config, Service::Crates),
+        ))
+        .suite(Releases::new(
+            config.releases().clone(),
+            endpoints(cli, config, Service::Releases),
+        ))
+        .suite(Rustup::new(config.rustup().clone()));
+
+    let runner = if includes_health(cli) {
+        runner.suite(Health::new(discovered.static_config()))
+    } else {
+        runner
+    };
+
+    runner.filter(cli.filter())
+}
+
+/// Check if the health suite runs in the selected mode
+///
+/// The health suite checks the fixtures rather than the infrastructure. A stale fixture should not
+/// block a deployment or alert anyone, so the modes that gate on the results, i.e. `wait`,
+/// `monitor`, `serve`, and `compare`, only run it when it is selected with `--tag config:health`.
+fn includes_health(cli: &Cli) -> bool {
+    matches!(
+        cli.command(),
+        None | Some(Command::Run) | Some(Command::Explain { .. })
+    ) || cli.filter().tags().contains(&Health::tag())
+}
+
+/// Return the endpoints that the checks for the given service are run against
+///
+/// The checks always run against the CDNs, and with `--origins` also against the origins behind
+/// them, so that a failure on a CDN can be compared with the result on its origin.
+fn endpoints(cli: &Cli, config: &Config, service: Service) -> Vec<Endpoint> {
+    let mut endpoints = config.endpoints(service);
+
+    if cli.origins() {
+        endpoints.extend(config.origin_endpoints(service));
+    }
+
+    endpoints
+}
+
+/// Load the configuration for the given environment, or exit if it is invalid
+async fn load_config_or_exit(cli: &Cli, env: &Environment) -> Discovered {
+    match load_config(cli, env).await {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!("Error: {error}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Load the configuration for the given environment
+///
+/// Overrides from environment variables are applied before the overrides from the command line, so
+/// that `--set` always wins. When the tests are run, fixtures are discovered at runtime and applied
+/// before both, so that explicit overrides take precedence over discovered fixtures.
+async fn load_config(cli: &Cli, env: &Environment) -> Result<Discovered, ConfigError> {
+    let mut overrides = Override::from_env()?;
+    overrides.extend(cli.overrides().iter().cloned());
+
+    if !matches!(
+        cli.command(),
+        None | Some(Command::Run)
+            | Some(Command::Compare { .. })
+            | Some(Command::Monitor { .. })
+            | Some(Command::Serve { .. })
+            | Some(Command::Wait { .. })
+    ) {
+        let config = Config::load(env, cli.config().as_deref(), &overrides)?;
+        return Ok(Discovered::without_discovery(config));
+    }
+
+    let discovered = discovery::load(env, cli.config().as_deref(), &overrides).await?;
+
+    for fixture in discovered.fixtures() {
+        match fixture.result() {
+            Ok(_) if cli.quiet() => {}
+            Ok(_) => eprintln!("{fixture}"),
+            Err(_) => eprintln!("Warning: {fixture}"),
+        }
+    }
+
+    Ok(discovered)
+}
+
+/// Run the smoke tests and print the results
+///
+/// The process exits with a non-zero exit code if any of the tests failed unexpectedly, or if the
+/// baseline contains expired entries.
+async fn run(cli: &Cli, runner: Runner, issues: &IssueTracker) {
+    let baseline = load_baseline_or_exit(cli);
+
+    let results = runner.baseline(baseline.clone()).run().await;
+
+    side_channels(cli).record(&results).await;
+
+    // The issue tracks the infrastructure, so stale fixtures in the configuration are left out
+    let tracked: Vec<TestSuiteResult> = results
+        .iter()
+        .filter(|suite| suite.name() != Health::NAME)
+        .cloned()
+        .collect();
+
+    let action = issues.report(cli.env(), &cli.filter(), &tracked).await;
+    if action != Action::Nothing && !cli.quiet() {
+        eprintln!("{action}");
+    }
+
+    let output = cli
+        .format()
+        .render(cli.env(), &results, style(cli), cli.quiet(), Utc::now());
+
+    match cli.output() {
+        Some(path) => {
+            if let Err(error) = std::fs::write(path, output) {
+                eprintln!("Error: failed to write {}: {error}", path.display());
+                std::process::exit(1);
+            }
+        }
+        None => print!("{output}"),
+    }
+
+    let expired = baseline.expired(chrono::Local::now().date_naive());
+
+    for entry in &expired {
+        eprintln!("Error: baseline entry has expired: {entry}");
+    }
 
-    let mut js = JoinSet::new();
-    for test in tests {
-        js.spawn(async move { test.run().await });
+    if !expired.is_empty() || results.iter().any(|result| !result.success()) {
+        std::process::exit(1);
     }
+}
 
-    let mut results = js.join_all().await;
+/// Return the style of the text report
+fn style(cli: &Cli) -> Style {
+    if cli.plain() {
+        Style::Plain
+    } else {
+        Style::Emoji
+    }
+}
+
+/// Return the side channels that the results of every run are recorded in
+fn side_channels(cli: &Cli) -> SideChannels {
+    SideChannels::builder()
+        .env(cli.env().clone())
+        .history(cli.history().clone())
+        .statsd(cli.statsd().clone())
+        .build()
+}
 
-    // Sort the results so that the output is deterministic
-    results.sort();
+/// Load the baseline of known failures, or exit if it can't be loaded
+///
+/// Without `--baseline`, the baseline is empty and every failure is unexpected.
+fn load_baseline_or_exit(cli: &Cli) -> Baseline {
+    let Some(path) = cli.baseline() else {
+        return Baseline::default();
+    };
 
-    for result in &results {
-        println!("{result}");
+    match Baseline::load(path) {
+        Ok(baseline) => baseline,
+        Err(error) => {
+            eprintln!("Error: {error}");
+            std::process::exit(1);
+        }
     }
+}
+
+/// Print the explanation for the test at the given path
+///
+/// The process exits with a non-zero exit code if no test exists at the given path.
+fn explain(runner: &Runner, path: &TestPath) {
+    match runner.explain(path) {
+        Some(explanation) => print!("{explanation}"),
+        None => {
+            eprintln!("No test found at '{path}'");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Check the configuration of the selected environment and print the problems
+///
+/// The process exits with a non-zero exit code if any problems were found.
+fn validate_config(cli: &Cli, config: &Config) {
+    let problems = config.lint(cli.env());
+
+    if problems.is_empty() {
+        println!("The configuration for '{}' is valid", cli.env());
+        return;
+    }
+
+    for problem in &problems {
+        println!("{problem}");
+    }
+
+    eprintln!(
+        "Found {} problem(s) in the configuration for '{}'",
+        problems.len(),
+        cli.env()
+    );
+    std::process::exit(1);
+}
+
+/// Run the smoke tests in two environments and print where their results differ
+///
+/// The process exits with a non-zero exit code if the environments behave differently.
+async fn compare(cli: &Cli, from: &Environment, to: &Environment) {
+    let from_config = load_config_or_exit(cli, from).await;
+    let to_config = load_config_or_exit(cli, to).await;
+
+    let from_runner = runner(cli, &from_config);
+    let to_runner = runner(cli, &to_config);
+
+    let (from_results, to_results) = tokio::join!(from_runner.run(), to_runner.run());
+
+    let comparison = Comparison::new(from.clone(), &from_results, to.clone(), &to_results);
+
+    print!("{comparison}");
+
+    if !comparison.is_empty() {
+        std::process::exit(1);
+    }
+}
+
+/// Run the smoke tests in a loop and print the tests whose result changed
+///
+/// If an address for the metrics is given, the results are also served as Prometheus metrics. The
+/// fixtures are discovered once when the monitor starts and are not refreshed between runs.
+async fn monitor(
+    cli: &Cli,
+    runner: Runner,
+    webhook: &Webhook,
+    interval: &Interval,
+    address: Option<SocketAddr>,
+) {
+    let runner = runner.baseline(load_baseline_or_exit(cli));
+
+    let listener = match address {
+        Some(address) => {
+            let listener = bind_or_exit(address, "metrics").await;
+            eprintln!("Serving metrics on http://{address}/metrics");
+            Some(listener)
+        }
+        None => None,
+    };
+
+    eprintln!("Running the smoke tests every {interval}");
+
+    monitor::run(&runner, &side_channels(cli), webhook, *interval, listener).await;
+}
+
+/// Run the smoke tests in a loop and serve the latest results on a status page
+///
+/// Like the monitor, the fixtures are only discovered once when the status page starts.
+async fn serve(cli: &Cli, runner: Runner, address: SocketAddr, interval: &Interval) {
+    let runner = runner.baseline(load_baseline_or_exit(cli));
+    let listener = bind_or_exit(address, "the status page").await;
+
+    eprintln!("Serving the status page on http://{address}/");
+    eprintln!("Running the smoke tests every {interval}");
+
+    status::run(&runner, &side_channels(cli), listener, *interval).await;
+}
+
+/// Bind a listener to the address, or exit if the address can't be bound
+async fn bind_or_exit(address: SocketAddr, what: &str) -> TcpListener {
+    match TcpListener::bind(address).await {
+        Ok(listener) => listener,
+        Err(error) => {
+            eprintln!("Error: failed to serve {what} on {address}: {error}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Rerun the smoke tests until all of them pass or the timeout expires
+///
+/// Once all tests pass, the results are printed and the process exits successfully. Otherwise, the
+/// failures of the last attempt are printed and the process exits with a non-zero exit code.
+async fn wait(cli: &Cli, runner: Runner, timeout: &Interval, interval: &Interval) {
+    let runner = runner.baseline(load_baseline_or_exit(cli));
+
+    match wait::wait(&runner, &side_channels(cli), *timeout, *interval).await {
+        Ok(passed) => {
+            let report = Report::builder()
+                .results(passed.results())
+                .style(style(cli))
+                .quiet(cli.quiet())
+                .build();
+
+            print!("{report}");
+            eprintln!("All tests passed after {} attempt(s)", passed.attempts());
+        }
+        Err(error) => {
+            if let WaitError::Failing { results, .. } = &error {
+                let report = Report::builder()
+                    .results(results)
+                    .style(style(cli))
+                    .quiet(true)
+                    .build();
+
+                print!("{report}");
+            }
+
+            eprintln!("Error: {error}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Print the statistics of the tests in the history file over the given time window
+///
+/// The process exits with a non-zero exit code if no history file was given or it can't be read.
+fn history(cli: &Cli, window: &Interval) {
+    let Some(path) = cli.history() else {
+        eprintln!("Error: the history subcommand requires --history FILE");
+        std::process::exit(1);
+    };
+
+    let history = match History::load(path) {
+        Ok(history) => history,
+        Err(error) => {
+            eprintln!("Error: {error}");
+            std::process::exit(1);
+        }
+    };
+
+    let statistics = history.statistics_within(cli.env(), window.duration(), Utc::now());
+
+    if statistics.is_empty() {
+        println!("No runs of '{}' in the last {window}", cli.env());
+    }
+
+    for test in &statistics {
+        print!("{test}");
+    }
+}
+
+/// Compare two saved runs and print their differences
+///
+/// The process exits with a non-zero exit code if a saved run can't be loaded, if the runs tested
+/// different environments, or if tests started failing in the new run.
+fn diff(old: &Path, new: &Path) {
+    let load = |path: &Path| match SavedRun::load(path) {
+        Ok(run) => run,
+        Err(error) => {
+            eprintln!("Error: {}: {error}", path.display());
+            std::process::exit(1);
+        }
+    };
+
+    let diff = match Diff::between(&load(old), &load(new)) {
+        Ok(diff) => diff,
+        Err(error) => {
+            eprintln!("Error: {error}, use the compare subcommand instead");
+            std::process::exit(1);
+        }
+    };
+
+    print!("{diff}");
+
+    if diff.has_regressions() {
+        std::process::exit(1);
+    }
+}
+
+/// Request the paths from every CDN of the site and print where their responses differ
+///
+/// The process exits with a non-zero exit code if a request failed or the responses differ in a way
+/// that is not allowed by the configuration.
+async fn parity(config: &Config, site: &str, paths: &[String]) {
+    let report = match Parity::new(config).check(site, paths).await {
+        Ok(report) => report,
+        Err(error) => {
+            eprintln!("Error: {error}");
+            std::process::exit(1);
+        }
+    };
+
+    print!("{report}");
 
-    if results.iter().any(|result| !result.success()) {
+    if !report.success() {
         std::process::exit(1);
     }
 }
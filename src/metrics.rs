@@ -0,0 +1,377 @@
+//! Prometheus metrics for the smoke tests
+//!
+//! When the smoke tests run continuously, their results can be exposed as Prometheus metrics so
+//! that dashboards and alerts can use them directly. The metrics are labelled with the suite, the
+//! group, and the name of the test, and with the environment that is tested:
+//!
+//! - `smoke_test_success` is a gauge that is `1` if the test passed in the last run, or failed as
+//!   expected by the baseline
+//! - `smoke_test_duration_seconds` is a histogram of the time the test took to run
+//! - `smoke_test_http_responses_total` counts the responses the test received by their status
+//! - `smoke_test_last_run_timestamp_seconds` is the time of the last run
+//!
+//! The metrics are served in the Prometheus text format on `/metrics` by a minimal HTTP server.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use tokio::net::TcpListener;
+
+use crate::environment::Environment;
+use crate::http_server::{self, Response};
+use crate::test::{TestPath, TestSuiteResult};
+
+/// The upper bounds of the buckets of the duration histogram, in seconds
+const DURATION_BUCKETS: [f64; 8] = [0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+/// The metrics of the smoke tests
+///
+/// The metrics accumulate the results of all runs. The gauges reflect the last run, while the
+/// histogram and the counters grow with every run.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Metrics {
+    /// The environment that is tested
+    env: Environment,
+
+    /// Whether each test passed in the last run
+    success: BTreeMap<TestPath, bool>,
+
+    /// The distribution of the durations of each test
+    durations: BTreeMap<TestPath, Histogram>,
+
+    /// The number of responses that each test received, by their status
+    responses: BTreeMap<(TestPath, u16), u64>,
+
+    /// The time of the last run
+    last_run: Option<DateTime<Utc>>,
+}
+
+/// A histogram of durations
+#[derive(Clone, PartialEq, Debug, Default)]
+struct Histogram {
+    /// The number of observations in each bucket of `DURATION_BUCKETS`, not cumulative
+    buckets: [u64; DURATION_BUCKETS.len()],
+
+    /// The sum of all observations in seconds
+    sum: f64,
+
+    /// The number of observations
+    count: u64,
+}
+
+impl Metrics {
+    /// Create empty metrics for the given environment
+    pub fn new(env: Environment) -> Self {
+        Self {
+            env,
+            success: BTreeMap::new(),
+            durations: BTreeMap::new(),
+            responses: BTreeMap::new(),
+            last_run: None,
+        }
+    }
+
+    /// Record the results of a run
+    pub fn record(&mut self, results: &[TestSuiteResult], at: DateTime<Utc>) {
+        for (path, result) in results.iter().flat_map(TestSuiteResult::test_results) {
+            self.success.insert(path.clone(), result.outcome().is_ok());
+
+            self.durations
+                .entry(path.clone())
+                .or_default()
+                .observe(result.duration().as_secs_f64());
+
+            for response in result.responses() {
+                *self
+                    .responses
+                    .entry((path.clone(), response.status()))
+                    .or_default() += 1;
+            }
+        }
+
+        self.last_run = Some(at);
+    }
+
+    /// Return the labels that identify a test
+    fn labels(&self, path: &TestPath) -> String {
+        format!(
+            "suite=\"{}\",group=\"{}\",test=\"{}\",environment=\"{}\"",
+            escape(path.suite()),
+            escape(path.group()),
+            escape(path.test()),
+            escape(self.env.name())
+        )
+    }
+}
+
+impl Display for Metrics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "# HELP smoke_test_success Whether the test passed in the last run"
+        )?;
+        writeln!(f, "# TYPE smoke_test_success gauge")?;
+        for (path, success) in &self.success {
+            writeln!(
+                f,
+                "smoke_test_success{{{}}} {}",
+                self.labels(path),
+                u8::from(*success)
+            )?;
+        }
+
+        writeln!(
+            f,
+            "# HELP smoke_test_duration_seconds The time the test took to run"
+        )?;
+        writeln!(f, "# TYPE smoke_test_duration_seconds histogram")?;
+        for (path, histogram) in &self.durations {
+            let labels = self.labels(path);
+            let mut cumulative = 0;
+
+            for (bound, count) in DURATION_BUCKETS.iter().zip(histogram.buckets) {
+                cumulative += count;
+                writeln!(
+                    f,
+                    "smoke_test_duration_seconds_bucket{{{labels},le=\"{bound}\"}} {cumulative}"
+                )?;
+            }
+
+            writeln!(
+                f,
+                "smoke_test_duration_seconds_bucket{{{labels},le=\"+Inf\"}} {}",
+                histogram.count
+            )?;
+            writeln!(
+                f,
+                "smoke_test_duration_seconds_sum{{{labels}}} {}",
+                histogram.sum
+            )?;
+            writeln!(
+                f,
+                "smoke_test_duration_seconds_count{{{labels}}} {}",
+                histogram.count
+            )?;
+        }
+
+        writeln!(
+            f,
+            "# HELP smoke_test_http_responses_total The responses the test received by status"
+        )?;
+        writeln!(f, "# TYPE smoke_test_http_responses_total counter")?;
+        for ((path, status), count) in &self.responses {
+            writeln!(
+                f,
+                "smoke_test_http_responses_total{{{},status=\"{status}\"}} {count}",
+                self.labels(path)
+            )?;
+        }
+
+        writeln!(
+            f,
+            "# HELP smoke_test_last_run_timestamp_seconds The time of the last run"
+        )?;
+        writeln!(f, "# TYPE smoke_test_last_run_timestamp_seconds gauge")?;
+        if let Some(last_run) = self.last_run {
+            writeln!(
+                f,
+                "smoke_test_last_run_timestamp_seconds{{environment=\"{}\"}} {}",
+                escape(self.env.name()),
+                last_run.timestamp()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Histogram {
+    /// Add an observation to the histogram
+    fn observe(&mut self, seconds: f64) {
+        if let Some(index) = DURATION_BUCKETS.iter().position(|bound| seconds <= *bound) {
+            self.buckets[index] += 1;
+        }
+
+        self.sum += seconds;
+        self.count += 1;
+    }
+}
+
+/// Serve the metrics on `/metrics` for as long as the future is polled
+///
+/// Every other path returns HTTP 404.
+pub async fn serve(listener: TcpListener, metrics: Arc<Mutex<Metrics>>) {
+    http_server::serve(listener, move |path| match path {
+        "/metrics" => {
+            let metrics = metrics.lock().expect("metrics lock is poisoned");
+            Response::ok(
+                "text/plain; version=0.0.4; charset=utf-8",
+                metrics.to_string(),
+            )
+        }
+        _ => Response::not_found(),
+    })
+    .await
+}
+
+/// Escape a label value for the Prometheus text format
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::TimeZone;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::test::{RecordedResponse, TestGroupResult, TestResult};
+    use crate::test_utils::*;
+
+    use super::*;
+
+    fn results(success: bool, duration: Duration) -> Vec<TestSuiteResult> {
+        let response = RecordedResponse::builder()
+            .method("HEAD")
+            .url("https://fastly-static.crates.io/db-dump.tar.gz")
+            .status(if success { 307 } else { 404 })
+            .headers(Vec::new())
+            .build();
+
+        let result = TestResult::builder()
+            .name("Fastly")
+            .success(success)
+            .responses(vec![response])
+            .duration(duration)
+            .build();
+
+        vec![TestSuiteResult::builder()
+            .name("crates.io")
+            .results(vec![TestGroupResult::builder()
+                .name("Database \"dumps\"")
+                .results(vec![result])
+                .build()])
+            .build()]
+    }
+
+    fn metrics() -> Metrics {
+        let mut metrics = Metrics::new(Environment::Staging);
+        let at = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+
+        metrics.record(&results(true, Duration::from_millis(300)), at);
+        metrics.record(&results(false, Duration::from_secs(60)), at);
+
+        metrics
+    }
+
+    #[test]
+    fn display_in_text_format() {
+        let labels =
+            r#"suite="crates.io",group="Database \"dumps\"",test="Fastly",environment="staging""#;
+
+        let expected = indoc! {r#"
+            # HELP smoke_test_success Whether the test passed in the last run
+            # TYPE smoke_test_success gauge
+            smoke_test_success{LABELS} 0
+            # HELP smoke_test_duration_seconds The time the test took to run
+            # TYPE smoke_test_duration_seconds histogram
+            smoke_test_duration_seconds_bucket{LABELS,le="0.1"} 0
+            smoke_test_duration_seconds_bucket{LABELS,le="0.25"} 0
+            smoke_test_duration_seconds_bucket{LABELS,le="0.5"} 1
+            smoke_test_duration_seconds_bucket{LABELS,le="1"} 1
+            smoke_test_duration_seconds_bucket{LABELS,le="2.5"} 1
+            smoke_test_duration_seconds_bucket{LABELS,le="5"} 1
+            smoke_test_duration_seconds_bucket{LABELS,le="10"} 1
+            smoke_test_duration_seconds_bucket{LABELS,le="30"} 1
+            smoke_test_duration_seconds_bucket{LABELS,le="+Inf"} 2
+            smoke_test_duration_seconds_sum{LABELS} 60.3
+            smoke_test_duration_seconds_count{LABELS} 2
+            # HELP smoke_test_http_responses_total The responses the test received by status
+            # TYPE smoke_test_http_responses_total counter
+            smoke_test_http_responses_total{LABELS,status="307"} 1
+            smoke_test_http_responses_total{LABELS,status="404"} 1
+            # HELP smoke_test_last_run_timestamp_seconds The time of the last run
+            # TYPE smoke_test_last_run_timestamp_seconds gauge
+            smoke_test_last_run_timestamp_seconds{environment="staging"} 1717243200
+        "#}
+        .replace("LABELS", labels);
+
+        assert_eq!(expected, metrics().to_string());
+    }
+
+    #[test]
+    fn record_expected_failure_as_success() {
+        let result = TestResult::builder()
+            .name("Fastly")
+            .success(false)
+            .expected_failure(Some("known outage".into()))
+            .build();
+        let results = vec![TestSuiteResult::builder()
+            .name("crates.io")
+            .results(vec![TestGroupResult::builder()
+                .name("Database dumps")
+                .results(vec![result])
+                .build()])
+            .build()];
+
+        let mut metrics = Metrics::new(Environment::Staging);
+        metrics.record(&results, Utc::now());
+
+        assert!(metrics.to_string().contains(
+            r#"smoke_test_success{suite="crates.io",group="Database dumps",test="Fastly",environment="staging"} 1"#
+        ));
+    }
+
+    #[tokio::test]
+    async fn serve_metrics() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        tokio::spawn(serve(listener, Arc::new(Mutex::new(metrics()))));
+
+        let response = reqwest::get(format!("http://{address}/metrics"))
+            .await
+            .unwrap();
+
+        assert_eq!(200, response.status().as_u16());
+        assert!(response
+            .text()
+            .await
+            .unwrap()
+            .contains("# TYPE smoke_test_success gauge"));
+    }
+
+    #[tokio::test]
+    async fn serve_not_found() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        tokio::spawn(serve(listener, Arc::new(Mutex::new(metrics()))));
+
+        let response = reqwest::get(format!("http://{address}/")).await.unwrap();
+
+        assert_eq!(404, response.status().as_u16());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Metrics>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Metrics>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Metrics>();
+    }
+}
@@ -0,0 +1,541 @@
+//! Continuous monitoring of the infrastructure
+//!
+//! The smoke tests are usually run once, e.g. after a deployment. In monitor mode, the same process
+//! runs the tests in a loop and keeps the last result of each test. Instead of printing the full
+//! report after every run, it only reports the tests whose result changed, i.e. that started or
+//! stopped failing. This turns the smoke tests into a lightweight synthetic-monitoring agent.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use getset::{CopyGetters, Getters};
+use tokio::net::TcpListener;
+use tokio::time::MissedTickBehavior;
+
+use crate::metrics::{self, Metrics};
+use crate::runner::Runner;
+use crate::side_channels::{warn_on_error, SideChannels};
+use crate::test::{TestPath, TestSuiteResult};
+use crate::webhook::{Notification, Webhook};
+
+/// The longest interval that can be parsed, which is about ten years
+const MAX_INTERVAL_SECONDS: u64 = 3650 * 86400;
+
+/// A span of time, e.g. the time between two runs of the smoke tests
+///
+/// The interval is written as a number followed by a unit, which is either `s` for seconds, `m` for
+/// minutes, `h` for hours, or `d` for days, e.g. `5m`. Intervals longer than `3650d` are rejected,
+/// so that adding them to the current time can't overflow.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters)]
+pub struct Interval {
+    /// The duration of the interval
+    #[getset(get_copy = "pub")]
+    duration: Duration,
+}
+
+/// The state of the monitored tests
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters)]
+pub struct Monitor {
+    /// The last state of each test that has been run, keyed by its path
+    #[getset(get = "pub")]
+    states: BTreeMap<TestPath, State>,
+}
+
+/// The last known state of a test
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters)]
+pub struct State {
+    /// Whether the test passed in the last run
+    #[getset(get_copy = "pub")]
+    success: bool,
+
+    /// When the test entered its current state
+    #[getset(get_copy = "pub")]
+    since: DateTime<Utc>,
+}
+
+/// A change in the result of a test
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, Getters)]
+pub struct Transition {
+    /// The path of the test
+    #[getset(get = "pub")]
+    path: TestPath,
+
+    /// Whether the test passed before, or `None` if it has not been run before
+    #[getset(get_copy = "pub")]
+    from: Option<bool>,
+
+    /// Whether the test passed in the latest run
+    #[getset(get_copy = "pub")]
+    to: bool,
+
+    /// When the change was observed
+    #[getset(get_copy = "pub")]
+    at: DateTime<Utc>,
+
+    /// The message of the latest result, which usually explains why the test failed
+    #[getset(get = "pub")]
+    message: Option<String>,
+}
+
+impl Interval {
+    /// Create a new interval with the given duration
+    pub fn new(duration: Duration) -> Self {
+        Self { duration }
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let seconds = self.duration.as_secs();
+
+        if seconds.is_multiple_of(86400) {
+            write!(f, "{}d", seconds / 86400)
+        } else if seconds.is_multiple_of(3600) {
+            write!(f, "{}h", seconds / 3600)
+        } else if seconds.is_multiple_of(60) {
+            write!(f, "{}m", seconds / 60)
+        } else {
+            write!(f, "{seconds}s")
+        }
+    }
+}
+
+impl FromStr for Interval {
+    type Err = String;
+
+    fn from_str(interval: &str) -> Result<Self, Self::Err> {
+        let error =
+            || format!("expected an interval like '30s', '5m', '1h', or '7d', got '{interval}'");
+
+        let split = interval.len().saturating_sub(1);
+        let (value, unit) = interval.split_at_checked(split).ok_or_else(error)?;
+
+        let value: u64 = value.parse().map_err(|_| error())?;
+        let seconds = match unit {
+            "s" => Some(value),
+            "m" => value.checked_mul(60),
+            "h" => value.checked_mul(3600),
+            "d" => value.checked_mul(86400),
+            _ => None,
+        };
+
+        match seconds {
+            Some(seconds) if seconds > MAX_INTERVAL_SECONDS => Err(format!(
+                "expected an interval of at most 3650d, got '{interval}'"
+            )),
+            Some(seconds) if seconds > 0 => Ok(Self::new(Duration::from_secs(seconds))),
+            _ => Err(error()),
+        }
+    }
+}
+
+impl Monitor {
+    /// Create a new monitor without any known states
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the results of a run and return the tests whose result changed
+    ///
+    /// Tests that are seen for the first time are only reported if they fail, so that the first run
+    /// reports the tests that are already failing without listing every passing test. Failures that
+    /// the baseline expects are not reported.
+    pub fn update(&mut self, results: &[TestSuiteResult], at: DateTime<Utc>) -> Vec<Transition> {
+        let mut transitions = Vec::new();
+
+        for (path, result) in results.iter().flat_map(TestSuiteResult::test_results) {
+            let from = self.states.get(&path).map(State::success);
+            let to = result.outcome().is_ok();
+
+            if from == Some(to) {
+                continue;
+            }
+
+            self.states.insert(
+                path.clone(),
+                State {
+                    success: to,
+                    since: at,
+                },
+            );
+
+            if from.is_none() && to {
+                continue;
+            }
+
+            transitions.push(Transition {
+                path,
+                from,
+                to,
+                at,
+                message: result.message().clone(),
+            });
+        }
+
+        transitions
+    }
+
+    /// Run the smoke tests once, report the results, and return the tests whose result changed
+    ///
+    /// The changes are posted to the webhook, and the results are recorded in the side channels and
+    /// the metrics.
+    pub async fn check(
+        &mut self,
+        runner: &Runner,
+        side_channels: &SideChannels,
+        webhook: &Webhook,
+        metrics: &Mutex<Metrics>,
+    ) -> Vec<Transition> {
+        let results = runner.run().await;
+        let now = Utc::now();
+
+        let transitions = self.update(&results, now);
+
+        let notification =
+            Notification::from_transitions(side_channels.env().clone(), &transitions);
+        warn_on_error(
+            webhook.notify(&notification).await,
+            "failed to send notification",
+        );
+
+        side_channels.record(&results).await;
+
+        metrics
+            .lock()
+            .expect("metrics lock is poisoned")
+            .record(&results, now);
+
+        transitions
+    }
+}
+
+/// Run the smoke tests in a loop and print the tests whose result changed
+///
+/// The first run prints the tests that are already failing. After that, only the tests that start
+/// or stop failing are printed and posted to the webhook. Failures that the baseline of the runner
+/// expects are not reported. If a listener is given, the results are also served on it as
+/// Prometheus metrics, alongside the loop rather than in a separate task, so that the metrics can't
+/// stop without the loop noticing. The loop runs until the process is stopped.
+pub async fn run(
+    runner: &Runner,
+    side_channels: &SideChannels,
+    webhook: &Webhook,
+    interval: Interval,
+    listener: Option<TcpListener>,
+) {
+    let mut monitor = Monitor::new();
+    let metrics = Arc::new(Mutex::new(Metrics::new(side_channels.env().clone())));
+
+    let checks = async {
+        let mut ticker = tokio::time::interval(interval.duration());
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+
+            for transition in monitor
+                .check(runner, side_channels, webhook, &metrics)
+                .await
+            {
+                println!("{transition}");
+            }
+        }
+    };
+
+    match listener {
+        Some(listener) => {
+            tokio::join!(metrics::serve(listener, metrics.clone()), checks);
+        }
+        None => checks.await,
+    }
+}
+
+impl Display for Transition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}: {} -> {}",
+            self.at.to_rfc3339_opts(SecondsFormat::Secs, true),
+            self.path,
+            describe_result(self.from),
+            describe_result(Some(self.to))
+        )?;
+
+        if let Some(message) = &self.message {
+            write!(f, " ({message})")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Describe the result of a test
+fn describe_result(success: Option<bool>) -> &'static str {
+    match success {
+        Some(true) => "passed",
+        Some(false) => "failed",
+        None => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone};
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::baseline::Baseline;
+    use crate::environment::Environment;
+    use crate::test::{TestGroupResult, TestResult, ToggleSuite};
+    use crate::test_utils::*;
+
+    use super::*;
+
+    fn results(fastly: bool, cloudfront: bool) -> Vec<TestSuiteResult> {
+        let result = |name: &str, success: bool| {
+            TestResult::builder()
+                .name(name)
+                .success(success)
+                .message((!success).then(|| "HTTP 404".to_string()))
+                .build()
+        };
+
+        vec![TestSuiteResult::builder()
+            .name("crates.io")
+            .results(vec![TestGroupResult::builder()
+                .name("Database dumps")
+                .results(vec![
+                    result("CloudFront", cloudfront),
+                    result("Fastly", fastly),
+                ])
+                .build()])
+            .build()]
+    }
+
+    fn time(minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 6, 1, 12, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn interval_from_str() {
+        assert_eq!(
+            Duration::from_secs(30),
+            "30s".parse::<Interval>().unwrap().duration()
+        );
+        assert_eq!(
+            Duration::from_secs(300),
+            "5m".parse::<Interval>().unwrap().duration()
+        );
+        assert_eq!(
+            Duration::from_secs(7200),
+            "2h".parse::<Interval>().unwrap().duration()
+        );
+        assert_eq!(
+            Duration::from_secs(604800),
+            "7d".parse::<Interval>().unwrap().duration()
+        );
+    }
+
+    #[test]
+    fn interval_from_str_with_invalid_value() {
+        for interval in ["", "5", "m", "0m", "5w", "-5m", "5 m"] {
+            assert_eq!(
+                Err(format!(
+                    "expected an interval like '30s', '5m', '1h', or '7d', got '{interval}'"
+                )),
+                interval.parse::<Interval>()
+            );
+        }
+    }
+
+    #[test]
+    fn interval_from_str_with_too_long_interval() {
+        assert_eq!(
+            Duration::from_secs(3650 * 86400),
+            "3650d".parse::<Interval>().unwrap().duration()
+        );
+
+        for interval in ["3651d", "18446744073709551615s"] {
+            assert_eq!(
+                Err(format!(
+                    "expected an interval of at most 3650d, got '{interval}'"
+                )),
+                interval.parse::<Interval>()
+            );
+        }
+    }
+
+    #[test]
+    fn interval_display() {
+        assert_eq!("90s", Interval::new(Duration::from_secs(90)).to_string());
+        assert_eq!("5m", Interval::new(Duration::from_secs(300)).to_string());
+        assert_eq!("1h", Interval::new(Duration::from_secs(3600)).to_string());
+        assert_eq!("7d", Interval::new(Duration::from_secs(604800)).to_string());
+    }
+
+    #[test]
+    fn update_reports_failures_on_first_run() {
+        let mut monitor = Monitor::new();
+
+        let transitions = monitor.update(&results(false, true), time(0));
+
+        assert_eq!(
+            vec!["2024-06-01T12:00:00Z crates.io > Database dumps > Fastly: unknown -> failed (HTTP 404)"],
+            transitions
+                .iter()
+                .map(Transition::to_string)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(2, monitor.states().len());
+    }
+
+    #[test]
+    fn update_reports_only_transitions() {
+        let mut monitor = Monitor::new();
+
+        monitor.update(&results(true, true), time(0));
+        let failing = monitor.update(&results(false, true), time(5));
+        let still_failing = monitor.update(&results(false, true), time(10));
+        let recovered = monitor.update(&results(true, true), time(15));
+
+        assert_eq!(
+            vec!["2024-06-01T12:05:00Z crates.io > Database dumps > Fastly: passed -> failed (HTTP 404)"],
+            failing
+                .iter()
+                .map(Transition::to_string)
+                .collect::<Vec<_>>()
+        );
+        assert!(still_failing.is_empty());
+        assert_eq!(
+            vec!["2024-06-01T12:15:00Z crates.io > Database dumps > Fastly: failed -> passed"],
+            recovered
+                .iter()
+                .map(Transition::to_string)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn update_ignores_expected_failures() {
+        let mut monitor = Monitor::new();
+
+        let mut results = results(false, true);
+        Baseline::from_str(indoc! {r#"
+            [[expected_failure]]
+            path = "crates.io > Database dumps > Fastly"
+            reason = "Fastly is not configured yet"
+            expires = 2024-06-30
+        "#})
+        .unwrap()
+        .apply(&mut results, NaiveDate::from_ymd_opt(2024, 6, 1).unwrap());
+
+        assert!(monitor.update(&results, time(0)).is_empty());
+    }
+
+    #[test]
+    fn update_keeps_time_of_last_transition() {
+        let mut monitor = Monitor::new();
+
+        monitor.update(&results(true, true), time(0));
+        monitor.update(&results(false, true), time(5));
+        monitor.update(&results(false, true), time(10));
+
+        let path: TestPath = "crates.io > Database dumps > Fastly".parse().unwrap();
+        let state = &monitor.states()[&path];
+
+        assert!(!state.success());
+        assert_eq!(time(5), state.since());
+    }
+
+    #[tokio::test]
+    async fn check_reports_transitions_and_records_metrics() {
+        let suite = ToggleSuite::new(true);
+        let runner = Runner::new().suite(suite.clone());
+        let side_channels = SideChannels::builder().env(Environment::Staging).build();
+        let metrics = Mutex::new(Metrics::new(Environment::Staging));
+        let mut monitor = Monitor::new();
+
+        let first = monitor
+            .check(&runner, &side_channels, &Webhook::default(), &metrics)
+            .await;
+
+        suite.set_success(false);
+        let second = monitor
+            .check(&runner, &side_channels, &Webhook::default(), &metrics)
+            .await;
+
+        assert!(first.is_empty());
+        assert_eq!(
+            vec![(Some(true), false)],
+            second
+                .iter()
+                .map(|transition| (transition.from(), transition.to()))
+                .collect::<Vec<_>>()
+        );
+        assert!(metrics
+            .lock()
+            .unwrap()
+            .to_string()
+            .contains("test=\"Fastly\",environment=\"staging\"} 0"));
+    }
+
+    #[tokio::test]
+    async fn run_serves_metrics_of_latest_run() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/metrics", listener.local_addr().unwrap());
+
+        let monitor = tokio::spawn(async move {
+            let runner = Runner::new().suite(ToggleSuite::new(true));
+            let side_channels = SideChannels::builder().env(Environment::Staging).build();
+            let interval = Interval::new(Duration::from_secs(60));
+
+            run(
+                &runner,
+                &side_channels,
+                &Webhook::default(),
+                interval,
+                Some(listener),
+            )
+            .await;
+        });
+
+        let mut metrics = String::new();
+        for _ in 0..50 {
+            metrics = reqwest::get(&url).await.unwrap().text().await.unwrap();
+
+            if metrics.contains("smoke_test_success{") {
+                break;
+            }
+
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        monitor.abort();
+
+        assert!(metrics.contains("test=\"Fastly\",environment=\"staging\"} 1"));
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Interval>();
+        assert_send::<Monitor>();
+        assert_send::<Transition>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Interval>();
+        assert_sync::<Monitor>();
+        assert_sync::<Transition>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Interval>();
+        assert_unpin::<Monitor>();
+        assert_unpin::<Transition>();
+    }
+}
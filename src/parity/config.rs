@@ -0,0 +1,203 @@
+//! Configuration for the parity checks between the CDNs
+
+use std::fmt::{Display, Formatter};
+
+use getset::Getters;
+use serde::Deserialize;
+#[cfg(test)]
+use typed_builder::TypedBuilder;
+
+use crate::config::{Validate, Validator};
+
+/// Configuration for the parity checks between the CDNs
+///
+/// The configuration is read from the `[parity]` table of the configuration file. It contains the
+/// differences between the CDNs that are expected and therefore don't fail the check. The CDNs that
+/// serve each site are taken from `[cdns]`.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(test, derive(TypedBuilder))]
+pub struct Config {
+    /// The differences between the CDNs that are expected
+    #[serde(default)]
+    #[cfg_attr(test, builder(default))]
+    #[getset(get = "pub")]
+    allowed_differences: Vec<AllowedDifference>,
+}
+
+/// A difference between the CDNs that is expected
+///
+/// ```toml
+/// [[parity.allowed_differences]]
+/// path = "/crates/"
+/// field = "cache-control"
+/// reason = "Fastly sets a longer max-age for crate files"
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Getters, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(test, derive(TypedBuilder))]
+pub struct AllowedDifference {
+    /// The prefix of the paths for which the difference is expected, e.g. `/crates/`
+    #[cfg_attr(test, builder(setter(into)))]
+    #[getset(get = "pub")]
+    path: String,
+
+    /// The field that is expected to differ
+    #[getset(get = "pub")]
+    field: Field,
+
+    /// The reason why the difference is expected
+    #[cfg_attr(test, builder(setter(into)))]
+    #[getset(get = "pub")]
+    reason: String,
+}
+
+/// A field of the response that is compared between the CDNs
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Field {
+    /// The status code of the response
+    Status,
+
+    /// The `Content-Type` header
+    ContentType,
+
+    /// The `Cache-Control` header
+    CacheControl,
+
+    /// The `Access-Control-Allow-Origin` header
+    AccessControlAllowOrigin,
+
+    /// The `Content-Length` header
+    ContentLength,
+
+    /// The SHA-256 digest of the body
+    Body,
+}
+
+impl Field {
+    /// All fields in the order in which they are compared
+    pub const ALL: [Field; 6] = [
+        Field::Status,
+        Field::ContentType,
+        Field::CacheControl,
+        Field::AccessControlAllowOrigin,
+        Field::ContentLength,
+        Field::Body,
+    ];
+
+    /// Return the name of the field, which is the lowercase name of the header for header fields
+    pub fn name(&self) -> &'static str {
+        match self {
+            Field::Status => "status",
+            Field::ContentType => "content-type",
+            Field::CacheControl => "cache-control",
+            Field::AccessControlAllowOrigin => "access-control-allow-origin",
+            Field::ContentLength => "content-length",
+            Field::Body => "body",
+        }
+    }
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl AllowedDifference {
+    /// Check if the difference is expected for the field at the given path
+    pub fn matches(&self, path: &str, field: Field) -> bool {
+        self.field == field && path.starts_with(&self.path)
+    }
+}
+
+impl Validate for Config {
+    fn validate(&self, validator: &mut Validator) {
+        for (index, allowed) in self.allowed_differences.iter().enumerate() {
+            if !allowed.path.starts_with('/') {
+                validator.problem(
+                    &format!("allowed_differences.{index}.path"),
+                    format!(
+                        "expected a path that starts with '/', got '{}'",
+                        allowed.path
+                    ),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::*;
+
+    use super::*;
+
+    #[test]
+    fn deserialize_allowed_difference() {
+        let config: Config = toml::from_str(
+            r#"
+            [[allowed_differences]]
+            path = "/crates/"
+            field = "cache-control"
+            reason = "reason"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            Field::CacheControl,
+            *config.allowed_differences()[0].field()
+        );
+    }
+
+    #[test]
+    fn allowed_difference_matches_prefix() {
+        let allowed = AllowedDifference::builder()
+            .path("/crates/")
+            .field(Field::CacheControl)
+            .reason("reason")
+            .build();
+
+        assert!(allowed.matches("/crates/axum/axum-0.6.10.crate", Field::CacheControl));
+        assert!(!allowed.matches("/crates/axum/axum-0.6.10.crate", Field::Body));
+        assert!(!allowed.matches("/db-dump.tar.gz", Field::CacheControl));
+    }
+
+    #[test]
+    fn validate_path_of_allowed_difference() {
+        let config = Config::builder()
+            .allowed_differences(vec![AllowedDifference::builder()
+                .path("crates/")
+                .field(Field::Body)
+                .reason("reason")
+                .build()])
+            .build();
+
+        let mut validator = Validator::new();
+        config.validate(&mut validator);
+
+        assert_eq!("allowed_differences.0.path", validator.problems()[0].key());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Config>();
+        assert_send::<Field>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Config>();
+        assert_sync::<Field>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Config>();
+        assert_unpin::<Field>();
+    }
+}
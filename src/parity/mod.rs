@@ -0,0 +1,570 @@
+//! Parity checks between CloudFront and Fastly
+//!
+//! Most sites are served by both CloudFront and Fastly, and both CDNs are expected to return the
+//! same response for the same path. Instead of writing a test for each CDN, the parity check takes
+//! any path, requests it from every CDN in `[cdns]` that serves the site, and compares the status,
+//! the key headers, and a digest of the body. Differences that are expected can be listed in the
+//! configuration, so that they don't fail the check.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use getset::Getters;
+use reqwest::redirect::Policy;
+use reqwest::Client;
+use sha2::{Digest, Sha256};
+use tokio::task::JoinSet;
+
+use crate::cdn::{Endpoint, Service};
+use crate::http_client::{custom_http_client, send};
+
+pub use self::config::{AllowedDifference, Config, Field};
+
+mod config;
+
+/// Parity checks between CloudFront and Fastly
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct Parity {
+    /// Configuration for the parity checks
+    config: Config,
+
+    /// The CDNs that serve each site, keyed by the name of the site, e.g. `crates`
+    sites: BTreeMap<String, Vec<Endpoint>>,
+}
+
+/// The result of the parity checks for a site
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Getters)]
+pub struct ParityReport {
+    /// The name of the site, e.g. `crates`
+    #[getset(get = "pub")]
+    site: String,
+
+    /// The names of the CDNs that were compared, e.g. `CloudFront` and `Fastly`
+    #[getset(get = "pub")]
+    cdns: Vec<String>,
+
+    /// The results for the individual paths, in the order in which they were given
+    #[getset(get = "pub")]
+    results: Vec<PathParity>,
+}
+
+/// The result of the parity check for a single path
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Getters)]
+pub struct PathParity {
+    /// The path that was requested, e.g. `/db-dump.tar.gz`
+    #[getset(get = "pub")]
+    path: String,
+
+    /// The fields that differ between the CDNs, or the error if a request failed
+    #[getset(get = "pub")]
+    result: Result<Vec<FieldDifference>, String>,
+}
+
+/// A field whose value differs between the CDNs
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Getters)]
+pub struct FieldDifference {
+    /// The field that differs
+    #[getset(get = "pub")]
+    field: Field,
+
+    /// The values of the field, paired with the names of the CDNs that returned them
+    #[getset(get = "pub")]
+    values: Vec<(String, String)>,
+
+    /// The reason why the difference is expected, if it is in the allow-list
+    #[getset(get = "pub")]
+    allowed: Option<String>,
+}
+
+impl Parity {
+    /// Create a new parity check for the sites of the CDNs in the configuration
+    pub fn new(config: &crate::config::Config) -> Self {
+        let sites = Service::ALL
+            .into_iter()
+            .map(|service| (service.name().to_string(), config.endpoints(service)))
+            .collect();
+
+        Self {
+            config: config.parity().clone(),
+            sites,
+        }
+    }
+
+    /// Request the paths from every CDN of the site and compare the responses
+    ///
+    /// Redirects are not followed, so that the responses of the CDNs themselves are compared.
+    /// Returns an error if the site is unknown or served by fewer than two CDNs.
+    pub async fn check(&self, site: &str, paths: &[String]) -> Result<ParityReport, String> {
+        let endpoints = self.sites.get(site).ok_or_else(|| {
+            let sites: Vec<&str> = self.sites.keys().map(String::as_str).collect();
+            format!(
+                "unknown site '{site}', expected one of: {}",
+                sites.join(", ")
+            )
+        })?;
+
+        if endpoints.len() < 2 {
+            return Err(format!(
+                "site '{site}' is served by fewer than two CDNs, so there is nothing to compare"
+            ));
+        }
+
+        let client = custom_http_client()
+            // Don't follow redirects, we want to compare the responses of the CDNs
+            .redirect(Policy::none())
+            .build()
+            .map_err(|error| error.to_string())?;
+
+        let mut results = Vec::with_capacity(paths.len());
+
+        for path in paths {
+            let mut js = JoinSet::new();
+
+            for (index, endpoint) in endpoints.iter().enumerate() {
+                let client = client.clone();
+                let url = endpoint.url().clone();
+                let path = path.clone();
+
+                js.spawn(async move { (index, snapshot(&client, &url, &path).await) });
+            }
+
+            let mut responses = js.join_all().await;
+            responses.sort_by_key(|(index, _)| *index);
+
+            let snapshots = endpoints
+                .iter()
+                .zip(responses)
+                .map(|(endpoint, (_, snapshot))| {
+                    snapshot
+                        .map(|snapshot| (endpoint.name().clone(), snapshot))
+                        .map_err(|error| format!("{}: {error}", endpoint.name()))
+                })
+                .collect::<Result<Vec<_>, String>>();
+
+            results.push(PathParity {
+                path: path.clone(),
+                result: snapshots.map(|snapshots| self.compare(path, &snapshots)),
+            });
+        }
+
+        Ok(ParityReport {
+            site: site.into(),
+            cdns: endpoints
+                .iter()
+                .map(|endpoint| endpoint.name().clone())
+                .collect(),
+            results,
+        })
+    }
+
+    /// Compare the snapshots of the responses and return the fields that differ
+    ///
+    /// Each snapshot is paired with the name of the CDN that returned it.
+    fn compare(
+        &self,
+        path: &str,
+        snapshots: &[(String, BTreeMap<Field, String>)],
+    ) -> Vec<FieldDifference> {
+        Field::ALL
+            .into_iter()
+            .filter(|field| {
+                snapshots
+                    .windows(2)
+                    .any(|pair| pair[0].1.get(field) != pair[1].1.get(field))
+            })
+            .map(|field| FieldDifference {
+                field,
+                values: snapshots
+                    .iter()
+                    .map(|(cdn, snapshot)| {
+                        (
+                            cdn.clone(),
+                            snapshot.get(&field).cloned().unwrap_or_default(),
+                        )
+                    })
+                    .collect(),
+                allowed: self
+                    .config
+                    .allowed_differences()
+                    .iter()
+                    .find(|allowed| allowed.matches(path, field))
+                    .map(|allowed| allowed.reason().clone()),
+            })
+            .collect()
+    }
+}
+
+impl ParityReport {
+    /// Check if the CDNs returned the same responses, apart from the expected differences
+    pub fn success(&self) -> bool {
+        self.results.iter().all(PathParity::success)
+    }
+}
+
+impl Display for ParityReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for result in &self.results {
+            write!(f, "{result}")?;
+        }
+
+        let unexpected = self
+            .results
+            .iter()
+            .filter(|result| !result.success())
+            .count();
+
+        if unexpected == 0 {
+            writeln!(f, "{} match for '{}'", self.cdns.join(" and "), self.site)
+        } else {
+            writeln!(
+                f,
+                "Found unexpected differences for {unexpected} path(s) of '{}'",
+                self.site
+            )
+        }
+    }
+}
+
+impl PathParity {
+    /// Check if the request succeeded and all differences are expected
+    pub fn success(&self) -> bool {
+        self.result.as_ref().is_ok_and(|differences| {
+            differences
+                .iter()
+                .all(|difference| difference.allowed.is_some())
+        })
+    }
+}
+
+impl Display for PathParity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.path)?;
+
+        match &self.result {
+            Err(error) => writeln!(f, "  Request failed: {error}"),
+            Ok(differences) if differences.is_empty() => writeln!(f, "  No differences"),
+            Ok(differences) => {
+                for difference in differences {
+                    writeln!(f, "  {difference}")?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Display for FieldDifference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let values: Vec<String> = self
+            .values
+            .iter()
+            .map(|(cdn, value)| format!("'{value}' on {cdn}"))
+            .collect();
+
+        write!(f, "{}: {}", self.field, values.join(", "))?;
+
+        if let Some(reason) = &self.allowed {
+            write!(f, " (allowed: {reason})")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Request the path and return the values of the compared fields
+///
+/// The base URL and the path are joined with exactly one slash, so that a base URL with a trailing
+/// slash doesn't request a different path. Headers that are missing from the response have the value `missing`, and the body is compared by
+/// its SHA-256 digest.
+async fn snapshot(
+    client: &Client,
+    base_url: &str,
+    path: &str,
+) -> Result<BTreeMap<Field, String>, String> {
+    let url = format!(
+        "{}/{}",
+        base_url.trim_end_matches('/'),
+        path.trim_start_matches('/')
+    );
+
+    let mut response = send(client.get(url))
+        .await
+        .map_err(|error| error.to_string())?;
+
+    let mut fields = BTreeMap::new();
+    fields.insert(Field::Status, response.status().as_u16().to_string());
+
+    for field in Field::ALL {
+        if matches!(field, Field::Status | Field::Body) {
+            continue;
+        }
+
+        let value = response
+            .headers()
+            .get(field.name())
+            .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+            .unwrap_or_else(|| "missing".into());
+
+        fields.insert(field, value);
+    }
+
+    // Artifacts can be large, so the body is hashed while it is streamed instead of buffered
+    let mut hasher = Sha256::new();
+    while let Some(chunk) = response.chunk().await.map_err(|error| error.to_string())? {
+        hasher.update(&chunk);
+    }
+
+    let digest: String = hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+
+    fields.insert(Field::Body, format!("sha256:{digest}"));
+
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use mockito::ServerGuard;
+    use pretty_assertions::assert_eq;
+
+    use crate::environment::Environment;
+    use crate::test::Tag;
+    use crate::test_utils::*;
+
+    use super::*;
+
+    async fn setup(
+        allowed_differences: Vec<AllowedDifference>,
+    ) -> (ServerGuard, ServerGuard, Parity) {
+        let cloudfront = mockito::Server::new_async().await;
+        let fastly = mockito::Server::new_async().await;
+
+        let endpoints = vec![
+            Endpoint::new("CloudFront", Tag::cdn("cloudfront"), cloudfront.url()),
+            Endpoint::new("Fastly", Tag::cdn("fastly"), fastly.url()),
+        ];
+
+        let parity = Parity {
+            config: Config::builder()
+                .allowed_differences(allowed_differences)
+                .build(),
+            sites: BTreeMap::from([("crates".to_string(), endpoints)]),
+        };
+
+        (cloudfront, fastly, parity)
+    }
+
+    #[tokio::test]
+    async fn check_without_differences() {
+        let (mut cloudfront, mut fastly, parity) = setup(Vec::new()).await;
+
+        for server in [&mut cloudfront, &mut fastly] {
+            server
+                .mock("GET", "/db-dump.tar.gz")
+                .with_header("Content-Type", "application/gzip")
+                .with_body("dump")
+                .create_async()
+                .await;
+        }
+
+        let report = parity
+            .check("crates", &["/db-dump.tar.gz".into()])
+            .await
+            .unwrap();
+
+        assert!(report.success());
+        assert_eq!(
+            "/db-dump.tar.gz\n  No differences\nCloudFront and Fastly match for 'crates'\n",
+            report.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn check_with_trailing_slash_in_base_url() {
+        let mut cloudfront = mockito::Server::new_async().await;
+        let mut fastly = mockito::Server::new_async().await;
+
+        let endpoints = vec![
+            Endpoint::new(
+                "CloudFront",
+                Tag::cdn("cloudfront"),
+                format!("{}/", cloudfront.url()),
+            ),
+            Endpoint::new("Fastly", Tag::cdn("fastly"), format!("{}/", fastly.url())),
+        ];
+
+        let parity = Parity {
+            config: Config::default(),
+            sites: BTreeMap::from([("crates".to_string(), endpoints)]),
+        };
+
+        let mut mocks = Vec::new();
+        for server in [&mut cloudfront, &mut fastly] {
+            let mock = server
+                .mock("GET", "/db-dump.tar.gz")
+                .with_body("dump")
+                .create_async()
+                .await;
+
+            mocks.push(mock);
+        }
+
+        let report = parity
+            .check("crates", &["/db-dump.tar.gz".into()])
+            .await
+            .unwrap();
+
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+        assert!(report.success());
+    }
+
+    #[tokio::test]
+    async fn check_with_differences() {
+        let allowed = AllowedDifference::builder()
+            .path("/")
+            .field(Field::CacheControl)
+            .reason("different TTLs")
+            .build();
+        let (mut cloudfront, mut fastly, parity) = setup(vec![allowed]).await;
+
+        cloudfront
+            .mock("GET", "/db-dump.tar.gz")
+            .with_header("Cache-Control", "max-age=60")
+            .with_body("dump")
+            .create_async()
+            .await;
+        fastly
+            .mock("GET", "/db-dump.tar.gz")
+            .with_status(404)
+            .with_header("Cache-Control", "max-age=3600")
+            .with_body("dump")
+            .create_async()
+            .await;
+
+        let report = parity
+            .check("crates", &["/db-dump.tar.gz".into()])
+            .await
+            .unwrap();
+
+        let expected = indoc! {"
+            /db-dump.tar.gz
+              status: '200' on CloudFront, '404' on Fastly
+              cache-control: 'max-age=60' on CloudFront, 'max-age=3600' on Fastly (allowed: different TTLs)
+            Found unexpected differences for 1 path(s) of 'crates'
+        "};
+
+        assert!(!report.success());
+        assert_eq!(expected, report.to_string());
+    }
+
+    #[tokio::test]
+    async fn check_with_different_body() {
+        let (mut cloudfront, mut fastly, parity) = setup(Vec::new()).await;
+
+        cloudfront
+            .mock("GET", "/index.html")
+            .with_body("a")
+            .create_async()
+            .await;
+        fastly
+            .mock("GET", "/index.html")
+            .with_body("b")
+            .create_async()
+            .await;
+
+        let report = parity
+            .check("crates", &["/index.html".into()])
+            .await
+            .unwrap();
+
+        let differences = report.results()[0].result().as_ref().unwrap();
+
+        assert_eq!(1, differences.len());
+        assert_eq!(&Field::Body, differences[0].field());
+    }
+
+    #[tokio::test]
+    async fn check_with_unknown_site() {
+        let (_cloudfront, _fastly, parity) = setup(Vec::new()).await;
+
+        let error = parity.check("releases", &["/".into()]).await.unwrap_err();
+
+        assert_eq!("unknown site 'releases', expected one of: crates", error);
+    }
+
+    #[tokio::test]
+    async fn check_with_single_cdn() {
+        let parity = Parity {
+            config: Config::default(),
+            sites: BTreeMap::from([(
+                "crates".to_string(),
+                vec![Endpoint::new(
+                    "Fastly",
+                    Tag::cdn("fastly"),
+                    "https://fastly-static.crates.io",
+                )],
+            )]),
+        };
+
+        let error = parity.check("crates", &["/".into()]).await.unwrap_err();
+
+        assert_eq!(
+            "site 'crates' is served by fewer than two CDNs, so there is nothing to compare",
+            error
+        );
+    }
+
+    #[test]
+    fn new_with_sites_from_cdns() {
+        let config = crate::config::Config::load(
+            &Environment::Production,
+            None,
+            &[crate::config::Override::new(
+                "cdns.fastly.crates_url",
+                "https://fastly-static.example.com",
+            )],
+        )
+        .unwrap();
+
+        let parity = Parity::new(&config);
+
+        let urls: Vec<&str> = parity.sites["crates"]
+            .iter()
+            .map(|endpoint| endpoint.url().as_str())
+            .collect();
+
+        assert_eq!(
+            vec![
+                "https://cloudfront-static.crates.io",
+                "https://fastly-static.example.com"
+            ],
+            urls
+        );
+        assert_eq!(2, parity.sites["releases"].len());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Parity>();
+        assert_send::<ParityReport>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Parity>();
+        assert_sync::<ParityReport>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Parity>();
+        assert_unpin::<ParityReport>();
+    }
+}
@@ -0,0 +1,53 @@
+//! Configuration for the Rust releases test suite
+
+use getset::Getters;
+use serde::Deserialize;
+
+use crate::config::{Validate, Validator};
+
+use super::{doc_router, list_files};
+
+/// Configuration for the Rust releases test suite
+///
+/// The configuration combines the configurations of the test groups in the suite. It is read from
+/// the `[releases]` table of the configuration file.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Configuration to test the doc-router
+    #[getset(get = "pub(crate)")]
+    doc_router: doc_router::Config,
+
+    /// Configuration to test `list-files.html`
+    #[getset(get = "pub(crate)")]
+    list_files: list_files::Config,
+}
+
+impl Validate for Config {
+    fn validate(&self, validator: &mut Validator) {
+        validator.nested("doc_router", &self.doc_router);
+        validator.nested("list_files", &self.list_files);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::*;
+
+    use super::*;
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Config>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Config>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Config>();
+    }
+}
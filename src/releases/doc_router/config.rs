@@ -1,33 +1,28 @@
 //! Configuration to test the doc-router
 
 use getset::Getters;
+use serde::Deserialize;
 #[cfg(test)]
 use typed_builder::TypedBuilder;
 
-use crate::environment::Environment;
+use crate::config::{Validate, Validator};
 
 /// Configuration to test the doc-router
 ///
 /// The tests for the doc-router call various endpoints and make assertions about the responses.
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters)]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters, Deserialize)]
+#[serde(deny_unknown_fields)]
 #[cfg_attr(test, derive(TypedBuilder))]
 pub struct Config {
     /// The URL for the CloudFront CDN
+    #[serde(deserialize_with = "crate::config::deserialize_url")]
     #[getset(get = "pub")]
     cloudfront_url: String,
 }
 
-impl Config {
-    /// Return the configuration for the given environment
-    pub fn for_env(env: Environment) -> Self {
-        match env {
-            Environment::Staging => Self {
-                cloudfront_url: "https://dev-doc.rust-lang.org".into(),
-            },
-            Environment::Production => Self {
-                cloudfront_url: "https://doc.rust-lang.org".into(),
-            },
-        }
+impl Validate for Config {
+    fn validate(&self, validator: &mut Validator) {
+        validator.url("cloudfront_url", &self.cloudfront_url);
     }
 }
 
@@ -6,10 +6,7 @@
 use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 
-use async_trait::async_trait;
-
-use crate::environment::Environment;
-use crate::test::{Test, TestGroup, TestGroupResult};
+use crate::test::{Metadata, Tag, Test, TestGroup};
 
 pub use self::config::Config;
 use self::redirect_minor_versions::RedirectMinorVersions;
@@ -34,9 +31,9 @@ pub struct DocRouter {
 
 impl DocRouter {
     /// Create a new instance of the test group
-    pub fn new(env: Environment) -> Self {
+    pub fn new(config: Config) -> Self {
         Self {
-            config: Arc::new(Config::for_env(env)),
+            config: Arc::new(config),
         }
     }
 }
@@ -47,22 +44,34 @@ impl Display for DocRouter {
     }
 }
 
-#[async_trait]
 impl TestGroup for DocRouter {
-    async fn run(&self) -> TestGroupResult {
-        let tests: Vec<Box<dyn Test>> = vec![
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn metadata(&self) -> Metadata {
+        Metadata::builder()
+            .description(
+                "The doc-router serves the documentation of Rust's standard library and \
+                redirects paths that would otherwise return HTTP 404 Not Found.",
+            )
+            .team("infra")
+            .build()
+    }
+
+    fn tags(&self) -> Vec<Tag> {
+        vec![Tag::cdn("cloudfront"), Tag::service("doc-router")]
+    }
+
+    fn tests(&self) -> Vec<Box<dyn Test>> {
+        vec![
             Box::new(RedirectMinorVersions::new(self.config.clone())),
             Box::new(RedirectRoot::new(self.config.clone())),
-        ];
-
-        let mut results = Vec::new();
-        for test in tests {
-            results.push(test.run().await);
-        }
+        ]
+    }
 
-        TestGroupResult::builder()
-            .name(NAME)
-            .results(results)
-            .build()
+    fn concurrent(&self) -> bool {
+        // The redirects are checked one after another, as before the group used the default run
+        false
     }
 }
@@ -12,9 +12,9 @@ use async_trait::async_trait;
 use reqwest::redirect::Policy;
 
 use crate::assertion::{is_redirect, redirects_to};
-use crate::http_client::custom_http_client;
+use crate::http_client::{custom_http_client, send};
 use crate::releases::doc_router::Config;
-use crate::test::{Test, TestResult};
+use crate::test::{Metadata, Test, TestResult};
 
 /// The name of the test
 const NAME: &str = "Redirect minor versions";
@@ -45,10 +45,23 @@ impl Display for RedirectMinorVersions {
 
 #[async_trait]
 impl Test for RedirectMinorVersions {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn metadata(&self) -> Metadata {
+        Metadata::builder()
+            .description(
+                "Request the documentation for the minor version `1.65` and expect a \
+                redirect to `1.65.0`.",
+            )
+            .build()
+    }
+
     async fn run(&self) -> TestResult {
         let test_result = TestResult::builder().name(NAME).success(false);
 
-        let response = match custom_http_client()
+        let request = custom_http_client()
             // Don't follow the redirect, we want to check the redirect location
             .redirect(Policy::none())
             .build()
@@ -56,10 +69,9 @@ impl Test for RedirectMinorVersions {
             .get(format!(
                 "{}/1.65/std/boxed/struct.Box.html",
                 self.config.cloudfront_url()
-            ))
-            .send()
-            .await
-        {
+            ));
+
+        let response = match send(request).await {
             Ok(response) => response,
             Err(error) => {
                 return test_result.message(Some(error.to_string())).build();
@@ -9,9 +9,9 @@ use async_trait::async_trait;
 use reqwest::redirect::Policy;
 
 use crate::assertion::{is_redirect, redirects_to};
-use crate::http_client::custom_http_client;
+use crate::http_client::{custom_http_client, send};
 use crate::releases::doc_router::Config;
-use crate::test::{Test, TestResult};
+use crate::test::{Metadata, Test, TestResult};
 
 /// The name of the test
 const NAME: &str = "Redirect root path";
@@ -40,18 +40,27 @@ impl Display for RedirectRoot {
 
 #[async_trait]
 impl Test for RedirectRoot {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn metadata(&self) -> Metadata {
+        Metadata::builder()
+            .description("Request the root path and expect a redirect to `/stable/`.")
+            .build()
+    }
+
     async fn run(&self) -> TestResult {
         let test_result = TestResult::builder().name(NAME).success(false);
 
-        let response = match custom_http_client()
+        let request = custom_http_client()
             // Don't follow the redirect, we want to check the redirect location
             .redirect(Policy::none())
             .build()
             .expect("failed to build reqwest client")
-            .get(format!("{}/", self.config.cloudfront_url()))
-            .send()
-            .await
-        {
+            .get(format!("{}/", self.config.cloudfront_url()));
+
+        let response = match send(request).await {
             Ok(response) => response,
             Err(error) => {
                 return test_result.message(Some(error.to_string())).build();
@@ -1,55 +1,50 @@
 //! Configuration to test `list-files.html`
 
 use getset::Getters;
+use serde::Deserialize;
 #[cfg(test)]
 use typed_builder::TypedBuilder;
 
-use crate::environment::Environment;
+use crate::config::{Validate, Validator};
 
 /// Configuration to test `list-files.html`
 ///
 /// The smoke tests request the `index.html` file in a release folder and expect it to be list the
-/// files in the folder.
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters)]
+/// files in the folder. The URLs of the CDNs are configured in the `[cdns]` table.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters, Deserialize)]
+#[serde(deny_unknown_fields)]
 #[cfg_attr(test, derive(TypedBuilder))]
 pub struct Config {
-    /// The URL for the CloudFront CDN
-    #[getset(get = "pub")]
-    cloudfront_url: String,
-
-    /// The URL for the Fastly CDN
-    #[getset(get = "pub")]
-    fastly_url: String,
-
     /// The date of the release to check
     #[getset(get = "pub")]
     release: String,
 }
 
-impl Config {
-    /// Return the configuration for the given environment
-    pub fn for_env(env: Environment) -> Self {
-        match env {
-            Environment::Staging => Self {
-                cloudfront_url: "https://cloudfront-dev-static.rust-lang.org".into(),
-                fastly_url: "https://fastly-dev-static.rust-lang.org".into(),
-                release: "2024-09-03".into(),
-            },
-            Environment::Production => Self {
-                cloudfront_url: "https://cloudfront-static.rust-lang.org".into(),
-                fastly_url: "https://fastly-static.rust-lang.org".into(),
-                release: "2024-09-11".into(),
-            },
-        }
+impl Validate for Config {
+    fn validate(&self, validator: &mut Validator) {
+        validator.date("release", &self.release);
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use pretty_assertions::assert_eq;
+
     use crate::test_utils::*;
 
     use super::*;
 
+    #[test]
+    fn validate() {
+        let config = Config::builder().release("09/03/2024".into()).build();
+
+        let mut validator = Validator::new();
+        config.validate(&mut validator);
+
+        assert_eq!(1, validator.problems().len());
+        assert_eq!("release", validator.problems()[0].key());
+    }
+
     #[test]
     fn trait_send() {
         assert_send::<Config>();
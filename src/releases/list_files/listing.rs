@@ -0,0 +1,133 @@
+//! Test that a CDN lists the files in a release
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::cdn::Endpoint;
+use crate::releases::list_files::request_index_and_expect_loading_files;
+use crate::test::{Metadata, Tag, Test, TestResult};
+
+use super::config::Config;
+
+/// Test that a CDN lists the files in a release
+///
+/// This test requests the `index.html` in a release folder and expects it to be rewritten to
+/// `list-files.html`. The test is named after the CDN, and is instantiated for every CDN that serves
+/// Rust releases.
+pub struct Listing {
+    /// The CDN to request
+    endpoint: Endpoint,
+
+    /// Configuration for this test
+    config: Arc<Config>,
+}
+
+impl Listing {
+    /// Create a new instance of the test for the given CDN
+    pub fn new(endpoint: Endpoint, config: Arc<Config>) -> Self {
+        Self { endpoint, config }
+    }
+}
+
+#[async_trait]
+impl Test for Listing {
+    fn name(&self) -> &str {
+        self.endpoint.name()
+    }
+
+    fn metadata(&self) -> Metadata {
+        Metadata::builder()
+            .description(
+                "Request `index.html` in a release folder from the CDN and expect the \
+                contents of `list-files.html`.",
+            )
+            .build()
+    }
+
+    fn tags(&self) -> Vec<Tag> {
+        vec![self.endpoint.tag().clone()]
+    }
+
+    async fn run(&self) -> TestResult {
+        request_index_and_expect_loading_files(
+            self.endpoint.name(),
+            self.endpoint.url(),
+            self.config.release(),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use mockito::ServerGuard;
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::*;
+
+    use super::*;
+
+    async fn setup() -> (ServerGuard, Listing) {
+        let server = mockito::Server::new_async().await;
+
+        let endpoint = Endpoint::new("Fastly", Tag::cdn("fastly"), server.url());
+        let config = Config::builder().release("2024-09-11".into()).build();
+
+        (server, Listing::new(endpoint, Arc::new(config)))
+    }
+
+    #[tokio::test]
+    async fn succeeds_when_listing_files() {
+        let (mut server, listing) = setup().await;
+
+        let mock = server
+            .mock("GET", "/dist/2024-09-11/index.html")
+            .with_status(200)
+            .with_body(indoc! {r#"
+                Loading directory contents...
+            "#})
+            .create();
+
+        let result = listing.run().await;
+
+        // Assert that the mock was called
+        mock.assert();
+
+        assert_eq!(&None, result.message());
+        assert!(result.success());
+    }
+
+    #[tokio::test]
+    async fn fails_otherwise() {
+        let (mut server, listing) = setup().await;
+
+        let mock = server
+            .mock("GET", "/dist/2024-09-11/index.html")
+            .with_status(404)
+            .create();
+
+        let result = listing.run().await;
+
+        // Assert that the mock was called
+        mock.assert();
+
+        assert!(!result.success());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Listing>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Listing>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Listing>();
+    }
+}
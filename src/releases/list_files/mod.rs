@@ -6,19 +6,17 @@
 use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 
-use async_trait::async_trait;
-use tokio::task::JoinSet;
+use reqwest::Client;
 
-use crate::environment::Environment;
-use crate::test::{Test, TestGroup, TestGroupResult, TestResult};
+use crate::cdn::Endpoint;
+use crate::http_client::send;
+use crate::test::{Metadata, Tag, Test, TestGroup, TestResult};
 
-pub use self::cloudfront::CloudFront;
 pub use self::config::Config;
-pub use self::fastly::Fastly;
+pub use self::listing::Listing;
 
-mod cloudfront;
 mod config;
-mod fastly;
+mod listing;
 
 /// The name of the test group
 const NAME: &str = "list-files.html";
@@ -31,13 +29,17 @@ const NAME: &str = "list-files.html";
 pub struct ListFiles {
     /// Configuration for the test group
     config: Arc<Config>,
+
+    /// The CDNs that serve Rust releases
+    cdns: Vec<Endpoint>,
 }
 
 impl ListFiles {
-    /// Create a new instance of the test group
-    pub fn new(env: Environment) -> Self {
+    /// Create a new instance of the test group that checks the given CDNs
+    pub fn new(config: Config, cdns: Vec<Endpoint>) -> Self {
         Self {
-            config: Arc::new(Config::for_env(env)),
+            config: Arc::new(config),
+            cdns,
         }
     }
 }
@@ -48,26 +50,33 @@ impl Display for ListFiles {
     }
 }
 
-#[async_trait]
 impl TestGroup for ListFiles {
-    async fn run(&self) -> TestGroupResult {
-        let tests: Vec<Box<dyn Test>> = vec![
-            Box::new(CloudFront::new(self.config.clone())),
-            Box::new(Fastly::new(self.config.clone())),
-        ];
-
-        let mut js = JoinSet::new();
-        for test in tests {
-            js.spawn(async move { test.run().await });
-        }
-
-        let results = js.join_all().await;
+    fn name(&self) -> &'static str {
+        NAME
+    }
 
-        TestGroupResult::builder()
-            .name(NAME)
-            .results(results)
+    fn metadata(&self) -> Metadata {
+        Metadata::builder()
+            .description(
+                "Requests for `index.html` in a release folder must be rewritten to \
+                `list-files.html`, which lists the files in the release.",
+            )
+            .team("infra")
             .build()
     }
+
+    fn tags(&self) -> Vec<Tag> {
+        vec![Tag::service("static.rust-lang.org")]
+    }
+
+    fn tests(&self) -> Vec<Box<dyn Test>> {
+        // The rewrite to `list-files.html` is configured on the CDNs, so the origins are not checked
+        self.cdns
+            .iter()
+            .filter(|cdn| !cdn.is_origin())
+            .map(|cdn| Box::new(Listing::new(cdn.clone(), self.config.clone())) as Box<dyn Test>)
+            .collect()
+    }
 }
 
 /// Request a releases `index.html` and assert that it starts loading the files of the release
@@ -77,13 +86,15 @@ impl TestGroup for ListFiles {
 /// a list of all files in the release. The test asserts that the CDN is correctly rewriting the
 /// path and returning the script.
 async fn request_index_and_expect_loading_files(
-    name: &'static str,
+    name: &str,
     base_url: &str,
     release: &str,
 ) -> TestResult {
     let test_result = TestResult::builder().name(name).success(false);
 
-    let response = match reqwest::get(format!("{base_url}/dist/{release}/index.html")).await {
+    let url = format!("{base_url}/dist/{release}/index.html");
+
+    let response = match send(Client::new().get(url)).await {
         Ok(response) => response,
         Err(error) => {
             return test_result.message(Some(error.to_string())).build();
@@ -114,9 +125,28 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn tests_skip_origins() {
+        let list_files = ListFiles::new(
+            Config::default(),
+            vec![
+                Endpoint::new("Fastly", Tag::cdn("fastly"), "https://fastly"),
+                Endpoint::new("S3", Tag::origin("s3"), "https://s3"),
+            ],
+        );
+
+        let names: Vec<String> = list_files
+            .tests()
+            .iter()
+            .map(|test| test.name().to_string())
+            .collect();
+
+        assert_eq!(vec!["Fastly"], names);
+    }
+
     #[test]
     fn trait_display() {
-        let list_files = ListFiles::new(Environment::Staging);
+        let list_files = ListFiles::new(Config::default(), Vec::new());
 
         assert_eq!("list-files.html", list_files.to_string());
     }
@@ -2,15 +2,15 @@
 
 use std::fmt::{Display, Formatter};
 
-use async_trait::async_trait;
-use tokio::task::JoinSet;
-
-use crate::environment::Environment;
+use crate::cdn::Endpoint;
 use crate::releases::doc_router::DocRouter;
 use crate::releases::list_files::ListFiles;
 use crate::releases::rustup_sh::RustupSh;
-use crate::test::{TestGroup, TestSuite, TestSuiteResult};
+use crate::test::{TestGroup, TestSuite};
+
+pub use self::config::Config;
 
+mod config;
 mod doc_router;
 mod list_files;
 mod rustup_sh;
@@ -19,16 +19,19 @@ mod rustup_sh;
 ///
 /// This test suite implements the smoke tests for the Rust releases. The tests confirm that the CDN
 /// for releases is working as expected and that no regressions have been introduced.
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
 pub struct Releases {
-    /// The environment to run the tests in
-    env: Environment,
+    /// The configuration for the test suite
+    config: Config,
+
+    /// The CDNs that serve Rust releases
+    cdns: Vec<Endpoint>,
 }
 
 impl Releases {
-    /// Creates a new instance of the test suite
-    pub fn new(env: Environment) -> Self {
-        Self { env }
+    /// Creates a new instance of the test suite that checks the given CDNs
+    pub fn new(config: Config, cdns: Vec<Endpoint>) -> Self {
+        Self { config, cdns }
     }
 }
 
@@ -38,26 +41,20 @@ impl Display for Releases {
     }
 }
 
-#[async_trait]
 impl TestSuite for Releases {
-    async fn run(&self) -> TestSuiteResult {
-        let groups: Vec<Box<dyn TestGroup>> = vec![
-            Box::new(DocRouter::new(self.env)),
-            Box::new(ListFiles::new(self.env)),
-            Box::new(RustupSh::new(self.env)),
-        ];
-
-        let mut js = JoinSet::new();
-        for group in groups {
-            js.spawn(async move { group.run().await });
-        }
-
-        let results = js.join_all().await;
-
-        TestSuiteResult::builder()
-            .name("Rust releases")
-            .results(results)
-            .build()
+    fn name(&self) -> &'static str {
+        "Rust releases"
+    }
+
+    fn groups(&self) -> Vec<Box<dyn TestGroup>> {
+        vec![
+            Box::new(DocRouter::new(self.config.doc_router().clone())),
+            Box::new(ListFiles::new(
+                self.config.list_files().clone(),
+                self.cdns.clone(),
+            )),
+            Box::new(RustupSh::new(self.cdns.clone())),
+        ]
     }
 }
 
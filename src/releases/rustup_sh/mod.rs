@@ -3,24 +3,17 @@
 //! This module test that the deprecated `/rustup.sh` path is redirected to `sh.rustup.rs`.
 
 use std::fmt::{Display, Formatter};
-use std::sync::Arc;
 
-use async_trait::async_trait;
 use reqwest::redirect::Policy;
-use tokio::task::JoinSet;
 
 use crate::assertion::{is_redirect, redirects_to};
-use crate::environment::Environment;
-use crate::http_client::custom_http_client;
-use crate::test::{Test, TestGroup, TestGroupResult, TestResult};
+use crate::cdn::Endpoint;
+use crate::http_client::{custom_http_client, send};
+use crate::test::{Metadata, Tag, Test, TestGroup, TestResult};
 
-pub use self::cloudfront::CloudFront;
-pub use self::config::Config;
-pub use self::fastly::Fastly;
+pub use self::redirect::Redirect;
 
-mod cloudfront;
-mod config;
-mod fastly;
+mod redirect;
 
 /// The name of the test group
 const NAME: &str = "rustup.sh";
@@ -31,16 +24,14 @@ const NAME: &str = "rustup.sh";
 /// contains further instructions for users who might not be following the redirect.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
 pub struct RustupSh {
-    /// Configuration for the test group
-    config: Arc<Config>,
+    /// The CDNs that serve Rust releases
+    cdns: Vec<Endpoint>,
 }
 
 impl RustupSh {
-    /// Create a new instance of the test group
-    pub fn new(env: Environment) -> Self {
-        Self {
-            config: Arc::new(Config::for_env(env)),
-        }
+    /// Create a new instance of the test group that checks the given CDNs
+    pub fn new(cdns: Vec<Endpoint>) -> Self {
+        Self { cdns }
     }
 }
 
@@ -50,26 +41,33 @@ impl Display for RustupSh {
     }
 }
 
-#[async_trait]
 impl TestGroup for RustupSh {
-    async fn run(&self) -> TestGroupResult {
-        let tests: Vec<Box<dyn Test>> = vec![
-            Box::new(CloudFront::new(self.config.clone())),
-            Box::new(Fastly::new(self.config.clone())),
-        ];
-
-        let mut js = JoinSet::new();
-        for test in tests {
-            js.spawn(async move { test.run().await });
-        }
-
-        let results = js.join_all().await;
+    fn name(&self) -> &'static str {
+        NAME
+    }
 
-        TestGroupResult::builder()
-            .name(NAME)
-            .results(results)
+    fn metadata(&self) -> Metadata {
+        Metadata::builder()
+            .description(
+                "The deprecated path `/rustup.sh` must redirect to `sh.rustup.rs` and \
+                explain the new location to users who don't follow redirects.",
+            )
+            .team("infra")
             .build()
     }
+
+    fn tags(&self) -> Vec<Tag> {
+        vec![Tag::service("static.rust-lang.org")]
+    }
+
+    fn tests(&self) -> Vec<Box<dyn Test>> {
+        // The redirect is configured on the CDNs, so the origins are not checked
+        self.cdns
+            .iter()
+            .filter(|cdn| !cdn.is_origin())
+            .map(|cdn| Box::new(Redirect::new(cdn.clone())) as Box<dyn Test>)
+            .collect()
+    }
 }
 
 /// Request `/rustup.sh` and assert the correct response
@@ -77,18 +75,17 @@ impl TestGroup for RustupSh {
 /// The path `/rustup.sh` is deprecated and is being redirected to `sh.rustup.rs`. This function
 /// requests the path from the given base URL and asserts that the response is both a redirect and
 /// contains instructions for users who don't follow redirects.
-async fn request_rustup_and_expect_redirect(name: &'static str, base_url: &str) -> TestResult {
+async fn request_rustup_and_expect_redirect(name: &str, base_url: &str) -> TestResult {
     let test_result = TestResult::builder().name(name).success(false);
 
-    let response = match custom_http_client()
+    let request = custom_http_client()
         // Don't follow the redirect, we want to check the redirect location
         .redirect(Policy::none())
         .build()
         .expect("failed to build reqwest client")
-        .get(format!("{base_url}/rustup.sh"))
-        .send()
-        .await
-    {
+        .get(format!("{base_url}/rustup.sh"));
+
+    let response = match send(request).await {
         Ok(response) => response,
         Err(error) => {
             return test_result.message(Some(error.to_string())).build();
@@ -131,9 +128,25 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn tests_skip_origins() {
+        let rustup_sh = RustupSh::new(vec![
+            Endpoint::new("Fastly", Tag::cdn("fastly"), "https://fastly"),
+            Endpoint::new("S3", Tag::origin("s3"), "https://s3"),
+        ]);
+
+        let names: Vec<String> = rustup_sh
+            .tests()
+            .iter()
+            .map(|test| test.name().to_string())
+            .collect();
+
+        assert_eq!(vec!["Fastly"], names);
+    }
+
     #[test]
     fn trait_display() {
-        let rustup_sh = RustupSh::new(Environment::Staging);
+        let rustup_sh = RustupSh::new(Vec::new());
 
         assert_eq!("rustup.sh", rustup_sh.to_string());
     }
@@ -0,0 +1,146 @@
+//! Test that a CDN redirects `/rustup.sh` to `sh.rustup.rs`
+
+use async_trait::async_trait;
+
+use crate::cdn::Endpoint;
+use crate::releases::rustup_sh::request_rustup_and_expect_redirect;
+use crate::test::{Metadata, Tag, Test, TestResult};
+
+/// Test that a CDN redirects `/rustup.sh` to `sh.rustup.rs`
+///
+/// The test requests the deprecated path `/rustup.sh` from a CDN and checks that it is redirected to
+/// `sh.rustup.rs`. The body of the response should contain instructions for the users who don't
+/// follow redirects. The test is named after the CDN, and is instantiated for every CDN that serves
+/// Rust releases.
+pub struct Redirect {
+    /// The CDN to request
+    endpoint: Endpoint,
+}
+
+impl Redirect {
+    /// Create a new instance of the test for the given CDN
+    pub fn new(endpoint: Endpoint) -> Self {
+        Self { endpoint }
+    }
+}
+
+#[async_trait]
+impl Test for Redirect {
+    fn name(&self) -> &str {
+        self.endpoint.name()
+    }
+
+    fn metadata(&self) -> Metadata {
+        Metadata::builder()
+            .description(
+                "Request `/rustup.sh` from the CDN and expect a redirect to `sh.rustup.rs`.",
+            )
+            .build()
+    }
+
+    fn tags(&self) -> Vec<Tag> {
+        vec![self.endpoint.tag().clone()]
+    }
+
+    async fn run(&self) -> TestResult {
+        request_rustup_and_expect_redirect(self.endpoint.name(), self.endpoint.url()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use mockito::ServerGuard;
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::*;
+
+    use super::*;
+
+    /// The body that explains the new location of rustup.sh
+    const BODY: &str = indoc! {r#"
+        #!/bin/bash
+        echo "The location of rustup.sh has moved."
+        echo "Run the following command to install from the new location:"
+        echo "    curl https://sh.rustup.rs -sSf | sh"
+    "#};
+
+    async fn setup() -> (ServerGuard, Redirect) {
+        let server = mockito::Server::new_async().await;
+
+        let endpoint = Endpoint::new("Fastly", Tag::cdn("fastly"), server.url());
+
+        (server, Redirect::new(endpoint))
+    }
+
+    #[tokio::test]
+    async fn succeeds_with_redirect_and_body() {
+        let (mut server, redirect) = setup().await;
+
+        let mock = server
+            .mock("GET", "/rustup.sh")
+            .with_status(307)
+            .with_header("Location", "https://sh.rustup.rs")
+            .with_body(BODY)
+            .create();
+
+        let result = redirect.run().await;
+
+        // Assert that the mock was called
+        mock.assert();
+
+        assert_eq!(&None, result.message());
+        assert!(result.success());
+    }
+
+    #[tokio::test]
+    async fn fails_without_redirect() {
+        let (mut server, redirect) = setup().await;
+
+        let mock = server
+            .mock("GET", "/rustup.sh")
+            .with_status(200)
+            .with_body(BODY)
+            .create();
+
+        let result = redirect.run().await;
+
+        // Assert that the mock was called
+        mock.assert();
+
+        assert!(!result.success());
+    }
+
+    #[tokio::test]
+    async fn fails_without_body() {
+        let (mut server, redirect) = setup().await;
+
+        let mock = server
+            .mock("GET", "/rustup.sh")
+            .with_status(307)
+            .with_header("Location", "https://sh.rustup.rs")
+            .create();
+
+        let result = redirect.run().await;
+
+        // Assert that the mock was called
+        mock.assert();
+
+        assert!(!result.success());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Redirect>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Redirect>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Redirect>();
+    }
+}
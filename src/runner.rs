@@ -0,0 +1,240 @@
+//! Run test suites and collect their results
+
+use std::sync::Arc;
+
+use tokio::task::JoinSet;
+
+use crate::baseline::Baseline;
+use crate::test::{Explanation, Filter, TestPath, TestSuite, TestSuiteResult};
+
+/// Run test suites and collect their results
+///
+/// The runner executes a set of test suites concurrently and returns their results in a
+/// deterministic order. Test suites are registered with the runner before it is started, which
+/// allows other crates to run their own test suites alongside the ones that are shipped with this
+/// crate. A filter can be set to run only the tests with certain tags, and a baseline can be set so
+/// that known failures are reported as expected failures.
+///
+/// ```no_run
+/// use infra_smoke_test::cdn::Service;
+/// use infra_smoke_test::config::Config;
+/// use infra_smoke_test::crates::Crates;
+/// use infra_smoke_test::environment::Environment;
+/// use infra_smoke_test::runner::Runner;
+///
+/// # async fn run() {
+/// let config = Config::for_env(&Environment::Staging).expect("built-in configuration is valid");
+///
+/// let results = Runner::new()
+///     .suite(Crates::new(
+///         config.crates().clone(),
+///         config.endpoints(Service::Crates),
+///     ))
+///     .run()
+///     .await;
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct Runner {
+    /// The test suites that are run by the runner
+    suites: Vec<Arc<dyn TestSuite>>,
+
+    /// The filter that selects the tests to run
+    filter: Filter,
+
+    /// The baseline of known failures that is applied to the results
+    baseline: Baseline,
+}
+
+impl Runner {
+    /// Create a new runner without any test suites
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a test suite with the runner
+    pub fn suite(mut self, suite: impl TestSuite + 'static) -> Self {
+        self.suites.push(Arc::new(suite));
+        self
+    }
+
+    /// Set the filter that selects the tests to run
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Set the baseline of known failures that is applied to the results of every run
+    pub fn baseline(mut self, baseline: Baseline) -> Self {
+        self.baseline = baseline;
+        self
+    }
+
+    /// Explain the test at the given path
+    ///
+    /// Returns `None` if none of the registered test suites contains a test at the given path.
+    pub fn explain(&self, path: &TestPath) -> Option<Explanation> {
+        self.suites.iter().find_map(|suite| suite.explain(path))
+    }
+
+    /// Run all registered test suites
+    ///
+    /// The test suites are run concurrently. Suites without any selected tests are omitted from the
+    /// results. The results are sorted so that the output is deterministic, and the baseline is
+    /// applied with today's date.
+    pub async fn run(&self) -> Vec<TestSuiteResult> {
+        let mut js = JoinSet::new();
+        for suite in &self.suites {
+            let suite = suite.clone();
+            let filter = self.filter.clone();
+            js.spawn(async move { suite.run(&filter).await });
+        }
+
+        let mut results: Vec<TestSuiteResult> = js
+            .join_all()
+            .await
+            .into_iter()
+            .filter(|result| !result.results().is_empty())
+            .collect();
+
+        // Sort the results so that the output is deterministic
+        results.sort();
+
+        self.baseline
+            .apply(&mut results, chrono::Local::now().date_naive());
+
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use async_trait::async_trait;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::test::{Outcome, Tag, Test, TestGroup, TestResult};
+    use crate::test_utils::*;
+
+    use super::*;
+
+    struct Fastly;
+
+    #[async_trait]
+    impl Test for Fastly {
+        fn name(&self) -> &'static str {
+            "Fastly"
+        }
+
+        fn tags(&self) -> Vec<Tag> {
+            vec![Tag::cdn("fastly")]
+        }
+
+        async fn run(&self) -> TestResult {
+            TestResult::builder().name("Fastly").success(true).build()
+        }
+    }
+
+    struct Group;
+
+    impl TestGroup for Group {
+        fn name(&self) -> &'static str {
+            "group"
+        }
+
+        fn tests(&self) -> Vec<Box<dyn Test>> {
+            vec![Box::new(Fastly)]
+        }
+    }
+
+    struct Suite(&'static str);
+
+    impl TestSuite for Suite {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn groups(&self) -> Vec<Box<dyn TestGroup>> {
+            vec![Box::new(Group)]
+        }
+    }
+
+    #[tokio::test]
+    async fn run_returns_sorted_results() {
+        let results = Runner::new()
+            .suite(Suite("b"))
+            .suite(Suite("a"))
+            .run()
+            .await;
+
+        let names: Vec<&str> = results.iter().map(|result| result.name()).collect();
+
+        assert_eq!(vec!["a", "b"], names);
+    }
+
+    #[tokio::test]
+    async fn run_omits_suites_without_selected_tests() {
+        let filter = Filter::builder().tags(vec![Tag::cdn("cloudfront")]).build();
+
+        let results = Runner::new().suite(Suite("a")).filter(filter).run().await;
+
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn explain_finds_test_in_suite() {
+        let runner = Runner::new().suite(Suite("a")).suite(Suite("b"));
+
+        let explanation = runner
+            .explain(&TestPath::new("b", "group", "Fastly"))
+            .unwrap();
+
+        assert_eq!(&TestPath::new("b", "group", "Fastly"), explanation.path());
+    }
+
+    #[tokio::test]
+    async fn run_applies_baseline() {
+        let baseline = Baseline::from_str(indoc! {r#"
+            [[expected_failure]]
+            path = "a > group > Fastly"
+            reason = "Fastly is not configured yet"
+            expires = 2999-12-31
+        "#})
+        .unwrap();
+
+        let results = Runner::new()
+            .suite(Suite("a"))
+            .baseline(baseline)
+            .run()
+            .await;
+
+        assert_eq!(
+            Outcome::UnexpectedPass,
+            results[0].results()[0].results()[0].outcome()
+        );
+    }
+
+    #[tokio::test]
+    async fn run_without_suites() {
+        let results = Runner::new().run().await;
+
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Runner>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Runner>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Runner>();
+    }
+}
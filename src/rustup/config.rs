@@ -0,0 +1,48 @@
+//! Configuration for the rustup test suite
+
+use getset::Getters;
+use serde::Deserialize;
+
+use crate::config::{Validate, Validator};
+
+use super::win_rustup_rs;
+
+/// Configuration for the rustup test suite
+///
+/// The configuration combines the configurations of the test groups in the suite. It is read from
+/// the `[rustup]` table of the configuration file.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Configuration to test `win.rustup.rs`
+    #[getset(get = "pub(crate)")]
+    win_rustup_rs: win_rustup_rs::Config,
+}
+
+impl Validate for Config {
+    fn validate(&self, validator: &mut Validator) {
+        validator.nested("win_rustup_rs", &self.win_rustup_rs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::*;
+
+    use super::*;
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Config>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Config>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Config>();
+    }
+}
@@ -2,29 +2,28 @@
 
 use std::fmt::{Display, Formatter};
 
-use async_trait::async_trait;
-use tokio::task::JoinSet;
-
-use crate::environment::Environment;
 use crate::rustup::win_rustup_rs::WinRustupRs;
-use crate::test::{TestGroup, TestSuite, TestSuiteResult};
+use crate::test::{TestGroup, TestSuite};
+
+pub use self::config::Config;
 
+mod config;
 mod win_rustup_rs;
 
 /// Smoke tests for rustup
 ///
 /// This test suite implements the smoke tests for rustup. The tests confirm that the domains of
 /// rustup redirect to the correct locations and that the cache invalidations in the CDNs work.
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
 pub struct Rustup {
-    /// The environment to run the tests in
-    env: Environment,
+    /// The configuration for the test suite
+    config: Config,
 }
 
 impl Rustup {
     /// Creates a new instance of the test suite
-    pub fn new(env: Environment) -> Self {
-        Self { env }
+    pub fn new(config: Config) -> Self {
+        Self { config }
     }
 }
 
@@ -34,22 +33,15 @@ impl Display for Rustup {
     }
 }
 
-#[async_trait]
 impl TestSuite for Rustup {
-    async fn run(&self) -> TestSuiteResult {
-        let groups: Vec<Box<dyn TestGroup>> = vec![Box::new(WinRustupRs::new(self.env))];
-
-        let mut js = JoinSet::new();
-        for group in groups {
-            js.spawn(async move { group.run().await });
-        }
-
-        let results = js.join_all().await;
+    fn name(&self) -> &'static str {
+        "rustup"
+    }
 
-        TestSuiteResult::builder()
-            .name("rustup")
-            .results(results)
-            .build()
+    fn groups(&self) -> Vec<Box<dyn TestGroup>> {
+        vec![Box::new(WinRustupRs::new(
+            self.config.win_rustup_rs().clone(),
+        ))]
     }
 }
 
@@ -1,33 +1,28 @@
 //! Configuration to test `win.rustup.rs`
 
 use getset::Getters;
+use serde::Deserialize;
 #[cfg(test)]
 use typed_builder::TypedBuilder;
 
-use crate::environment::Environment;
+use crate::config::{Validate, Validator};
 
 /// Configuration to test `win.rustup.rs`
 ///
 /// `win.rustup.rs` is only served by CloudFront, thus only the CloudFront URL is needed.
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters)]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters, Deserialize)]
+#[serde(deny_unknown_fields)]
 #[cfg_attr(test, derive(TypedBuilder))]
 pub struct Config {
     /// The URL for the CloudFront CDN
+    #[serde(deserialize_with = "crate::config::deserialize_url")]
     #[getset(get = "pub")]
     cloudfront_url: String,
 }
 
-impl Config {
-    /// Return the configuration for the given environment
-    pub fn for_env(env: Environment) -> Self {
-        match env {
-            Environment::Staging => Self {
-                cloudfront_url: "https://dev-win.rustup.rs".into(),
-            },
-            Environment::Production => Self {
-                cloudfront_url: "https://win.rustup.rs".into(),
-            },
-        }
+impl Validate for Config {
+    fn validate(&self, validator: &mut Validator) {
+        validator.url("cloudfront_url", &self.cloudfront_url);
     }
 }
 
@@ -5,12 +5,8 @@
 use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 
-use async_trait::async_trait;
-use tokio::task::JoinSet;
-
-use crate::environment::Environment;
-use crate::http_client::custom_http_client;
-use crate::test::{Test, TestGroup, TestGroupResult, TestResult};
+use crate::http_client::{custom_http_client, send};
+use crate::test::{Metadata, Tag, Test, TestGroup, TestResult};
 
 pub use self::aarch64::Aarch64;
 pub use self::config::Config;
@@ -37,9 +33,9 @@ pub struct WinRustupRs {
 
 impl WinRustupRs {
     /// Create a new instance of the test group
-    pub fn new(env: Environment) -> Self {
+    pub fn new(config: Config) -> Self {
         Self {
-            config: Arc::new(Config::for_env(env)),
+            config: Arc::new(config),
         }
     }
 }
@@ -50,26 +46,31 @@ impl Display for WinRustupRs {
     }
 }
 
-#[async_trait]
 impl TestGroup for WinRustupRs {
-    async fn run(&self) -> TestGroupResult {
-        let tests: Vec<Box<dyn Test>> = vec![
-            Box::new(Aarch64::new(self.config.clone())),
-            Box::new(I686::new(self.config.clone())),
-            Box::new(X86_64::new(self.config.clone())),
-        ];
+    fn name(&self) -> &'static str {
+        NAME
+    }
 
-        let mut js = JoinSet::new();
-        for test in tests {
-            js.spawn(async move { test.run().await });
-        }
+    fn metadata(&self) -> Metadata {
+        Metadata::builder()
+            .description(
+                "`win.rustup.rs` serves the rustup installer for Windows as an \
+                attachment, with one path for each architecture.",
+            )
+            .team("infra")
+            .build()
+    }
 
-        let results = js.join_all().await;
+    fn tags(&self) -> Vec<Tag> {
+        vec![Tag::cdn("cloudfront"), Tag::service("win.rustup.rs")]
+    }
 
-        TestGroupResult::builder()
-            .name(NAME)
-            .results(results)
-            .build()
+    fn tests(&self) -> Vec<Box<dyn Test>> {
+        vec![
+            Box::new(Aarch64::new(self.config.clone())),
+            Box::new(I686::new(self.config.clone())),
+            Box::new(X86_64::new(self.config.clone())),
+        ]
     }
 }
 
@@ -80,13 +81,12 @@ impl TestGroup for WinRustupRs {
 async fn request_installer_and_expect_attachment(name: &'static str, url: &str) -> TestResult {
     let test_result = TestResult::builder().name(name).success(false);
 
-    let response = match custom_http_client()
+    let request = custom_http_client()
         .build()
         .expect("failed to build reqwest client")
-        .head(url)
-        .send()
-        .await
-    {
+        .head(url);
+
+    let response = match send(request).await {
         Ok(response) => response,
         Err(error) => {
             return test_result.message(Some(error.to_string())).build();
@@ -141,7 +141,7 @@ mod tests {
 
     #[test]
     fn trait_display() {
-        let rustup_sh = WinRustupRs::new(Environment::Staging);
+        let rustup_sh = WinRustupRs::new(Config::default());
 
         assert_eq!("win.rustup.rs", rustup_sh.to_string());
     }
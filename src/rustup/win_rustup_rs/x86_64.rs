@@ -5,7 +5,7 @@ use std::sync::Arc;
 use async_trait::async_trait;
 
 use crate::rustup::win_rustup_rs::request_installer_and_expect_attachment;
-use crate::test::{Test, TestResult};
+use crate::test::{Metadata, Test, TestResult};
 
 use super::config::Config;
 
@@ -30,6 +30,19 @@ impl X86_64 {
 
 #[async_trait]
 impl Test for X86_64 {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn metadata(&self) -> Metadata {
+        Metadata::builder()
+            .description(
+                "Request the installer for x86_64 and expect it to be served as an \
+                attachment.",
+            )
+            .build()
+    }
+
     async fn run(&self) -> TestResult {
         request_installer_and_expect_attachment(
             NAME,
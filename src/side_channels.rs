@@ -0,0 +1,170 @@
+//! Side channels that record the results of a run
+//!
+//! Besides the report that is printed after a run, the results can be appended to a history file
+//! and sent to a StatsD agent. Like the notifications and the issue, these side channels only
+//! report on a run that already happened, so failing to update them prints a warning instead of
+//! failing the run.
+
+use std::fmt::Display;
+use std::path::PathBuf;
+
+use chrono::Utc;
+use getset::Getters;
+use typed_builder::TypedBuilder;
+
+use crate::environment::Environment;
+use crate::history::{Entry, History};
+use crate::statsd::StatsdClient;
+use crate::test::TestSuiteResult;
+
+/// The side channels that the results of every run are recorded in
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Getters, TypedBuilder)]
+pub struct SideChannels {
+    /// The environment in which the tests run
+    #[getset(get = "pub")]
+    env: Environment,
+
+    /// The history file that the results are appended to, if one was given
+    #[builder(default)]
+    #[getset(get = "pub")]
+    history: Option<PathBuf>,
+
+    /// The address of the StatsD agent that the results are sent to, if one was given
+    #[builder(default)]
+    #[getset(get = "pub")]
+    statsd: Option<String>,
+}
+
+impl SideChannels {
+    /// Append the results to the history file and send them to the StatsD agent
+    pub async fn record(&self, results: &[TestSuiteResult]) {
+        self.record_history(results);
+        self.send_to_statsd(results).await;
+    }
+
+    /// Append the results to the history file, if one was given
+    fn record_history(&self, results: &[TestSuiteResult]) {
+        let Some(path) = &self.history else {
+            return;
+        };
+
+        let entries = Entry::from_results(&self.env, results, Utc::now());
+
+        warn_on_error(
+            History::append(path, &entries),
+            &format!("failed to record the run in {}", path.display()),
+        );
+    }
+
+    /// Send the results to the StatsD agent, if an address for it was given
+    async fn send_to_statsd(&self, results: &[TestSuiteResult]) {
+        let Some(address) = &self.statsd else {
+            return;
+        };
+
+        let sent = match StatsdClient::connect(address).await {
+            Ok(client) => client.send(&self.env, results).await,
+            Err(error) => Err(error),
+        };
+
+        warn_on_error(
+            sent,
+            &format!("failed to send metrics to StatsD at {address}"),
+        );
+    }
+}
+
+/// Print a warning if reporting the results to a side channel failed
+///
+/// The history, the metrics, the notifications, and the issue only report on a run that already
+/// happened, so failing to update them doesn't fail the run.
+pub(crate) fn warn_on_error(result: Result<(), impl Display>, context: &str) {
+    if let Err(error) = result {
+        eprintln!("Warning: {context}: {error}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tokio::net::UdpSocket;
+
+    use crate::test::{TestGroupResult, TestResult};
+    use crate::test_utils::*;
+
+    use super::*;
+
+    fn results() -> Vec<TestSuiteResult> {
+        vec![TestSuiteResult::builder()
+            .name("crates.io")
+            .results(vec![TestGroupResult::builder()
+                .name("Database dumps")
+                .results(vec![TestResult::builder()
+                    .name("Fastly")
+                    .success(true)
+                    .build()])
+                .build()])
+            .build()]
+    }
+
+    #[tokio::test]
+    async fn record_appends_to_history() {
+        let path = std::env::temp_dir().join(format!(
+            "infra-smoke-test-side-channels-{}.jsonl",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let side_channels = SideChannels::builder()
+            .env(Environment::Staging)
+            .history(Some(path.clone()))
+            .build();
+
+        side_channels.record(&results()).await;
+        side_channels.record(&results()).await;
+
+        let history = History::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(2, history.entries().len());
+    }
+
+    #[tokio::test]
+    async fn record_sends_to_statsd() {
+        let agent = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+
+        let side_channels = SideChannels::builder()
+            .env(Environment::Staging)
+            .statsd(Some(agent.local_addr().unwrap().to_string()))
+            .build();
+
+        side_channels.record(&results()).await;
+
+        let mut buffer = [0; 1024];
+        let length = agent.recv(&mut buffer).await.unwrap();
+
+        assert!(String::from_utf8_lossy(&buffer[..length]).starts_with("smoke_test.success:1|g"));
+    }
+
+    #[tokio::test]
+    async fn record_without_side_channels() {
+        let side_channels = SideChannels::builder().env(Environment::Staging).build();
+
+        side_channels.record(&results()).await;
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<SideChannels>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<SideChannels>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<SideChannels>();
+    }
+}
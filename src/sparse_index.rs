@@ -0,0 +1,147 @@
+//! Access to the sparse index of crates.io
+//!
+//! The sparse index serves one file per crate, with one line of JSON for each version of the crate.
+//! It is used to discover fresh fixtures and to check that the configured fixtures still exist.
+
+use getset::{CopyGetters, Getters};
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::http_client::send;
+
+/// An entry in a file of the sparse index
+///
+/// Each line in a file of the sparse index describes one version of the crate. Only the fields that
+/// are needed by the smoke tests are deserialized.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, Getters, Deserialize)]
+pub(crate) struct Entry {
+    /// The version of the crate
+    #[getset(get = "pub(crate)")]
+    vers: String,
+
+    /// Whether the version has been yanked
+    #[getset(get_copy = "pub(crate)")]
+    yanked: bool,
+}
+
+/// Request the entries of the crate from the sparse index at the given URL
+pub(crate) async fn entries(
+    client: &Client,
+    index_url: &str,
+    krate: &str,
+) -> Result<Vec<Entry>, String> {
+    if krate.is_empty() {
+        return Err("no crate is configured".into());
+    }
+
+    let url = format!("{}/{}", index_url.trim_end_matches('/'), path(krate));
+
+    let file = send(client.get(&url))
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|error| error.to_string())?
+        .text()
+        .await
+        .map_err(|error| error.to_string())?;
+
+    file.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line)
+                .map_err(|error| format!("failed to parse the index entry for {krate}: {error}"))
+        })
+        .collect()
+}
+
+/// Return the path of the crate's file in the sparse index
+///
+/// See <https://doc.rust-lang.org/cargo/reference/registry-index.html#index-files>.
+pub(crate) fn path(krate: &str) -> String {
+    let krate = krate.to_lowercase();
+
+    match krate.len() {
+        1 => format!("1/{krate}"),
+        2 => format!("2/{krate}"),
+        3 => format!("3/{}/{krate}", &krate[..1]),
+        _ => format!("{}/{}/{krate}", &krate[..2], &krate[2..4]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::*;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn entries_from_index() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mock = server
+            .mock("GET", "/ax/um/axum")
+            .with_body(
+                "{\"name\":\"axum\",\"vers\":\"0.6.10\",\"yanked\":false}\n\
+                {\"name\":\"axum\",\"vers\":\"0.6.11\",\"yanked\":true}\n",
+            )
+            .create_async()
+            .await;
+
+        let entries = entries(&Client::new(), &server.url(), "axum")
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+
+        assert_eq!(2, entries.len());
+        assert_eq!("0.6.10", entries[0].vers());
+        assert!(entries[1].yanked());
+    }
+
+    #[tokio::test]
+    async fn entries_with_missing_crate() {
+        let mut server = mockito::Server::new_async().await;
+
+        server
+            .mock("GET", "/ax/um/axum")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        assert!(entries(&Client::new(), &server.url(), "axum")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn entries_without_crate() {
+        assert!(entries(&Client::new(), "https://index.crates.io", "")
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn path_by_length() {
+        assert_eq!("1/a", path("a"));
+        assert_eq!("2/ab", path("ab"));
+        assert_eq!("3/a/abc", path("abc"));
+        assert_eq!("an/si/ansi_term", path("ansi_term"));
+        assert_eq!("se/rd/serde", path("Serde"));
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Entry>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Entry>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Entry>();
+    }
+}
@@ -0,0 +1,219 @@
+//! StatsD metrics for the smoke tests
+//!
+//! The Rust project's infrastructure is monitored with Datadog, which receives metrics from a
+//! DogStatsD agent. After each run, the success and the duration of every test can be sent to the
+//! agent over UDP:
+//!
+//! - `smoke_test.success` is a gauge that is `1` if the test passed, or failed as expected by the
+//!   baseline
+//! - `smoke_test.duration` is a timer with the time the test took to run in milliseconds
+//!
+//! The metrics are tagged with the environment, the CDN or origin that the test requested, and the
+//! suite, group, and name of the test, using the DogStatsD extension for tags.
+
+use std::io::{Error, ErrorKind};
+
+use tokio::net::{lookup_host, UdpSocket};
+
+use crate::environment::Environment;
+use crate::test::{TestPath, TestResult, TestSuiteResult};
+
+/// A client that sends metrics to a StatsD agent
+#[derive(Debug)]
+pub struct StatsdClient {
+    /// The socket that is connected to the agent
+    socket: UdpSocket,
+}
+
+impl StatsdClient {
+    /// Create a client that sends metrics to the agent at the given address, e.g. `localhost:8125`
+    pub async fn connect(address: &str) -> std::io::Result<Self> {
+        let address = lookup_host(address).await?.next().ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("failed to resolve '{address}'"),
+            )
+        })?;
+
+        let local = if address.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+
+        let socket = UdpSocket::bind(local).await?;
+        socket.connect(address).await?;
+
+        Ok(Self { socket })
+    }
+
+    /// Send the metrics for the results of a run
+    ///
+    /// Each metric is sent in its own datagram, so that a large run doesn't exceed the maximum size
+    /// of a packet.
+    pub async fn send(
+        &self,
+        env: &Environment,
+        results: &[TestSuiteResult],
+    ) -> std::io::Result<()> {
+        for line in lines(env, results) {
+            self.socket.send(line.as_bytes()).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Format the metrics for the results of a run in the DogStatsD format
+pub fn lines(env: &Environment, results: &[TestSuiteResult]) -> Vec<String> {
+    results
+        .iter()
+        .flat_map(TestSuiteResult::test_results)
+        .flat_map(|(path, result)| {
+            let tags = tags(env, &path, result);
+
+            [
+                format!(
+                    "smoke_test.success:{}|g|#{tags}",
+                    u8::from(result.outcome().is_ok())
+                ),
+                format!(
+                    "smoke_test.duration:{}|ms|#{tags}",
+                    result.duration().as_millis()
+                ),
+            ]
+        })
+        .collect()
+}
+
+/// Return the tags for the metrics of a test
+fn tags(env: &Environment, path: &TestPath, result: &TestResult) -> String {
+    let mut tags = vec![format!("environment:{}", sanitize(env.name()))];
+
+    tags.extend(
+        result
+            .tags()
+            .iter()
+            .map(|tag| tag.as_str())
+            .filter(|tag| tag.starts_with("cdn:") || tag.starts_with("origin:"))
+            .map(sanitize),
+    );
+
+    tags.push(format!("suite:{}", sanitize(path.suite())));
+    tags.push(format!("group:{}", sanitize(path.group())));
+    tags.push(format!("test:{}", sanitize(path.test())));
+
+    tags.join(",")
+}
+
+/// Replace the characters that have a special meaning in the DogStatsD format
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            ',' | '|' | '#' | '@' | '\n' | ' ' => '_',
+            c => c,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::test::{Tag, TestGroupResult};
+    use crate::test_utils::*;
+
+    use super::*;
+
+    fn results() -> Vec<TestSuiteResult> {
+        let result = TestResult::builder()
+            .name("Fastly")
+            .success(false)
+            .duration(Duration::from_millis(250))
+            .tags(vec![Tag::service("db-dump"), Tag::cdn("fastly")])
+            .build();
+
+        vec![TestSuiteResult::builder()
+            .name("crates.io")
+            .results(vec![TestGroupResult::builder()
+                .name("Database dumps")
+                .results(vec![result])
+                .build()])
+            .build()]
+    }
+
+    #[test]
+    fn lines_with_tags() {
+        let tags =
+            "environment:staging,cdn:fastly,suite:crates.io,group:Database_dumps,test:Fastly";
+
+        assert_eq!(
+            vec![
+                format!("smoke_test.success:0|g|#{tags}"),
+                format!("smoke_test.duration:250|ms|#{tags}"),
+            ],
+            lines(&Environment::Staging, &results())
+        );
+    }
+
+    #[test]
+    fn lines_with_expected_failure() {
+        let result = TestResult::builder()
+            .name("Fastly")
+            .success(false)
+            .expected_failure(Some("known outage".into()))
+            .build();
+        let results = vec![TestSuiteResult::builder()
+            .name("crates.io")
+            .results(vec![TestGroupResult::builder()
+                .name("Database dumps")
+                .results(vec![result])
+                .build()])
+            .build()];
+
+        let lines = lines(&Environment::Staging, &results);
+
+        assert!(lines[0].starts_with("smoke_test.success:1|g|"));
+    }
+
+    #[tokio::test]
+    async fn send_to_udp_listener() {
+        let listener = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        let client = StatsdClient::connect(&address).await.unwrap();
+        client
+            .send(&Environment::Production, &results())
+            .await
+            .unwrap();
+
+        let mut buffer = [0; 1024];
+        let mut received = Vec::new();
+
+        for _ in 0..2 {
+            let size = listener.recv(&mut buffer).await.unwrap();
+            received.push(String::from_utf8_lossy(&buffer[..size]).into_owned());
+        }
+
+        assert!(received[0].starts_with("smoke_test.success:0|g|#environment:production"));
+        assert!(received[1].starts_with("smoke_test.duration:250|ms|#environment:production"));
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<StatsdClient>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<StatsdClient>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<StatsdClient>();
+    }
+}
@@ -0,0 +1,531 @@
+//! A self-hosted status page with the latest results
+//!
+//! When the smoke tests run on a schedule, the status page keeps the results of the recent runs
+//! and serves them to people who want to check the health of the infrastructure without running
+//! the tests themselves:
+//!
+//! - `/` is an HTML page with the results of the latest run and a summary of the recent runs
+//! - `/api/status` returns the same information as JSON, with the latest run in the format of
+//!   `--format json`
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use getset::{CopyGetters, Getters};
+use serde::Serialize;
+use tokio::net::TcpListener;
+use tokio::time::MissedTickBehavior;
+
+use crate::environment::Environment;
+use crate::http_server::{self, Response};
+use crate::monitor::Interval;
+use crate::runner::Runner;
+use crate::side_channels::SideChannels;
+use crate::test::html_report::{class, escape};
+use crate::test::{OutcomeCounts, SavedRun, TestSuiteResult};
+
+/// The number of runs that the status page keeps by default
+pub const DEFAULT_CAPACITY: usize = 100;
+
+/// The results of the recent runs in an environment
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, Getters)]
+pub struct StatusPage {
+    /// The environment that is tested
+    #[getset(get = "pub")]
+    env: Environment,
+
+    /// The recent runs, from the oldest to the latest
+    #[getset(get = "pub")]
+    runs: VecDeque<SavedRun>,
+
+    /// The number of runs to keep
+    #[getset(get_copy = "pub")]
+    capacity: usize,
+}
+
+/// A summary of a run for the history on the status page
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, Serialize)]
+pub struct RunSummary {
+    /// When the run finished
+    #[getset(get_copy = "pub")]
+    time: DateTime<Utc>,
+
+    /// The number of tests with each outcome
+    #[serde(flatten)]
+    #[getset(get_copy = "pub")]
+    counts: OutcomeCounts,
+}
+
+/// The body of the JSON API
+#[derive(Serialize)]
+struct Status<'a> {
+    /// The name of the environment that is tested
+    environment: &'a str,
+
+    /// The results of the latest run, if the tests have run yet
+    latest: Option<&'a SavedRun>,
+
+    /// The summaries of the recent runs, from the latest to the oldest
+    history: Vec<RunSummary>,
+}
+
+impl StatusPage {
+    /// Create an empty status page that keeps the given number of runs
+    pub fn new(env: Environment, capacity: usize) -> Self {
+        Self {
+            env,
+            runs: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Record the results of a run, dropping the oldest run if the page is full
+    pub fn record(&mut self, results: &[TestSuiteResult], at: DateTime<Utc>) {
+        if self.runs.len() == self.capacity {
+            self.runs.pop_front();
+        }
+
+        self.runs.push_back(SavedRun::new(&self.env, results, at));
+    }
+
+    /// Return the latest run, if the tests have run yet
+    pub fn latest(&self) -> Option<&SavedRun> {
+        self.runs.back()
+    }
+
+    /// Return the summaries of the recent runs, from the latest to the oldest
+    pub fn history(&self) -> Vec<RunSummary> {
+        self.runs.iter().rev().map(RunSummary::new).collect()
+    }
+
+    /// Return the status as JSON
+    pub fn to_json(&self) -> String {
+        let status = Status {
+            environment: self.env.name(),
+            latest: self.latest(),
+            history: self.history(),
+        };
+
+        serde_json::to_string_pretty(&status).expect("a status can always be serialized")
+    }
+
+    /// Return the status as an HTML page
+    ///
+    /// The page refreshes itself every minute, so that it can be left open on a dashboard.
+    pub fn to_html(&self) -> String {
+        let env = escape(self.env.name());
+
+        let mut html = format!(
+            "<!DOCTYPE html>\n\
+            <html lang=\"en\">\n\
+            <head>\n\
+            <meta charset=\"utf-8\">\n\
+            <meta http-equiv=\"refresh\" content=\"60\">\n\
+            <title>Smoke tests: {env}</title>\n\
+            <style>\n\
+            body {{ font-family: sans-serif; margin: 2em; }}\n\
+            table {{ border-collapse: collapse; }}\n\
+            th, td {{ border-bottom: 1px solid #ddd; padding: 0.25em 1em; text-align: left; }}\n\
+            .passed {{ color: #1a7f37; }}\n\
+            .failed {{ color: #cf222e; }}\n\
+            .expected-failure, .unexpected-pass {{ color: #9a6700; }}\n\
+            </style>\n\
+            </head>\n\
+            <body>\n\
+            <h1>Smoke tests: {env}</h1>\n"
+        );
+
+        match self.latest() {
+            Some(latest) => {
+                let summary = RunSummary::new(latest);
+
+                html.push_str(&format!(
+                    "<p>Last run: {} ({})</p>\n",
+                    format_time(summary.time),
+                    summary.counts
+                ));
+
+                html.push_str(
+                    "<table>\n\
+                    <tr><th>Test</th><th>Result</th><th>Duration</th><th>Message</th></tr>\n",
+                );
+
+                for test in latest.tests() {
+                    let result = class(test.outcome());
+
+                    html.push_str(&format!(
+                        "<tr class=\"{result}\"><td>{}</td><td>{result}</td><td>{} ms</td><td>{}</td></tr>\n",
+                        escape(&test.path().to_string()),
+                        test.duration_ms(),
+                        escape(test.message().as_deref().unwrap_or_default())
+                    ));
+                }
+
+                html.push_str("</table>\n");
+            }
+            None => html.push_str("<p>The smoke tests have not run yet.</p>\n"),
+        }
+
+        if self.runs.len() > 1 {
+            html.push_str(
+                "<h2>Recent runs</h2>\n\
+                <table>\n\
+                <tr><th>Time</th><th>Passed</th><th>Failed</th>\
+                <th>Expected failures</th><th>Unexpected passes</th></tr>\n",
+            );
+
+            for run in self.history() {
+                let counts = run.counts;
+                let class = if counts.failed() == 0 {
+                    "passed"
+                } else {
+                    "failed"
+                };
+
+                html.push_str(&format!(
+                    "<tr class=\"{class}\"><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    format_time(run.time),
+                    counts.passed(),
+                    counts.failed(),
+                    counts.expected_failures(),
+                    counts.unexpected_passes()
+                ));
+            }
+
+            html.push_str("</table>\n");
+        }
+
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+impl RunSummary {
+    /// Summarize a saved run
+    fn new(run: &SavedRun) -> Self {
+        Self {
+            time: run.time(),
+            counts: run.tests().iter().map(|test| test.outcome()).collect(),
+        }
+    }
+}
+
+/// Serve the status page on `/` and the JSON API on `/api/status` for as long as the future is polled
+///
+/// Every other path returns HTTP 404.
+pub async fn serve(listener: TcpListener, status: Arc<Mutex<StatusPage>>) {
+    http_server::serve(listener, move |path| {
+        let status = status.lock().expect("status lock is poisoned");
+
+        match path {
+            "/" => Response::ok("text/html; charset=utf-8", status.to_html()),
+            "/api/status" => Response::ok("application/json", status.to_json()),
+            _ => Response::not_found(),
+        }
+    })
+    .await
+}
+
+/// Run the smoke tests in a loop and serve the latest results on a status page
+///
+/// The status page is served on the listener as HTML on `/` and as JSON on `/api/status`. The
+/// baseline of the runner is applied to every run, so that expected failures are shown as such. The
+/// loop runs until the process is stopped. The status page is served alongside the loop rather than
+/// in a separate task, so that the page can't stop without the loop noticing.
+pub async fn run(
+    runner: &Runner,
+    side_channels: &SideChannels,
+    listener: TcpListener,
+    interval: Interval,
+) {
+    let status = Arc::new(Mutex::new(StatusPage::new(
+        side_channels.env().clone(),
+        DEFAULT_CAPACITY,
+    )));
+
+    let checks = async {
+        let mut ticker = tokio::time::interval(interval.duration());
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+            check(runner, side_channels, &status).await;
+        }
+    };
+
+    tokio::join!(serve(listener, status.clone()), checks);
+}
+
+/// Run the smoke tests once and record the results in the side channels and on the status page
+async fn check(runner: &Runner, side_channels: &SideChannels, status: &Mutex<StatusPage>) {
+    let results = runner.run().await;
+
+    side_channels.record(&results).await;
+
+    status
+        .lock()
+        .expect("status lock is poisoned")
+        .record(&results, Utc::now());
+}
+
+/// Format a time for the status page
+fn format_time(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use std::str::FromStr;
+
+    use chrono::{NaiveDate, TimeZone};
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::baseline::Baseline;
+    use crate::test::{Outcome, TestGroupResult, TestResult, ToggleSuite};
+    use crate::test_utils::*;
+
+    use super::*;
+
+    fn time(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 6, 1, hour, 0, 0).unwrap()
+    }
+
+    fn results(success: bool) -> Vec<TestSuiteResult> {
+        let result = TestResult::builder()
+            .name("Fastly")
+            .success(success)
+            .message((!success).then(|| "expected <307>, got 404".to_string()))
+            .duration(Duration::from_millis(132))
+            .build();
+
+        vec![TestSuiteResult::builder()
+            .name("crates.io")
+            .results(vec![TestGroupResult::builder()
+                .name("Database dumps")
+                .results(vec![result])
+                .build()])
+            .build()]
+    }
+
+    fn status_page() -> StatusPage {
+        let mut status = StatusPage::new(Environment::Staging, 2);
+
+        status.record(&results(true), time(1));
+        status.record(&results(true), time(2));
+        status.record(&results(false), time(3));
+
+        status
+    }
+
+    #[tokio::test]
+    async fn check_records_run_with_baseline() {
+        let baseline = Baseline::from_str(indoc! {r#"
+            [[expected_failure]]
+            path = "crates.io > Database dumps > Fastly"
+            reason = "Fastly is not configured yet"
+            expires = 2999-12-31
+        "#})
+        .unwrap();
+        let runner = Runner::new()
+            .suite(ToggleSuite::new(false))
+            .baseline(baseline);
+        let side_channels = SideChannels::builder().env(Environment::Staging).build();
+        let status = Mutex::new(StatusPage::new(Environment::Staging, DEFAULT_CAPACITY));
+
+        check(&runner, &side_channels, &status).await;
+        check(&runner, &side_channels, &status).await;
+
+        let status = status.lock().unwrap();
+        let latest = status.latest().unwrap();
+
+        assert_eq!(2, status.runs().len());
+        assert_eq!(Outcome::ExpectedFailure, latest.tests()[0].outcome());
+    }
+
+    #[tokio::test]
+    async fn run_serves_latest_run() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/api/status", listener.local_addr().unwrap());
+
+        let status = tokio::spawn(async move {
+            let runner = Runner::new().suite(ToggleSuite::new(true));
+            let side_channels = SideChannels::builder().env(Environment::Staging).build();
+            let interval = Interval::new(Duration::from_secs(60));
+
+            run(&runner, &side_channels, listener, interval).await;
+        });
+
+        let mut json = String::new();
+        for _ in 0..50 {
+            json = reqwest::get(&url).await.unwrap().text().await.unwrap();
+
+            if !json.contains("\"latest\": null") {
+                break;
+            }
+
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        status.abort();
+
+        assert!(json.contains("\"passed\": 1"));
+    }
+
+    #[test]
+    fn record_drops_oldest_run() {
+        let status = status_page();
+
+        assert_eq!(2, status.runs().len());
+        assert_eq!(time(2), status.runs()[0].time());
+        assert_eq!(Some(time(3)), status.latest().map(SavedRun::time));
+    }
+
+    #[test]
+    fn history_from_latest_to_oldest() {
+        let history = status_page().history();
+
+        assert_eq!(
+            vec![(time(3), 0, 1), (time(2), 1, 0)],
+            history
+                .iter()
+                .map(|run| (run.time(), run.counts().passed(), run.counts().failed()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn to_json() {
+        let expected = indoc! {r#"
+            {
+              "environment": "staging",
+              "latest": {
+                "environment": "staging",
+                "time": "2024-06-01T03:00:00Z",
+                "tests": [
+                  {
+                    "path": "crates.io > Database dumps > Fastly",
+                    "success": false,
+                    "outcome": "failed",
+                    "message": "expected <307>, got 404",
+                    "duration_ms": 132
+                  }
+                ]
+              },
+              "history": [
+                {
+                  "time": "2024-06-01T03:00:00Z",
+                  "passed": 0,
+                  "failed": 1,
+                  "expected_failures": 0,
+                  "unexpected_passes": 0
+                },
+                {
+                  "time": "2024-06-01T02:00:00Z",
+                  "passed": 1,
+                  "failed": 0,
+                  "expected_failures": 0,
+                  "unexpected_passes": 0
+                }
+              ]
+            }"#};
+
+        assert_eq!(expected, status_page().to_json());
+    }
+
+    #[test]
+    fn to_json_without_runs() {
+        let status = StatusPage::new(Environment::Production, DEFAULT_CAPACITY);
+
+        assert!(status.to_json().contains(r#""latest": null"#));
+    }
+
+    #[test]
+    fn to_html_escapes_messages() {
+        let html = status_page().to_html();
+
+        assert!(html.contains("<title>Smoke tests: staging</title>"));
+        assert!(html.contains(
+            "<tr class=\"failed\"><td>crates.io &gt; Database dumps &gt; Fastly</td>\
+            <td>failed</td><td>132 ms</td><td>expected &lt;307&gt;, got 404</td></tr>"
+        ));
+        assert!(html.contains("<h2>Recent runs</h2>"));
+    }
+
+    #[test]
+    fn history_counts_expected_failures_separately() {
+        let mut results = results(false);
+        Baseline::from_str(indoc! {r#"
+            [[expected_failure]]
+            path = "crates.io > Database dumps > Fastly"
+            reason = "Fastly is not configured yet"
+            expires = 2024-06-30
+        "#})
+        .unwrap()
+        .apply(&mut results, NaiveDate::from_ymd_opt(2024, 6, 1).unwrap());
+
+        let mut status = StatusPage::new(Environment::Staging, DEFAULT_CAPACITY);
+        status.record(&results, time(1));
+
+        let counts = status.history()[0].counts();
+
+        assert_eq!(0, counts.failed());
+        assert_eq!(1, counts.expected_failures());
+        assert!(status.to_html().contains(
+            "<p>Last run: 2024-06-01T01:00:00Z (0 passed, 0 failed, 1 expected failure(s))</p>"
+        ));
+        assert!(status.to_html().contains("<td>expected-failure</td>"));
+    }
+
+    #[test]
+    fn to_html_without_runs() {
+        let html = StatusPage::new(Environment::Production, DEFAULT_CAPACITY).to_html();
+
+        assert!(html.contains("<p>The smoke tests have not run yet.</p>"));
+        assert!(!html.contains("<h2>Recent runs</h2>"));
+    }
+
+    #[tokio::test]
+    async fn serve_html_and_json() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        tokio::spawn(serve(listener, Arc::new(Mutex::new(status_page()))));
+
+        let html = reqwest::get(format!("http://{address}/")).await.unwrap();
+        assert_eq!("text/html; charset=utf-8", html.headers()["Content-Type"]);
+
+        let json: serde_json::Value = reqwest::get(format!("http://{address}/api/status"))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!("staging", json["environment"]);
+
+        let missing = reqwest::get(format!("http://{address}/metrics"))
+            .await
+            .unwrap();
+        assert_eq!(404, missing.status().as_u16());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<StatusPage>();
+        assert_send::<RunSummary>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<StatusPage>();
+        assert_sync::<RunSummary>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<StatusPage>();
+        assert_unpin::<RunSummary>();
+    }
+}
@@ -0,0 +1,127 @@
+//! An explanation of what a test checks
+
+use std::fmt::{Display, Formatter};
+
+use getset::{CopyGetters, Getters};
+use typed_builder::TypedBuilder;
+
+use crate::test::{Metadata, Tag, TestPath};
+
+/// An explanation of what a test checks
+///
+/// The explanation combines the metadata of a test with the metadata of its group, and lists the
+/// tags that can be used to select the test. It is printed by the `explain` subcommand.
+#[derive(
+    Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, Getters, TypedBuilder,
+)]
+pub struct Explanation {
+    /// The path of the test
+    #[getset(get = "pub")]
+    path: TestPath,
+
+    /// The tags of the test, including the tags of its group
+    #[builder(default)]
+    #[getset(get = "pub")]
+    tags: Vec<Tag>,
+
+    /// The metadata of the test
+    #[builder(default)]
+    #[getset(get_copy = "pub")]
+    metadata: Metadata,
+
+    /// The metadata of the test group
+    #[builder(default)]
+    #[getset(get_copy = "pub")]
+    group_metadata: Metadata,
+}
+
+impl Display for Explanation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.path)?;
+
+        let descriptions = [
+            self.metadata.description(),
+            self.group_metadata.description(),
+        ];
+
+        for description in descriptions.into_iter().flatten() {
+            writeln!(f)?;
+            writeln!(f, "{description}")?;
+        }
+
+        writeln!(f)?;
+
+        if !self.tags.is_empty() {
+            let tags: Vec<&str> = self.tags.iter().map(Tag::as_str).collect();
+            writeln!(f, "Tags: {}", tags.join(", "))?;
+        }
+
+        let metadata = self.metadata.or(self.group_metadata);
+        let fields = [
+            ("Team", metadata.team()),
+            ("Issue", metadata.issue()),
+            ("Runbook", metadata.runbook()),
+        ];
+
+        for (label, value) in fields {
+            writeln!(f, "{label}: {}", value.unwrap_or("-"))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::*;
+
+    use super::*;
+
+    #[test]
+    fn trait_display() {
+        let explanation = Explanation::builder()
+            .path(TestPath::new("suite", "group", "test"))
+            .tags(vec![Tag::cdn("fastly"), Tag::service("service")])
+            .metadata(Metadata::builder().description("test description").build())
+            .group_metadata(
+                Metadata::builder()
+                    .description("group description")
+                    .team("infra")
+                    .build(),
+            )
+            .build();
+
+        let expected = indoc! {r#"
+            suite > group > test
+
+            test description
+
+            group description
+
+            Tags: cdn:fastly, service:service
+            Team: infra
+            Issue: -
+            Runbook: -
+        "#};
+
+        assert_eq!(expected, explanation.to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Explanation>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Explanation>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Explanation>();
+    }
+}
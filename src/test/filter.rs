@@ -0,0 +1,106 @@
+//! Select tests by their tags
+
+use getset::Getters;
+use typed_builder::TypedBuilder;
+
+use crate::test::Tag;
+
+/// Select tests by their tags
+///
+/// The filter decides which tests are run based on their tags. A test is selected if it has all of
+/// the required tags and none of the excluded tags. An empty filter selects every test.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Getters, TypedBuilder)]
+pub struct Filter {
+    /// The tags that a test must have to be selected
+    #[builder(default)]
+    #[getset(get = "pub")]
+    tags: Vec<Tag>,
+
+    /// The tags that exclude a test from being selected
+    #[builder(default)]
+    #[getset(get = "pub")]
+    excluded_tags: Vec<Tag>,
+}
+
+impl Filter {
+    /// Check if a test with the given tags is selected by the filter
+    pub fn matches(&self, tags: &[Tag]) -> bool {
+        self.tags.iter().all(|tag| tags.contains(tag))
+            && !self.excluded_tags.iter().any(|tag| tags.contains(tag))
+    }
+
+    /// Check if the filter is empty, i.e. if it selects every test
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.excluded_tags.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::*;
+
+    use super::*;
+
+    #[test]
+    fn matches_everything_without_tags() {
+        let filter = Filter::default();
+
+        assert!(filter.matches(&[]));
+        assert!(filter.matches(&[Tag::cdn("fastly")]));
+        assert!(filter.is_empty());
+    }
+
+    #[test]
+    fn matches_with_required_tag() {
+        let filter = Filter::builder().tags(vec![Tag::cdn("fastly")]).build();
+
+        assert!(filter.matches(&[Tag::cdn("fastly"), Tag::service("doc-router")]));
+        assert!(!filter.matches(&[Tag::cdn("cloudfront")]));
+        assert!(!filter.is_empty());
+    }
+
+    #[test]
+    fn matches_with_all_required_tags() {
+        let filter = Filter::builder()
+            .tags(vec![Tag::cdn("fastly"), Tag::issue("crates.io#4891")])
+            .build();
+
+        assert!(filter.matches(&[Tag::cdn("fastly"), Tag::issue("crates.io#4891")]));
+        assert!(!filter.matches(&[Tag::cdn("fastly")]));
+    }
+
+    #[test]
+    fn matches_without_excluded_tag() {
+        let filter = Filter::builder()
+            .excluded_tags(vec![Tag::cdn("cloudfront")])
+            .build();
+
+        assert!(filter.matches(&[Tag::cdn("fastly")]));
+        assert!(!filter.matches(&[Tag::cdn("cloudfront")]));
+    }
+
+    #[test]
+    fn matches_excluded_tag_over_required_tag() {
+        let filter = Filter::builder()
+            .tags(vec![Tag::cdn("fastly")])
+            .excluded_tags(vec![Tag::issue("crates.io#4891")])
+            .build();
+
+        assert!(!filter.matches(&[Tag::cdn("fastly"), Tag::issue("crates.io#4891")]));
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Filter>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Filter>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Filter>();
+    }
+}
@@ -0,0 +1,170 @@
+//! Formats to print test results
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+
+use crate::environment::Environment;
+use crate::test::{HtmlReport, Report, SavedRun, Style, TestSuiteResult};
+
+/// Formats to print test results
+///
+/// By default, the results are printed as a tree for a human reader. They can also be printed as
+/// JSON, which can be saved and compared with another run later, e.g. with the `diff` subcommand,
+/// or as a self-contained HTML page that can be uploaded as a CI artifact.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub enum Format {
+    /// Print the results as text for a human reader
+    #[default]
+    Text,
+
+    /// Print the results as JSON
+    Json,
+
+    /// Print the results as a self-contained HTML page
+    Html,
+}
+
+impl Format {
+    /// Render the results of a run in this format
+    ///
+    /// The style and quiet mode only apply to the text format, while the JSON and the HTML format
+    /// include the environment and the time of the run.
+    pub fn render(
+        &self,
+        env: &Environment,
+        results: &[TestSuiteResult],
+        style: Style,
+        quiet: bool,
+        time: DateTime<Utc>,
+    ) -> String {
+        match self {
+            Format::Text => Report::builder()
+                .results(results)
+                .style(style)
+                .quiet(quiet)
+                .build()
+                .to_string(),
+            Format::Json => format!("{}\n", SavedRun::new(env, results, time).to_json()),
+            Format::Html => HtmlReport::builder()
+                .env(env)
+                .results(results)
+                .time(time)
+                .build()
+                .to_string(),
+        }
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Format::Text => write!(f, "text"),
+            Format::Json => write!(f, "json"),
+            Format::Html => write!(f, "html"),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "html" => Ok(Format::Html),
+            _ => Err(format!(
+                "expected 'text', 'json', or 'html', got '{format}'"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    use crate::test::{TestGroupResult, TestResult};
+    use crate::test_utils::*;
+
+    use super::*;
+
+    fn results() -> Vec<TestSuiteResult> {
+        vec![TestSuiteResult::builder()
+            .name("crates.io")
+            .results(vec![TestGroupResult::builder()
+                .name("Database dumps")
+                .results(vec![TestResult::builder()
+                    .name("Fastly")
+                    .success(false)
+                    .message(Some("HTTP 404".into()))
+                    .build()])
+                .build()])
+            .build()]
+    }
+
+    fn render(format: Format) -> String {
+        format.render(
+            &Environment::Staging,
+            &results(),
+            Style::Plain,
+            true,
+            Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap(),
+        )
+    }
+
+    #[test]
+    fn render_text() {
+        assert_eq!(
+            "FAIL crates.io > Database dumps > Fastly HTTP 404\n",
+            render(Format::Text)
+        );
+    }
+
+    #[test]
+    fn render_json() {
+        let json = render(Format::Json);
+
+        assert!(json.ends_with("}\n"));
+        assert!(serde_json::from_str::<SavedRun>(&json).is_ok());
+    }
+
+    #[test]
+    fn render_html() {
+        assert!(render(Format::Html).starts_with("<!DOCTYPE html>"));
+    }
+
+    #[test]
+    fn trait_from_str() {
+        assert_eq!(Ok(Format::Json), "json".parse());
+        assert_eq!(Ok(Format::Html), "html".parse());
+        assert_eq!(
+            Err("expected 'text', 'json', or 'html', got 'xml'".to_string()),
+            "xml".parse::<Format>()
+        );
+    }
+
+    #[test]
+    fn trait_display() {
+        assert_eq!("text", Format::Text.to_string());
+        assert_eq!("html", Format::Html.to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Format>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Format>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Format>();
+    }
+}
@@ -0,0 +1,364 @@
+//! A self-contained HTML report of the results of the test suites
+
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use getset::CopyGetters;
+use typed_builder::TypedBuilder;
+
+use crate::environment::Environment;
+use crate::test::{Outcome, OutcomeCounts, Style, TestResult, TestSuiteResult};
+
+/// The styles of the report, which are inlined so that the page has no external dependencies
+const STYLE: &str = "\
+body { font-family: sans-serif; margin: 2em; max-width: 72em; }
+dl { display: grid; grid-template-columns: max-content auto; gap: 0.25em 1em; }
+dt { font-weight: bold; }
+dd { margin: 0; }
+ul { list-style: none; padding-left: 1em; }
+li { margin: 0.25em 0; }
+pre { background: #f6f8fa; padding: 0.5em; white-space: pre-wrap; }
+table { border-collapse: collapse; margin-bottom: 0.5em; }
+th, td { border-bottom: 1px solid #ddd; padding: 0.1em 0.75em; text-align: left; }
+summary { cursor: pointer; }
+.duration { color: #57606a; }
+.passed { color: #1a7f37; }
+.failed { color: #cf222e; }
+.expected-failure, .unexpected-pass { color: #9a6700; }
+";
+
+/// A self-contained HTML report of the results of the test suites
+///
+/// The report is a single page without scripts or external resources, so that it can be uploaded
+/// as a CI artifact and opened in any browser. It shows the metadata of the run, the tree of suites,
+/// groups, and tests with their durations, and the details of each failure in a collapsible
+/// section: the message, the metadata of the test, and the requests that it sent with the status
+/// and headers of their responses.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, TypedBuilder)]
+pub struct HtmlReport<'a> {
+    /// The environment in which the tests ran
+    #[getset(get_copy = "pub")]
+    env: &'a Environment,
+
+    /// The results of the test suites
+    #[getset(get_copy = "pub")]
+    results: &'a [TestSuiteResult],
+
+    /// When the run finished
+    #[getset(get_copy = "pub")]
+    time: DateTime<Utc>,
+}
+
+impl HtmlReport<'_> {
+    /// Write the result of a test as an item of the tree
+    fn write_test(&self, f: &mut Formatter<'_>, result: &TestResult) -> std::fmt::Result {
+        let outcome = result.outcome();
+
+        writeln!(
+            f,
+            "<li class=\"{}\">{} {} <span class=\"duration\">{}</span>",
+            class(outcome),
+            Style::Emoji.marker(outcome),
+            escape(result.name()),
+            format_duration(result.duration())
+        )?;
+
+        if outcome != Outcome::Passed {
+            let summary = result.message().as_deref().unwrap_or("Details");
+
+            writeln!(f, "<details>")?;
+            writeln!(f, "<summary>{}</summary>", escape(summary))?;
+
+            if let Some(reason) = result.expected_failure() {
+                writeln!(f, "<p>Expected failure: {}</p>", escape(reason))?;
+            }
+
+            if !result.metadata().is_empty() {
+                writeln!(f, "<pre>{}</pre>", escape(&result.metadata().to_string()))?;
+            }
+
+            for response in result.responses() {
+                writeln!(f, "<p><code>{}</code></p>", escape(&response.to_string()))?;
+
+                if !response.headers().is_empty() {
+                    writeln!(f, "<table>")?;
+
+                    for (name, value) in response.headers() {
+                        writeln!(
+                            f,
+                            "<tr><th>{}</th><td>{}</td></tr>",
+                            escape(name),
+                            escape(value)
+                        )?;
+                    }
+
+                    writeln!(f, "</table>")?;
+                }
+            }
+
+            writeln!(f, "</details>")?;
+        }
+
+        writeln!(f, "</li>")
+    }
+}
+
+impl Display for HtmlReport<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let env = escape(self.env.name());
+        let tests: Vec<&TestResult> = self
+            .results
+            .iter()
+            .flat_map(TestSuiteResult::test_results)
+            .map(|(_, result)| result)
+            .collect();
+        let counts: OutcomeCounts = tests.iter().map(|result| result.outcome()).collect();
+        let duration = tests.iter().map(|result| result.duration()).sum();
+
+        writeln!(f, "<!DOCTYPE html>")?;
+        writeln!(f, "<html lang=\"en\">")?;
+        writeln!(f, "<head>")?;
+        writeln!(f, "<meta charset=\"utf-8\">")?;
+        writeln!(f, "<title>Smoke tests: {env}</title>")?;
+        writeln!(f, "<style>\n{STYLE}</style>")?;
+        writeln!(f, "</head>")?;
+        writeln!(f, "<body>")?;
+        writeln!(f, "<h1>Smoke tests: {env}</h1>")?;
+
+        writeln!(f, "<dl>")?;
+        writeln!(f, "<dt>Environment</dt><dd>{env}</dd>")?;
+        writeln!(
+            f,
+            "<dt>Finished</dt><dd>{}</dd>",
+            self.time.to_rfc3339_opts(SecondsFormat::Secs, true)
+        )?;
+        writeln!(f, "<dt>Tests</dt><dd>{counts}</dd>")?;
+        writeln!(f, "<dt>Duration</dt><dd>{}</dd>", format_duration(duration))?;
+        writeln!(
+            f,
+            "<dt>Version</dt><dd>infra-smoke-test {}</dd>",
+            env!("CARGO_PKG_VERSION")
+        )?;
+        writeln!(f, "</dl>")?;
+
+        for suite in self.results {
+            let outcome = Outcome::from(suite.success());
+
+            writeln!(
+                f,
+                "<h2 class=\"{}\">{} {}</h2>",
+                class(outcome),
+                Style::Emoji.marker(outcome),
+                escape(suite.name())
+            )?;
+
+            for group in suite.results() {
+                let outcome = Outcome::from(group.success());
+
+                writeln!(
+                    f,
+                    "<h3 class=\"{}\">{} {}</h3>",
+                    class(outcome),
+                    Style::Emoji.marker(outcome),
+                    escape(group.name())
+                )?;
+
+                let mut results = group.results().clone();
+                results.sort();
+
+                writeln!(f, "<ul>")?;
+
+                for result in &results {
+                    self.write_test(f, result)?;
+                }
+
+                writeln!(f, "</ul>")?;
+            }
+        }
+
+        writeln!(f, "</body>")?;
+        writeln!(f, "</html>")
+    }
+}
+
+/// Return the CSS class for an outcome
+pub(crate) fn class(outcome: Outcome) -> &'static str {
+    match outcome {
+        Outcome::Passed => "passed",
+        Outcome::Failed => "failed",
+        Outcome::ExpectedFailure => "expected-failure",
+        Outcome::UnexpectedPass => "unexpected-pass",
+    }
+}
+
+/// Format a duration in milliseconds
+fn format_duration(duration: Duration) -> String {
+    format!("{} ms", duration.as_millis())
+}
+
+/// Escape text for HTML
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    use crate::test::{Metadata, RecordedResponse, TestGroupResult};
+    use crate::test_utils::*;
+
+    use super::*;
+
+    fn results() -> Vec<TestSuiteResult> {
+        let response = RecordedResponse::builder()
+            .method("GET")
+            .url("https://fastly-static.crates.io/db-dump.tar.gz")
+            .status(404)
+            .headers(vec![("x-cache".into(), "MISS".into())])
+            .build();
+
+        let fastly = TestResult::builder()
+            .name("Fastly")
+            .success(false)
+            .message(Some("expected <307>, got 404".into()))
+            .metadata(Metadata::builder().team("crates.io").build())
+            .responses(vec![response])
+            .duration(Duration::from_millis(132))
+            .build();
+        let cloudfront = TestResult::builder()
+            .name("CloudFront")
+            .success(true)
+            .duration(Duration::from_millis(98))
+            .build();
+
+        vec![TestSuiteResult::builder()
+            .name("crates.io")
+            .results(vec![TestGroupResult::builder()
+                .name("Database dumps")
+                .results(vec![fastly, cloudfront])
+                .build()])
+            .build()]
+    }
+
+    fn html() -> String {
+        let results = results();
+
+        HtmlReport::builder()
+            .env(&Environment::Staging)
+            .results(&results)
+            .time(Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap())
+            .build()
+            .to_string()
+    }
+
+    #[test]
+    fn display_run_metadata() {
+        let html = html();
+
+        assert!(html.contains("<dt>Environment</dt><dd>staging</dd>"));
+        assert!(html.contains("<dt>Finished</dt><dd>2024-06-01T12:00:00Z</dd>"));
+        assert!(html.contains("<dt>Tests</dt><dd>1 passed, 1 failed</dd>"));
+        assert!(html.contains("<dt>Duration</dt><dd>230 ms</dd>"));
+    }
+
+    #[test]
+    fn display_counts_expected_failures_separately() {
+        let xfail = TestResult::builder()
+            .name("S3")
+            .success(false)
+            .expected_failure(Some("not migrated yet".into()))
+            .build();
+        let mut results = results();
+        results.push(
+            TestSuiteResult::builder()
+                .name("Rust releases")
+                .results(vec![TestGroupResult::builder()
+                    .name("rustup.sh")
+                    .results(vec![xfail])
+                    .build()])
+                .build(),
+        );
+
+        let html = HtmlReport::builder()
+            .env(&Environment::Staging)
+            .results(&results)
+            .time(Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap())
+            .build()
+            .to_string();
+
+        assert!(html.contains("<dt>Tests</dt><dd>1 passed, 1 failed, 1 expected failure(s)</dd>"));
+    }
+
+    #[test]
+    fn display_tree_with_sorted_tests() {
+        let html = html();
+
+        let suite = html.find("<h2 class=\"failed\">❌ crates.io</h2>").unwrap();
+        let group = html
+            .find("<h3 class=\"failed\">❌ Database dumps</h3>")
+            .unwrap();
+        let cloudfront = html
+            .find("<li class=\"passed\">✅ CloudFront <span class=\"duration\">98 ms</span>")
+            .unwrap();
+        let fastly = html
+            .find("<li class=\"failed\">❌ Fastly <span class=\"duration\">132 ms</span>")
+            .unwrap();
+
+        assert!(suite < group && group < cloudfront && cloudfront < fastly);
+    }
+
+    #[test]
+    fn display_failure_details() {
+        let html = html();
+
+        let expected = "<details>\n\
+            <summary>expected &lt;307&gt;, got 404</summary>\n\
+            <pre>Team: crates.io\n</pre>\n\
+            <p><code>GET https://fastly-static.crates.io/db-dump.tar.gz (HTTP 404)</code></p>\n\
+            <table>\n\
+            <tr><th>x-cache</th><td>MISS</td></tr>\n\
+            </table>\n\
+            </details>\n";
+
+        assert!(html.contains(expected));
+        assert_eq!(1, html.matches("<details>").count());
+    }
+
+    #[test]
+    fn display_is_self_contained() {
+        let html = html();
+
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("<link"));
+        assert!(html.contains("<style>"));
+    }
+
+    #[test]
+    fn escape_special_characters() {
+        assert_eq!(
+            "&lt;a href=&quot;/&quot;&gt;A &amp; B&lt;/a&gt;",
+            escape("<a href=\"/\">A & B</a>")
+        );
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<HtmlReport>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<HtmlReport>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<HtmlReport>();
+    }
+}
@@ -0,0 +1,142 @@
+//! Metadata that describes tests and test groups
+
+use std::fmt::{Display, Formatter};
+
+use getset::CopyGetters;
+use typed_builder::TypedBuilder;
+
+/// Metadata that describes tests and test groups
+///
+/// The metadata explains what a test checks and who to contact when it fails. It is printed next to
+/// failing tests and by the `explain` subcommand, so that the person looking at a failure does not
+/// need to read the source code to understand it.
+#[derive(
+    Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, CopyGetters, TypedBuilder,
+)]
+pub struct Metadata {
+    /// A description of what is being checked and why
+    #[builder(default, setter(strip_option))]
+    #[getset(get_copy = "pub")]
+    description: Option<&'static str>,
+
+    /// The team that owns the checked infrastructure
+    #[builder(default, setter(strip_option))]
+    #[getset(get_copy = "pub")]
+    team: Option<&'static str>,
+
+    /// The URL of the issue that is tracked by the test
+    #[builder(default, setter(strip_option))]
+    #[getset(get_copy = "pub")]
+    issue: Option<&'static str>,
+
+    /// The URL of the runbook that explains how to respond to a failure
+    #[builder(default, setter(strip_option))]
+    #[getset(get_copy = "pub")]
+    runbook: Option<&'static str>,
+}
+
+impl Metadata {
+    /// Fill in the missing fields from the given fallback
+    ///
+    /// Tests inherit the metadata of their group, so that common fields like the owning team only
+    /// need to be set once.
+    pub fn or(self, fallback: Metadata) -> Self {
+        Self {
+            description: self.description.or(fallback.description),
+            team: self.team.or(fallback.team),
+            issue: self.issue.or(fallback.issue),
+            runbook: self.runbook.or(fallback.runbook),
+        }
+    }
+
+    /// Check if none of the fields are set
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Display for Metadata {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(description) = self.description {
+            writeln!(f, "{description}")?;
+        }
+
+        let fields = [
+            ("Team", self.team),
+            ("Issue", self.issue),
+            ("Runbook", self.runbook),
+        ];
+
+        for (label, value) in fields {
+            if let Some(value) = value {
+                writeln!(f, "{label}: {value}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::*;
+
+    use super::*;
+
+    #[test]
+    fn or_fills_missing_fields() {
+        let test = Metadata::builder().description("test").build();
+        let group = Metadata::builder()
+            .description("group")
+            .team("infra")
+            .build();
+
+        let expected = Metadata::builder()
+            .description("test")
+            .team("infra")
+            .build();
+
+        assert_eq!(expected, test.or(group));
+    }
+
+    #[test]
+    fn is_empty() {
+        assert!(Metadata::default().is_empty());
+        assert!(!Metadata::builder().team("infra").build().is_empty());
+    }
+
+    #[test]
+    fn trait_display() {
+        let metadata = Metadata::builder()
+            .description("description")
+            .team("infra")
+            .issue("https://example.com/issue")
+            .build();
+
+        let expected = indoc! {r#"
+            description
+            Team: infra
+            Issue: https://example.com/issue
+        "#};
+
+        assert_eq!(expected, metadata.to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Metadata>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Metadata>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Metadata>();
+    }
+}
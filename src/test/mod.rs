@@ -2,17 +2,45 @@
 
 use async_trait::async_trait;
 
+pub use self::explanation::Explanation;
+pub use self::filter::Filter;
+pub use self::format::Format;
+pub use self::html_report::HtmlReport;
+pub use self::metadata::Metadata;
+pub use self::outcome::{Outcome, OutcomeCounts};
+pub use self::recorded_response::RecordedResponse;
+pub use self::report::Report;
+pub use self::saved_run::{SavedRun, SavedRunError, SavedTest};
+pub use self::style::Style;
+pub use self::tag::Tag;
 pub use self::test_group::TestGroup;
 pub use self::test_group_result::TestGroupResult;
+pub use self::test_path::TestPath;
 pub use self::test_result::TestResult;
 pub use self::test_suite::TestSuite;
 pub use self::test_suite_result::TestSuiteResult;
+#[cfg(test)]
+pub(crate) use self::toggle_suite::ToggleSuite;
 
+mod explanation;
+mod filter;
+mod format;
+pub(crate) mod html_report;
+mod metadata;
+mod outcome;
+pub(crate) mod recorded_response;
+mod report;
+mod saved_run;
+mod style;
+mod tag;
 mod test_group;
 mod test_group_result;
+mod test_path;
 mod test_result;
 mod test_suite;
 mod test_suite_result;
+#[cfg(test)]
+mod toggle_suite;
 
 /// A test
 ///
@@ -21,6 +49,28 @@ mod test_suite_result;
 /// have side effects.
 #[async_trait]
 pub trait Test: Send + Sync {
+    /// Return the name of the test
+    ///
+    /// The name only needs to be unique within its group. Tests that are instantiated for every
+    /// configured CDN include the name of the CDN, e.g. `Fastly encoded`.
+    fn name(&self) -> &str;
+
+    /// Return the metadata that describes the test
+    ///
+    /// Tests inherit the metadata of their group, so only the fields that are specific to the test
+    /// need to be set here, for example its description.
+    fn metadata(&self) -> Metadata {
+        Metadata::default()
+    }
+
+    /// Return the tags that describe the test
+    ///
+    /// Tests inherit the tags of their group, so only tags that are specific to the test need to be
+    /// returned here, for example the CDN that the test requests.
+    fn tags(&self) -> Vec<Tag> {
+        Vec::new()
+    }
+
     /// Run the test
     async fn run(&self) -> TestResult;
 }
@@ -0,0 +1,180 @@
+//! The outcome of a test
+
+use std::fmt::{Display, Formatter};
+
+use getset::CopyGetters;
+use serde::{Deserialize, Serialize};
+
+/// The outcome of a test
+///
+/// Most tests either pass or fail. But tests can also be listed in a baseline of known failures,
+/// for example while a fix for a staging environment is pending. Failures of these tests are
+/// expected and don't fail the run, and passes are unexpected and should be looked at.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Outcome {
+    /// The test passed
+    Passed,
+
+    /// The test failed
+    Failed,
+
+    /// The test failed as expected by the baseline
+    ExpectedFailure,
+
+    /// The test passed, but the baseline expected it to fail
+    UnexpectedPass,
+}
+
+/// The number of tests with each outcome in a run
+///
+/// Reports count the outcomes the same way, so that an expected failure is never shown as a failure
+/// in one place and as a pass in another.
+#[derive(
+    Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, CopyGetters, Serialize,
+)]
+pub struct OutcomeCounts {
+    /// The number of tests that passed
+    #[getset(get_copy = "pub")]
+    passed: usize,
+
+    /// The number of tests that failed unexpectedly
+    #[getset(get_copy = "pub")]
+    failed: usize,
+
+    /// The number of tests that failed as expected by the baseline
+    #[getset(get_copy = "pub")]
+    expected_failures: usize,
+
+    /// The number of tests that passed although the baseline expected them to fail
+    #[getset(get_copy = "pub")]
+    unexpected_passes: usize,
+}
+
+impl Outcome {
+    /// Check if the outcome is acceptable and does not fail the run
+    pub fn is_ok(&self) -> bool {
+        !matches!(self, Outcome::Failed)
+    }
+}
+
+impl From<bool> for Outcome {
+    fn from(success: bool) -> Self {
+        if success {
+            Outcome::Passed
+        } else {
+            Outcome::Failed
+        }
+    }
+}
+
+impl FromIterator<Outcome> for OutcomeCounts {
+    fn from_iter<T: IntoIterator<Item = Outcome>>(outcomes: T) -> Self {
+        let mut counts = Self::default();
+
+        for outcome in outcomes {
+            match outcome {
+                Outcome::Passed => counts.passed += 1,
+                Outcome::Failed => counts.failed += 1,
+                Outcome::ExpectedFailure => counts.expected_failures += 1,
+                Outcome::UnexpectedPass => counts.unexpected_passes += 1,
+            }
+        }
+
+        counts
+    }
+}
+
+impl Display for OutcomeCounts {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} passed, {} failed", self.passed, self.failed)?;
+
+        if self.expected_failures > 0 {
+            write!(f, ", {} expected failure(s)", self.expected_failures)?;
+        }
+
+        if self.unexpected_passes > 0 {
+            write!(f, ", {} unexpected pass(es)", self.unexpected_passes)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::*;
+
+    use super::*;
+
+    #[test]
+    fn is_ok() {
+        assert!(Outcome::Passed.is_ok());
+        assert!(!Outcome::Failed.is_ok());
+        assert!(Outcome::ExpectedFailure.is_ok());
+        assert!(Outcome::UnexpectedPass.is_ok());
+    }
+
+    #[test]
+    fn serialize_as_kebab_case() {
+        assert_eq!(
+            "\"expected-failure\"",
+            serde_json::to_string(&Outcome::ExpectedFailure).unwrap()
+        );
+    }
+
+    #[test]
+    fn trait_from_bool() {
+        assert_eq!(Outcome::Passed, Outcome::from(true));
+        assert_eq!(Outcome::Failed, Outcome::from(false));
+    }
+
+    #[test]
+    fn counts_from_outcomes() {
+        let counts: OutcomeCounts = [
+            Outcome::Passed,
+            Outcome::Failed,
+            Outcome::ExpectedFailure,
+            Outcome::ExpectedFailure,
+            Outcome::Passed,
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(2, counts.passed());
+        assert_eq!(1, counts.failed());
+        assert_eq!(2, counts.expected_failures());
+        assert_eq!(0, counts.unexpected_passes());
+        assert_eq!(
+            "2 passed, 1 failed, 2 expected failure(s)",
+            counts.to_string()
+        );
+    }
+
+    #[test]
+    fn counts_display_without_baseline() {
+        let counts: OutcomeCounts = [Outcome::Passed].into_iter().collect();
+
+        assert_eq!("1 passed, 0 failed", counts.to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Outcome>();
+        assert_send::<OutcomeCounts>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Outcome>();
+        assert_sync::<OutcomeCounts>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Outcome>();
+        assert_unpin::<OutcomeCounts>();
+    }
+}
@@ -0,0 +1,144 @@
+//! A response that was recorded while a test was running
+
+use std::fmt::{Display, Formatter};
+
+use getset::{CopyGetters, Getters};
+use reqwest::header::HeaderMap;
+use typed_builder::TypedBuilder;
+
+/// A response that was recorded while a test was running
+///
+/// Tests send their requests through [`crate::http_client::send`], which records the method and URL
+/// of each request together with the status and headers of its response. The recorded responses
+/// are attached to the result of the test, so that the responses of the same logical request can
+/// be compared between environments.
+#[derive(
+    Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, Getters, TypedBuilder,
+)]
+pub struct RecordedResponse {
+    /// The method of the request, e.g. `GET`
+    #[builder(setter(into))]
+    #[getset(get = "pub")]
+    method: String,
+
+    /// The URL of the request
+    #[builder(setter(into))]
+    #[getset(get = "pub")]
+    url: String,
+
+    /// The status code of the response
+    #[getset(get_copy = "pub")]
+    status: u16,
+
+    /// The headers of the response with lowercase names, sorted by name
+    ///
+    /// Headers that appear multiple times are joined with `, `.
+    #[builder(default)]
+    #[getset(get = "pub")]
+    headers: Vec<(String, String)>,
+}
+
+impl RecordedResponse {
+    /// Return the value of the header with the given lowercase name
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Convert a header map into a sorted list of names and values
+pub(crate) fn headers_from_map(map: &HeaderMap) -> Vec<(String, String)> {
+    let mut headers: Vec<(String, String)> = map
+        .keys()
+        .map(|name| {
+            let value = map
+                .get_all(name)
+                .iter()
+                .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            (name.as_str().to_string(), value)
+        })
+        .collect();
+
+    headers.sort();
+
+    headers
+}
+
+impl Display for RecordedResponse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} (HTTP {})", self.method, self.url, self.status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use reqwest::header::HeaderValue;
+
+    use crate::test_utils::*;
+
+    use super::*;
+
+    #[test]
+    fn headers_from_map_joins_values() {
+        let mut map = HeaderMap::new();
+        map.append("X-Cache", HeaderValue::from_static("MISS"));
+        map.append("Vary", HeaderValue::from_static("Origin"));
+        map.append("Vary", HeaderValue::from_static("Accept"));
+
+        assert_eq!(
+            vec![
+                ("vary".to_string(), "Origin, Accept".to_string()),
+                ("x-cache".to_string(), "MISS".to_string()),
+            ],
+            headers_from_map(&map)
+        );
+    }
+
+    #[test]
+    fn header() {
+        let response = RecordedResponse::builder()
+            .method("GET")
+            .url("https://example.com")
+            .status(200)
+            .headers(vec![("x-cache".into(), "HIT".into())])
+            .build();
+
+        assert_eq!(Some("HIT"), response.header("x-cache"));
+        assert_eq!(None, response.header("vary"));
+    }
+
+    #[test]
+    fn trait_display() {
+        let response = RecordedResponse::builder()
+            .method("HEAD")
+            .url("https://example.com/db-dump.tar.gz")
+            .status(307)
+            .build();
+
+        assert_eq!(
+            "HEAD https://example.com/db-dump.tar.gz (HTTP 307)",
+            response.to_string()
+        );
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<RecordedResponse>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<RecordedResponse>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<RecordedResponse>();
+    }
+}
@@ -0,0 +1,233 @@
+//! A report of the results of the test suites
+
+use std::fmt::{Display, Formatter};
+
+use getset::CopyGetters;
+use indent::indent_all_by;
+use typed_builder::TypedBuilder;
+
+use crate::test::{Outcome, Style, TestSuiteResult};
+
+/// A report of the results of the test suites
+///
+/// The report prints the results of the test suites for a human reader. By default, it prints the
+/// full tree of suites, groups, and tests. In quiet mode, it prints only the failing tests together
+/// with their full path so that failures can be spotted in long logs. Tests with an outcome that
+/// deviates from the baseline of known failures are printed in quiet mode as well.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, TypedBuilder)]
+pub struct Report<'a> {
+    /// The results of the test suites
+    #[getset(get_copy = "pub")]
+    results: &'a [TestSuiteResult],
+
+    /// The style of the markers for passing and failing tests
+    #[builder(default)]
+    #[getset(get_copy = "pub")]
+    style: Style,
+
+    /// Whether to print only the failing tests
+    #[builder(default)]
+    #[getset(get_copy = "pub")]
+    quiet: bool,
+}
+
+impl Display for Report<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if !self.quiet {
+            for result in self.results {
+                writeln!(f, "{}", result.render(self.style))?;
+            }
+
+            return Ok(());
+        }
+
+        for suite in self.results {
+            for (path, result) in suite
+                .test_results()
+                .filter(|(_, result)| result.outcome() != Outcome::Passed)
+            {
+                write!(f, "{} {path}", self.style.marker(result.outcome()))?;
+
+                if let Some(message) = result.message() {
+                    write!(f, " {message}")?;
+                }
+
+                match (result.outcome(), result.expected_failure()) {
+                    (Outcome::ExpectedFailure, Some(reason)) => {
+                        write!(f, " (expected failure: {reason})")?;
+                    }
+                    (Outcome::UnexpectedPass, Some(reason)) => {
+                        write!(f, " (unexpected pass, expected failure: {reason})")?;
+                    }
+                    _ => {}
+                }
+
+                writeln!(f)?;
+
+                if result.outcome() == Outcome::Failed && !result.metadata().is_empty() {
+                    write!(f, "{}", indent_all_by(2, result.metadata().to_string()))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::test::{TestGroupResult, TestResult};
+    use crate::test_utils::*;
+
+    use super::*;
+
+    fn results() -> Vec<TestSuiteResult> {
+        let successful_group = TestGroupResult::builder()
+            .name("success")
+            .results(vec![TestResult::builder()
+                .name("test 1")
+                .success(true)
+                .build()])
+            .build();
+
+        let failing_group = TestGroupResult::builder()
+            .name("failure")
+            .results(vec![
+                TestResult::builder().name("test 1").success(true).build(),
+                TestResult::builder()
+                    .name("test 2")
+                    .success(false)
+                    .message(Some("message".into()))
+                    .build(),
+            ])
+            .build();
+
+        vec![TestSuiteResult::builder()
+            .name("suite")
+            .results(vec![successful_group, failing_group])
+            .build()]
+    }
+
+    #[test]
+    fn trait_display_with_emoji() {
+        let results = results();
+        let report = Report::builder().results(&results).build();
+
+        let expected = indoc! {r#"
+            ❌ suite
+              ❌ failure
+                ✅ test 1
+                ❌ test 2 message
+              ✅ success
+                ✅ test 1
+
+        "#};
+
+        assert_eq!(expected, report.to_string());
+    }
+
+    #[test]
+    fn trait_display_with_plain_style() {
+        let results = results();
+        let report = Report::builder()
+            .results(&results)
+            .style(Style::Plain)
+            .build();
+
+        let expected = indoc! {r#"
+            FAIL suite
+              FAIL failure
+                PASS test 1
+                FAIL test 2 message
+              PASS success
+                PASS test 1
+
+        "#};
+
+        assert_eq!(expected, report.to_string());
+    }
+
+    #[test]
+    fn trait_display_quiet() {
+        let results = results();
+        let report = Report::builder().results(&results).quiet(true).build();
+
+        assert_eq!("❌ suite > failure > test 2 message\n", report.to_string());
+    }
+
+    #[test]
+    fn trait_display_quiet_and_plain() {
+        let results = results();
+        let report = Report::builder()
+            .results(&results)
+            .style(Style::Plain)
+            .quiet(true)
+            .build();
+
+        assert_eq!(
+            "FAIL suite > failure > test 2 message\n",
+            report.to_string()
+        );
+    }
+
+    #[test]
+    fn trait_display_quiet_with_baseline_outcomes() {
+        let results = vec![TestSuiteResult::builder()
+            .name("suite")
+            .results(vec![TestGroupResult::builder()
+                .name("group")
+                .results(vec![
+                    TestResult::builder()
+                        .name("xfail")
+                        .success(false)
+                        .message(Some("message".into()))
+                        .expected_failure(Some("not migrated yet".into()))
+                        .build(),
+                    TestResult::builder()
+                        .name("xpass")
+                        .success(true)
+                        .expected_failure(Some("fixed upstream".into()))
+                        .build(),
+                ])
+                .build()])
+            .build()];
+        let report = Report::builder()
+            .results(&results)
+            .style(Style::Plain)
+            .quiet(true)
+            .build();
+
+        let expected = indoc! {r#"
+            XFAIL suite > group > xfail message (expected failure: not migrated yet)
+            XPASS suite > group > xpass (unexpected pass, expected failure: fixed upstream)
+        "#};
+
+        assert_eq!(expected, report.to_string());
+    }
+
+    #[test]
+    fn trait_display_quiet_without_failures() {
+        let results = vec![TestSuiteResult::builder().name("suite").build()];
+        let report = Report::builder().results(&results).quiet(true).build();
+
+        assert_eq!("", report.to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Report>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Report>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Report>();
+    }
+}
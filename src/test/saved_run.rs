@@ -0,0 +1,194 @@
+//! The results of a run in a format that can be saved
+
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+
+use crate::environment::Environment;
+use crate::test::{Outcome, TestPath, TestSuiteResult};
+
+/// The results of a run in a format that can be saved
+///
+/// A saved run is a flat list of the tests with their outcome and message. It is printed as JSON
+/// with `--format json`, and two saved runs can be compared with the `diff` subcommand, for example
+/// before and after a change to the configuration of a CDN.
+#[derive(
+    Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, Getters, Deserialize, Serialize,
+)]
+pub struct SavedRun {
+    /// The name of the environment that was tested
+    #[getset(get = "pub")]
+    environment: String,
+
+    /// When the run finished
+    #[getset(get_copy = "pub")]
+    time: DateTime<Utc>,
+
+    /// The results of the tests, in the order in which they are printed
+    #[getset(get = "pub")]
+    tests: Vec<SavedTest>,
+}
+
+/// The result of a test in a saved run
+#[derive(
+    Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, Getters, Deserialize, Serialize,
+)]
+pub struct SavedTest {
+    /// The path of the test
+    #[getset(get = "pub")]
+    path: TestPath,
+
+    /// Whether the test passed
+    #[getset(get_copy = "pub")]
+    success: bool,
+
+    /// The outcome of the test, which takes the baseline into account
+    #[getset(get_copy = "pub")]
+    outcome: Outcome,
+
+    /// The message of the result, which usually explains why the test failed
+    #[getset(get = "pub")]
+    message: Option<String>,
+
+    /// How long the test took to run in milliseconds
+    #[getset(get_copy = "pub")]
+    duration_ms: u64,
+}
+
+/// Errors that can occur when loading a saved run
+#[derive(Debug)]
+pub enum SavedRunError {
+    /// The file could not be read
+    Read(std::io::Error),
+
+    /// The file is not a valid saved run
+    Parse(serde_json::Error),
+}
+
+impl SavedRun {
+    /// Create a saved run from the results of the test suites
+    pub fn new(env: &Environment, results: &[TestSuiteResult], time: DateTime<Utc>) -> Self {
+        let tests = results
+            .iter()
+            .flat_map(TestSuiteResult::test_results)
+            .map(|(path, result)| SavedTest {
+                path,
+                success: result.success(),
+                outcome: result.outcome(),
+                message: result.message().clone(),
+                duration_ms: u64::try_from(result.duration().as_millis()).unwrap_or(u64::MAX),
+            })
+            .collect();
+
+        Self {
+            environment: env.name().into(),
+            time,
+            tests,
+        }
+    }
+
+    /// Load a saved run from the JSON file at the given path
+    pub fn load(path: &Path) -> Result<Self, SavedRunError> {
+        let json = std::fs::read_to_string(path).map_err(SavedRunError::Read)?;
+        serde_json::from_str(&json).map_err(SavedRunError::Parse)
+    }
+
+    /// Return the saved run as pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a saved run can always be serialized")
+    }
+}
+
+impl Display for SavedRunError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SavedRunError::Read(error) => write!(f, "failed to read the saved run: {error}"),
+            SavedRunError::Parse(error) => write!(f, "failed to parse the saved run: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for SavedRunError {}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::TimeZone;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::test::{TestGroupResult, TestResult};
+    use crate::test_utils::*;
+
+    use super::*;
+
+    fn saved_run() -> SavedRun {
+        let result = TestResult::builder()
+            .name("Fastly")
+            .success(false)
+            .message(Some("HTTP 404".into()))
+            .expected_failure(Some("not deployed yet".into()))
+            .duration(Duration::from_millis(132))
+            .build();
+
+        let results = vec![TestSuiteResult::builder()
+            .name("crates.io")
+            .results(vec![TestGroupResult::builder()
+                .name("Database dumps")
+                .results(vec![result])
+                .build()])
+            .build()];
+
+        let time = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+
+        SavedRun::new(&Environment::Staging, &results, time)
+    }
+
+    #[test]
+    fn to_json() {
+        let expected = indoc! {r#"
+            {
+              "environment": "staging",
+              "time": "2024-06-01T12:00:00Z",
+              "tests": [
+                {
+                  "path": "crates.io > Database dumps > Fastly",
+                  "success": false,
+                  "outcome": "expected-failure",
+                  "message": "HTTP 404",
+                  "duration_ms": 132
+                }
+              ]
+            }"#};
+
+        assert_eq!(expected, saved_run().to_json());
+    }
+
+    #[test]
+    fn to_json_and_back() {
+        let saved_run = saved_run();
+
+        let parsed: SavedRun = serde_json::from_str(&saved_run.to_json()).unwrap();
+
+        assert_eq!(saved_run, parsed);
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<SavedRun>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<SavedRun>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<SavedRun>();
+    }
+}
@@ -0,0 +1,83 @@
+//! Styles to print test results
+
+use crate::test::Outcome;
+
+/// Styles to print test results
+///
+/// The results of the smoke tests are printed with a marker that indicates whether a test passed
+/// or failed. By default, emoji are used for the markers. Since emoji are not rendered correctly by
+/// every pager or log aggregator, the results can also be printed with plain ASCII markers.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub enum Style {
+    /// Print emoji to mark passing and failing tests
+    #[default]
+    Emoji,
+
+    /// Print `PASS` and `FAIL` to mark passing and failing tests
+    Plain,
+}
+
+impl Style {
+    /// Return the marker for the given outcome
+    pub fn marker(&self, outcome: impl Into<Outcome>) -> &'static str {
+        match (self, outcome.into()) {
+            (Style::Emoji, Outcome::Passed) => "✅",
+            (Style::Emoji, Outcome::Failed) => "❌",
+            (Style::Emoji, Outcome::ExpectedFailure) => "⚠️",
+            (Style::Emoji, Outcome::UnexpectedPass) => "❗",
+            (Style::Plain, Outcome::Passed) => "PASS",
+            (Style::Plain, Outcome::Failed) => "FAIL",
+            (Style::Plain, Outcome::ExpectedFailure) => "XFAIL",
+            (Style::Plain, Outcome::UnexpectedPass) => "XPASS",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::*;
+
+    use super::*;
+
+    #[test]
+    fn marker_with_emoji() {
+        assert_eq!("✅", Style::Emoji.marker(true));
+        assert_eq!("❌", Style::Emoji.marker(false));
+    }
+
+    #[test]
+    fn marker_with_plain() {
+        assert_eq!("PASS", Style::Plain.marker(true));
+        assert_eq!("FAIL", Style::Plain.marker(false));
+    }
+
+    #[test]
+    fn marker_with_baseline_outcomes() {
+        assert_eq!("XFAIL", Style::Plain.marker(Outcome::ExpectedFailure));
+        assert_eq!("XPASS", Style::Plain.marker(Outcome::UnexpectedPass));
+        assert_eq!("⚠️", Style::Emoji.marker(Outcome::ExpectedFailure));
+        assert_eq!("❗", Style::Emoji.marker(Outcome::UnexpectedPass));
+    }
+
+    #[test]
+    fn trait_default() {
+        assert_eq!(Style::Emoji, Style::default());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Style>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Style>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Style>();
+    }
+}
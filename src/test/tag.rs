@@ -0,0 +1,114 @@
+//! Tags that describe tests and test groups
+
+use std::fmt::{Display, Formatter};
+
+/// Tags that describe tests and test groups
+///
+/// Tags are short labels that describe what a test checks, for example the CDN that it requests or
+/// the issue that it guards against. By convention, tags consist of a kind and a value separated by
+/// a colon, e.g. `cdn:fastly` or `issue:crates.io#4891`. Tags are used to select the tests that are
+/// run, for example to run only the checks for Fastly during an incident.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Tag(String);
+
+impl Tag {
+    /// Create a new tag
+    pub fn new(tag: impl Into<String>) -> Self {
+        Self(tag.into())
+    }
+
+    /// Create a tag for the Content Delivery Network that a test requests
+    pub fn cdn(name: &str) -> Self {
+        Self(format!("cdn:{name}"))
+    }
+
+    /// Create a tag for the origin that a test requests directly, bypassing the CDNs
+    pub fn origin(name: &str) -> Self {
+        Self(format!("origin:{name}"))
+    }
+
+    /// Create a tag for the issue that a test guards against
+    pub fn issue(name: &str) -> Self {
+        Self(format!("issue:{name}"))
+    }
+
+    /// Create a tag for the service that a test checks
+    pub fn service(name: &str) -> Self {
+        Self(format!("service:{name}"))
+    }
+
+    /// Return the tag as a string slice
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for Tag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<&str> for Tag {
+    fn from(tag: &str) -> Self {
+        Self::new(tag)
+    }
+}
+
+impl From<String> for Tag {
+    fn from(tag: String) -> Self {
+        Self::new(tag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::*;
+
+    use super::*;
+
+    #[test]
+    fn cdn() {
+        assert_eq!("cdn:fastly", Tag::cdn("fastly").as_str());
+    }
+
+    #[test]
+    fn issue() {
+        assert_eq!(
+            "issue:crates.io#4891",
+            Tag::issue("crates.io#4891").as_str()
+        );
+    }
+
+    #[test]
+    fn origin() {
+        assert_eq!("origin:s3", Tag::origin("s3").as_str());
+    }
+
+    #[test]
+    fn service() {
+        assert_eq!("service:doc-router", Tag::service("doc-router").as_str());
+    }
+
+    #[test]
+    fn trait_display() {
+        assert_eq!("cdn:cloudfront", Tag::from("cdn:cloudfront").to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Tag>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Tag>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Tag>();
+    }
+}
@@ -1,8 +1,12 @@
 //! A group of tests that belong together
 
+use std::time::Instant;
+
 use async_trait::async_trait;
+use tokio::task::JoinSet;
 
-use crate::test::TestGroupResult;
+use crate::http_client::record;
+use crate::test::{Filter, Metadata, Tag, Test, TestGroupResult};
 
 /// A group of tests that belong together
 ///
@@ -11,6 +15,250 @@ use crate::test::TestGroupResult;
 /// run together and the results are aggregated to produce a single result for the group.
 #[async_trait]
 pub trait TestGroup: Send + Sync {
-    /// Run the tests in this group
-    async fn run(&self) -> TestGroupResult;
+    /// Return the name of the test group
+    fn name(&self) -> &'static str;
+
+    /// Return the metadata that describes the test group
+    fn metadata(&self) -> Metadata {
+        Metadata::default()
+    }
+
+    /// Return the tags that describe all tests in this group
+    fn tags(&self) -> Vec<Tag> {
+        Vec::new()
+    }
+
+    /// Return the tests in this group
+    fn tests(&self) -> Vec<Box<dyn Test>>;
+
+    /// Return whether the tests in this group can run concurrently
+    ///
+    /// Groups whose tests depend on each other, or that should not send their requests at the same
+    /// time, can return `false` to run their tests one after another in the order of [`tests`].
+    ///
+    /// [`tests`]: TestGroup::tests
+    fn concurrent(&self) -> bool {
+        true
+    }
+
+    /// Run the tests in this group that are selected by the filter
+    ///
+    /// The tests are run concurrently, unless the group opts out with [`concurrent`]. Each test is
+    /// matched against the filter with its own tags and the tags of the group. The results are
+    /// annotated with the metadata and the tags of the tests, the responses that the tests
+    /// received, and the time they took to run.
+    ///
+    /// [`concurrent`]: TestGroup::concurrent
+    async fn run(&self, filter: &Filter) -> TestGroupResult {
+        let group_tags = self.tags();
+        let group_metadata = self.metadata();
+
+        let selected = self.tests().into_iter().filter_map(|test| {
+            let tags: Vec<Tag> = group_tags.iter().cloned().chain(test.tags()).collect();
+
+            if !filter.matches(&tags) {
+                return None;
+            }
+
+            let metadata = test.metadata().or(group_metadata);
+
+            Some(async move {
+                let start = Instant::now();
+                let result = record(test.run()).await;
+
+                result
+                    .with_duration(start.elapsed())
+                    .with_metadata(metadata)
+                    .with_tags(tags)
+            })
+        });
+
+        let results = if self.concurrent() {
+            let mut js = JoinSet::new();
+            for test in selected {
+                js.spawn(test);
+            }
+
+            js.join_all().await
+        } else {
+            let mut results = Vec::new();
+            for test in selected {
+                results.push(test.await);
+            }
+
+            results
+        };
+
+        TestGroupResult::builder()
+            .name(self.name())
+            .results(results)
+            .build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test::TestResult;
+
+    use super::*;
+
+    struct Fastly;
+
+    #[async_trait]
+    impl Test for Fastly {
+        fn name(&self) -> &'static str {
+            "Fastly"
+        }
+
+        fn metadata(&self) -> Metadata {
+            Metadata::builder().description("Fastly").build()
+        }
+
+        fn tags(&self) -> Vec<Tag> {
+            vec![Tag::cdn("fastly")]
+        }
+
+        async fn run(&self) -> TestResult {
+            TestResult::builder().name("Fastly").success(true).build()
+        }
+    }
+
+    struct CloudFront;
+
+    #[async_trait]
+    impl Test for CloudFront {
+        fn name(&self) -> &'static str {
+            "CloudFront"
+        }
+
+        fn tags(&self) -> Vec<Tag> {
+            vec![Tag::cdn("cloudfront")]
+        }
+
+        async fn run(&self) -> TestResult {
+            TestResult::builder()
+                .name("CloudFront")
+                .success(true)
+                .build()
+        }
+    }
+
+    struct Group;
+
+    impl TestGroup for Group {
+        fn name(&self) -> &'static str {
+            "group"
+        }
+
+        fn metadata(&self) -> Metadata {
+            Metadata::builder()
+                .description("group")
+                .team("infra")
+                .build()
+        }
+
+        fn tags(&self) -> Vec<Tag> {
+            vec![Tag::service("service")]
+        }
+
+        fn tests(&self) -> Vec<Box<dyn Test>> {
+            vec![Box::new(CloudFront), Box::new(Fastly)]
+        }
+    }
+
+    struct SequentialGroup;
+
+    impl TestGroup for SequentialGroup {
+        fn name(&self) -> &'static str {
+            "sequential"
+        }
+
+        fn tests(&self) -> Vec<Box<dyn Test>> {
+            vec![Box::new(Fastly), Box::new(CloudFront)]
+        }
+
+        fn concurrent(&self) -> bool {
+            false
+        }
+    }
+
+    #[tokio::test]
+    async fn run_all_tests_without_filter() {
+        let result = Group.run(&Filter::default()).await;
+
+        let mut names: Vec<&str> = result.results().iter().map(|test| test.name()).collect();
+        names.sort();
+
+        assert_eq!("group", result.name());
+        assert_eq!(vec!["CloudFront", "Fastly"], names);
+        assert!(result.success());
+    }
+
+    #[tokio::test]
+    async fn run_sequential_tests_in_order() {
+        let result = SequentialGroup.run(&Filter::default()).await;
+
+        let names: Vec<&str> = result.results().iter().map(|test| test.name()).collect();
+
+        assert_eq!(vec!["Fastly", "CloudFront"], names);
+    }
+
+    #[tokio::test]
+    async fn run_annotates_results_with_metadata() {
+        let result = Group.run(&Filter::default()).await;
+
+        let fastly = result
+            .results()
+            .iter()
+            .find(|test| test.name() == "Fastly")
+            .unwrap();
+        let cloudfront = result
+            .results()
+            .iter()
+            .find(|test| test.name() == "CloudFront")
+            .unwrap();
+
+        assert_eq!(Some("Fastly"), fastly.metadata().description());
+        assert_eq!(Some("infra"), fastly.metadata().team());
+        assert_eq!(Some("group"), cloudfront.metadata().description());
+    }
+
+    #[tokio::test]
+    async fn run_annotates_results_with_tags() {
+        let filter = Filter::builder().tags(vec![Tag::cdn("fastly")]).build();
+
+        let result = Group.run(&filter).await;
+
+        assert_eq!(
+            &vec![Tag::service("service"), Tag::cdn("fastly")],
+            result.results()[0].tags()
+        );
+    }
+
+    #[tokio::test]
+    async fn run_tests_with_test_tag() {
+        let filter = Filter::builder().tags(vec![Tag::cdn("fastly")]).build();
+
+        let result = Group.run(&filter).await;
+
+        let names: Vec<&str> = result.results().iter().map(|test| test.name()).collect();
+
+        assert_eq!(vec!["Fastly"], names);
+    }
+
+    #[tokio::test]
+    async fn run_tests_with_group_tag() {
+        let filter = Filter::builder()
+            .tags(vec![Tag::service("service")])
+            .excluded_tags(vec![Tag::cdn("fastly")])
+            .build();
+
+        let result = Group.run(&filter).await;
+
+        let names: Vec<&str> = result.results().iter().map(|test| test.name()).collect();
+
+        assert_eq!(vec!["CloudFront"], names);
+    }
 }
@@ -2,17 +2,28 @@
 
 use std::fmt::{Display, Formatter};
 
-use getset::{CopyGetters, Getters};
+use getset::{CopyGetters, Getters, MutGetters};
+use indent::indent_all_by;
 use typed_builder::TypedBuilder;
 
-use crate::test::TestResult;
+use crate::test::{Style, TestResult};
 
 /// The result of a group of tests
 ///
 /// A test group result contains the name of the group and the results of the individual tests in
 /// the group.
 #[derive(
-    Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, Getters, TypedBuilder,
+    Clone,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    Debug,
+    CopyGetters,
+    Getters,
+    MutGetters,
+    TypedBuilder,
 )]
 pub struct TestGroupResult {
     /// The name of the test group
@@ -21,7 +32,7 @@ pub struct TestGroupResult {
 
     /// The results of the individual tests in the group
     #[builder(default)]
-    #[getset(get = "pub")]
+    #[getset(get = "pub", get_mut = "pub")]
     results: Vec<TestResult>,
 }
 
@@ -29,27 +40,30 @@ impl TestGroupResult {
     /// Check if all the results in the group are successful
     ///
     /// A test group is successful if all the tests in the group are successful. This method
-    /// iterates over the individual test results and checks if all of them are successful.
+    /// iterates over the individual test results and checks if all of them are successful. Failures
+    /// that are expected by the baseline don't fail the group.
     pub fn success(&self) -> bool {
-        self.results.iter().all(|result| result.success())
+        self.results.iter().all(|result| result.outcome().is_ok())
     }
-}
 
-impl Display for TestGroupResult {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let emoji = if self.success() { "✅" } else { "❌" };
-        let display = format!("{} {}", emoji, self.name());
-
-        writeln!(f, "{display}")?;
+    /// Render the result and the results of its tests with the given style
+    pub fn render(&self, style: Style) -> String {
+        let mut display = format!("{} {}\n", style.marker(self.success()), self.name());
 
         let mut sorted_results = self.results.clone();
         sorted_results.sort();
 
         for result in sorted_results {
-            writeln!(f, "  {result}")?;
+            display.push_str(&indent_all_by(2, format!("{}\n", result.render(style))));
         }
 
-        Ok(())
+        display
+    }
+}
+
+impl Display for TestGroupResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render(Style::default()))
     }
 }
 
@@ -88,6 +102,20 @@ mod tests {
         assert!(!group_result.success());
     }
 
+    #[test]
+    fn success_with_expected_failure() {
+        let group_result = TestGroupResult::builder()
+            .name("group")
+            .results(vec![TestResult::builder()
+                .name("test 1")
+                .success(false)
+                .expected_failure(Some("reason".into()))
+                .build()])
+            .build();
+
+        assert!(group_result.success());
+    }
+
     #[test]
     fn trait_display_success_without_message() {
         let test_result = TestResult::builder().name("test").success(true).build();
@@ -124,6 +152,26 @@ mod tests {
         assert_eq!(expected, format!("{}", group_result));
     }
 
+    #[test]
+    fn render_plain_failure_with_message() {
+        let test_result = TestResult::builder()
+            .name("test")
+            .success(false)
+            .message(Some("message".into()))
+            .build();
+        let group_result = TestGroupResult::builder()
+            .name("group")
+            .results(vec![test_result])
+            .build();
+
+        let expected = indoc! {r#"
+            FAIL group
+              FAIL test message
+        "#};
+
+        assert_eq!(expected, group_result.render(Style::Plain));
+    }
+
     #[test]
     fn trait_send() {
         assert_send::<TestResult>();
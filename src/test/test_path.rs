@@ -0,0 +1,141 @@
+//! The path of a test
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+
+/// The separator between the components of a test path
+const SEPARATOR: &str = " > ";
+
+/// The character that separates the components when parsing a test path
+const SEPARATOR_CHAR: char = '>';
+
+/// The path of a test
+///
+/// Tests are organized in suites and groups, and their names are only unique within their group. The
+/// path of a test combines the names of the suite, the group, and the test to uniquely identify the
+/// test across all suites, e.g. `crates.io > Database dumps > Fastly`.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Getters, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TestPath {
+    /// The name of the test suite
+    #[getset(get = "pub")]
+    suite: String,
+
+    /// The name of the test group
+    #[getset(get = "pub")]
+    group: String,
+
+    /// The name of the test
+    #[getset(get = "pub")]
+    test: String,
+}
+
+impl TestPath {
+    /// Create a new test path
+    pub fn new(
+        suite: impl Into<String>,
+        group: impl Into<String>,
+        test: impl Into<String>,
+    ) -> Self {
+        Self {
+            suite: suite.into(),
+            group: group.into(),
+            test: test.into(),
+        }
+    }
+}
+
+impl Display for TestPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{SEPARATOR}{}{SEPARATOR}{}",
+            self.suite, self.group, self.test
+        )
+    }
+}
+
+impl FromStr for TestPath {
+    type Err = String;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        let components: Vec<&str> = path.split(SEPARATOR_CHAR).map(str::trim).collect();
+
+        match components.as_slice() {
+            [suite, group, test] if !suite.is_empty() && !group.is_empty() && !test.is_empty() => {
+                Ok(Self::new(*suite, *group, *test))
+            }
+            _ => Err(format!(
+                "expected a path in the format 'suite{SEPARATOR}group{SEPARATOR}test', got '{path}'"
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for TestPath {
+    type Error = String;
+
+    fn try_from(path: String) -> Result<Self, Self::Error> {
+        path.parse()
+    }
+}
+
+impl From<TestPath> for String {
+    fn from(path: TestPath) -> Self {
+        path.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::*;
+
+    use super::*;
+
+    #[test]
+    fn trait_from_str() {
+        let path: TestPath = "crates.io > Database dumps > Fastly".parse().unwrap();
+
+        assert_eq!(TestPath::new("crates.io", "Database dumps", "Fastly"), path);
+    }
+
+    #[test]
+    fn trait_from_str_without_spaces() {
+        let path: TestPath = "rustup>win.rustup.rs>i686".parse().unwrap();
+
+        assert_eq!(TestPath::new("rustup", "win.rustup.rs", "i686"), path);
+    }
+
+    #[test]
+    fn trait_from_str_with_missing_component() {
+        assert!("crates.io > Database dumps".parse::<TestPath>().is_err());
+        assert!("crates.io > > Fastly".parse::<TestPath>().is_err());
+    }
+
+    #[test]
+    fn trait_display() {
+        let path = TestPath::new("crates.io", "Database dumps", "Fastly");
+
+        assert_eq!("crates.io > Database dumps > Fastly", path.to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<TestPath>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<TestPath>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<TestPath>();
+    }
+}
@@ -1,21 +1,28 @@
 //! The result of a test
 
 use std::fmt::{Display, Formatter};
+use std::time::Duration;
 
-use getset::{CopyGetters, Getters};
+use getset::{CopyGetters, Getters, Setters};
+use indent::indent_all_by;
 use typed_builder::TypedBuilder;
 
+use crate::test::{Metadata, Outcome, RecordedResponse, Style, Tag};
+
 /// The result of a test
 ///
 /// This struct represents the result of a test. It contains the name of the test, whether it was
-/// successful, and an optional message.
+/// successful, an optional message, and the metadata that describes the test. If the test is listed
+/// in the baseline of known failures, the result also contains the reason why it is expected to
+/// fail. The responses that the test received are recorded, so that they can be compared between
+/// environments, as is the time that the test took to run.
 #[derive(
-    Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, Getters, TypedBuilder,
+    Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, Getters, Setters, TypedBuilder,
 )]
 pub struct TestResult {
     /// The name of the test
-    #[getset(get_copy = "pub")]
-    name: &'static str,
+    #[builder(setter(into))]
+    name: String,
 
     /// Whether the test was successful
     #[getset(get_copy = "pub")]
@@ -25,19 +32,110 @@ pub struct TestResult {
     #[builder(default)]
     #[getset(get = "pub")]
     message: Option<String>,
+
+    /// The metadata that describes the test
+    #[builder(default)]
+    #[getset(get_copy = "pub")]
+    metadata: Metadata,
+
+    /// The reason why the test is expected to fail, if it is listed in the baseline
+    #[builder(default)]
+    #[getset(get = "pub", set = "pub")]
+    expected_failure: Option<String>,
+
+    /// The responses to the requests that the test sent, in the order they were sent
+    #[builder(default)]
+    #[getset(get = "pub")]
+    responses: Vec<RecordedResponse>,
+
+    /// How long the test took to run
+    #[builder(default)]
+    #[getset(get_copy = "pub")]
+    duration: Duration,
+
+    /// The tags of the test, including the tags that it inherits from its group
+    #[builder(default)]
+    #[getset(get = "pub")]
+    tags: Vec<Tag>,
 }
 
-impl Display for TestResult {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let emoji = if self.success { "✅" } else { "❌" };
-        let mut display = format!("{} {}", emoji, self.name);
+impl TestResult {
+    /// Return the name of the test
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Attach the metadata that describes the test to the result
+    pub fn with_metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Attach the responses that were recorded while the test was running to the result
+    pub fn with_responses(mut self, responses: Vec<RecordedResponse>) -> Self {
+        self.responses = responses;
+        self
+    }
+
+    /// Attach the time that the test took to run to the result
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Attach the tags of the test to the result
+    pub fn with_tags(mut self, tags: Vec<Tag>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Return the outcome of the test
+    ///
+    /// The outcome takes the baseline of known failures into account. A failure of a test that is
+    /// listed in the baseline is expected, while a pass is unexpected.
+    pub fn outcome(&self) -> Outcome {
+        match (self.success, self.expected_failure.is_some()) {
+            (true, false) => Outcome::Passed,
+            (false, false) => Outcome::Failed,
+            (false, true) => Outcome::ExpectedFailure,
+            (true, true) => Outcome::UnexpectedPass,
+        }
+    }
+
+    /// Render the result with the given style
+    ///
+    /// The metadata of failing tests is printed below the result, so that the reader knows what the
+    /// test checks and who to contact.
+    pub fn render(&self, style: Style) -> String {
+        let mut display = format!("{} {}", style.marker(self.outcome()), self.name);
 
         if let Some(message) = &self.message {
             display.push(' ');
             display.push_str(message);
         }
 
-        write!(f, "{display}")
+        match (self.outcome(), &self.expected_failure) {
+            (Outcome::ExpectedFailure, Some(reason)) => {
+                display.push_str(&format!(" (expected failure: {reason})"));
+            }
+            (Outcome::UnexpectedPass, Some(reason)) => {
+                display.push_str(&format!(" (unexpected pass, expected failure: {reason})"));
+            }
+            _ => {}
+        }
+
+        if self.outcome() == Outcome::Failed && !self.metadata.is_empty() {
+            display.push('\n');
+            display.push_str(indent_all_by(2, self.metadata.to_string()).trim_end());
+        }
+
+        display
+    }
+}
+
+impl Display for TestResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render(Style::default()))
     }
 }
 
@@ -67,6 +165,95 @@ mod tests {
         assert_eq!(format!("{}", outcome), "❌ name message");
     }
 
+    #[test]
+    fn render_failure_with_metadata() {
+        let outcome = TestResult::builder()
+            .name("name")
+            .success(false)
+            .message(Some("message".into()))
+            .metadata(
+                Metadata::builder()
+                    .description("description")
+                    .team("infra")
+                    .build(),
+            )
+            .build();
+
+        assert_eq!(
+            "❌ name message\n  description\n  Team: infra",
+            outcome.render(Style::Emoji)
+        );
+    }
+
+    #[test]
+    fn render_success_without_metadata() {
+        let outcome = TestResult::builder()
+            .name("name")
+            .success(true)
+            .metadata(Metadata::builder().team("infra").build())
+            .build();
+
+        assert_eq!("✅ name", outcome.render(Style::Emoji));
+    }
+
+    #[test]
+    fn outcome_with_expected_failure() {
+        let failure = TestResult::builder()
+            .name("name")
+            .success(false)
+            .expected_failure(Some("reason".into()))
+            .build();
+        let pass = TestResult::builder()
+            .name("name")
+            .success(true)
+            .expected_failure(Some("reason".into()))
+            .build();
+
+        assert_eq!(Outcome::ExpectedFailure, failure.outcome());
+        assert_eq!(Outcome::UnexpectedPass, pass.outcome());
+    }
+
+    #[test]
+    fn render_expected_failure() {
+        let outcome = TestResult::builder()
+            .name("name")
+            .success(false)
+            .message(Some("message".into()))
+            .metadata(Metadata::builder().team("infra").build())
+            .expected_failure(Some("reason".into()))
+            .build();
+
+        assert_eq!(
+            "XFAIL name message (expected failure: reason)",
+            outcome.render(Style::Plain)
+        );
+    }
+
+    #[test]
+    fn render_unexpected_pass() {
+        let outcome = TestResult::builder()
+            .name("name")
+            .success(true)
+            .expected_failure(Some("reason".into()))
+            .build();
+
+        assert_eq!(
+            "XPASS name (unexpected pass, expected failure: reason)",
+            outcome.render(Style::Plain)
+        );
+    }
+
+    #[test]
+    fn render_plain_failure_with_message() {
+        let outcome = TestResult::builder()
+            .name("name")
+            .success(false)
+            .message(Some("message".into()))
+            .build();
+
+        assert_eq!("FAIL name message", outcome.render(Style::Plain));
+    }
+
     #[test]
     fn trait_send() {
         assert_send::<TestResult>();
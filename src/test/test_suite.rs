@@ -1,8 +1,9 @@
 //! A suite of test groups
 
 use async_trait::async_trait;
+use tokio::task::JoinSet;
 
-use crate::test::TestSuiteResult;
+use crate::test::{Explanation, Filter, TestGroup, TestPath, TestSuiteResult};
 
 /// A suite of test groups
 ///
@@ -10,7 +11,163 @@ use crate::test::TestSuiteResult;
 /// related to each other in some way. The results of the test groups are aggregated to produce the
 /// overall result of the test suite.
 #[async_trait]
-pub trait TestSuite: Send {
-    /// Run the tests in this suite
-    async fn run(&self) -> TestSuiteResult;
+pub trait TestSuite: Send + Sync {
+    /// Return the name of the test suite
+    fn name(&self) -> &'static str;
+
+    /// Return the test groups in this suite
+    fn groups(&self) -> Vec<Box<dyn TestGroup>>;
+
+    /// Explain the test at the given path
+    ///
+    /// Returns `None` if the path does not point to a test in this suite.
+    fn explain(&self, path: &TestPath) -> Option<Explanation> {
+        if path.suite() != self.name() {
+            return None;
+        }
+
+        let group = self
+            .groups()
+            .into_iter()
+            .find(|group| group.name() == path.group())?;
+        let test = group
+            .tests()
+            .into_iter()
+            .find(|test| test.name() == path.test())?;
+
+        let explanation = Explanation::builder()
+            .path(path.clone())
+            .tags(group.tags().into_iter().chain(test.tags()).collect())
+            .metadata(test.metadata())
+            .group_metadata(group.metadata())
+            .build();
+
+        Some(explanation)
+    }
+
+    /// Run the tests in this suite that are selected by the filter
+    ///
+    /// The test groups are run concurrently. Groups without any selected tests are omitted from the
+    /// result.
+    async fn run(&self, filter: &Filter) -> TestSuiteResult {
+        let mut js = JoinSet::new();
+        for group in self.groups() {
+            let filter = filter.clone();
+            js.spawn(async move { group.run(&filter).await });
+        }
+
+        let results = js
+            .join_all()
+            .await
+            .into_iter()
+            .filter(|result| !result.results().is_empty())
+            .collect();
+
+        TestSuiteResult::builder()
+            .name(self.name())
+            .results(results)
+            .build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test::{Tag, Test, TestResult};
+
+    use super::*;
+
+    struct Fastly;
+
+    #[async_trait]
+    impl Test for Fastly {
+        fn name(&self) -> &'static str {
+            "Fastly"
+        }
+
+        fn tags(&self) -> Vec<Tag> {
+            vec![Tag::cdn("fastly")]
+        }
+
+        async fn run(&self) -> TestResult {
+            TestResult::builder().name("Fastly").success(true).build()
+        }
+    }
+
+    struct Group(&'static str, Vec<Tag>);
+
+    impl TestGroup for Group {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn tags(&self) -> Vec<Tag> {
+            self.1.clone()
+        }
+
+        fn tests(&self) -> Vec<Box<dyn Test>> {
+            vec![Box::new(Fastly)]
+        }
+    }
+
+    struct Suite;
+
+    impl TestSuite for Suite {
+        fn name(&self) -> &'static str {
+            "suite"
+        }
+
+        fn groups(&self) -> Vec<Box<dyn TestGroup>> {
+            vec![
+                Box::new(Group("a", vec![Tag::service("a")])),
+                Box::new(Group("b", vec![Tag::service("b")])),
+            ]
+        }
+    }
+
+    #[tokio::test]
+    async fn run_all_groups_without_filter() {
+        let result = Suite.run(&Filter::default()).await;
+
+        let mut names: Vec<&str> = result.results().iter().map(|group| group.name()).collect();
+        names.sort();
+
+        assert_eq!("suite", result.name());
+        assert_eq!(vec!["a", "b"], names);
+        assert!(result.success());
+    }
+
+    #[test]
+    fn explain_test() {
+        let path = TestPath::new("suite", "b", "Fastly");
+
+        let explanation = Suite.explain(&path).unwrap();
+
+        assert_eq!(
+            &vec![Tag::service("b"), Tag::cdn("fastly")],
+            explanation.tags()
+        );
+    }
+
+    #[test]
+    fn explain_unknown_test() {
+        assert!(Suite
+            .explain(&TestPath::new("suite", "b", "CloudFront"))
+            .is_none());
+        assert!(Suite
+            .explain(&TestPath::new("other", "b", "Fastly"))
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn run_omits_groups_without_selected_tests() {
+        let filter = Filter::builder().tags(vec![Tag::service("b")]).build();
+
+        let result = Suite.run(&filter).await;
+
+        let names: Vec<&str> = result.results().iter().map(|group| group.name()).collect();
+
+        assert_eq!(vec!["b"], names);
+    }
 }
@@ -2,18 +2,29 @@
 
 use std::fmt::{Display, Formatter};
 
-use getset::{CopyGetters, Getters};
+use getset::{CopyGetters, Getters, MutGetters};
 use indent::indent_all_by;
 use typed_builder::TypedBuilder;
 
-use crate::test::TestGroupResult;
+use crate::test::{Style, TestGroupResult, TestPath, TestResult};
 
 /// The result of a test suite
 ///
 /// A test suite is a collection of test groups. Each test group is a collection of tests. The test
 /// suite result contains the results of all the test groups.
 #[derive(
-    Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, CopyGetters, Getters, TypedBuilder,
+    Clone,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    Debug,
+    Default,
+    CopyGetters,
+    Getters,
+    MutGetters,
+    TypedBuilder,
 )]
 pub struct TestSuiteResult {
     /// The name of the test suite
@@ -22,7 +33,7 @@ pub struct TestSuiteResult {
 
     /// The results of the individual test groups in the suite
     #[builder(default)]
-    #[getset(get = "pub")]
+    #[getset(get = "pub", get_mut = "pub")]
     results: Vec<TestGroupResult>,
 }
 
@@ -34,24 +45,37 @@ impl TestSuiteResult {
     pub fn success(&self) -> bool {
         self.results.iter().all(|result| result.success())
     }
-}
 
-impl Display for TestSuiteResult {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let emoji = if self.success() { "✅" } else { "❌" };
-        let display = format!("{} {}", emoji, self.name());
+    /// Iterate over the results of all tests in the suite together with their paths
+    pub fn test_results(&self) -> impl Iterator<Item = (TestPath, &TestResult)> {
+        self.results.iter().flat_map(move |group| {
+            group.results().iter().map(move |result| {
+                (
+                    TestPath::new(self.name, group.name(), result.name()),
+                    result,
+                )
+            })
+        })
+    }
 
-        writeln!(f, "{display}")?;
+    /// Render the result and the results of its groups with the given style
+    pub fn render(&self, style: Style) -> String {
+        let mut display = format!("{} {}\n", style.marker(self.success()), self.name());
 
         let mut sorted_results = self.results.clone();
         sorted_results.sort();
 
         for result in sorted_results {
-            let indented_result = indent_all_by(2, result.to_string());
-            write!(f, "{indented_result}")?;
+            display.push_str(&indent_all_by(2, result.render(style)));
         }
 
-        Ok(())
+        display
+    }
+}
+
+impl Display for TestSuiteResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render(Style::default()))
     }
 }
 
@@ -60,7 +84,6 @@ mod tests {
     use indoc::indoc;
     use pretty_assertions::assert_eq;
 
-    use crate::test::TestResult;
     use crate::test_utils::*;
 
     use super::*;
@@ -174,6 +197,32 @@ mod tests {
         assert_eq!(expected, format!("{}", suite_result));
     }
 
+    #[test]
+    fn test_results_with_paths() {
+        let group_result = TestGroupResult::builder()
+            .name("group")
+            .results(vec![
+                TestResult::builder().name("test 1").success(true).build(),
+                TestResult::builder().name("test 2").success(false).build(),
+            ])
+            .build();
+
+        let suite_result = TestSuiteResult::builder()
+            .name("suite")
+            .results(vec![group_result])
+            .build();
+
+        let paths: Vec<String> = suite_result
+            .test_results()
+            .map(|(path, _)| path.to_string())
+            .collect();
+
+        assert_eq!(
+            vec!["suite > group > test 1", "suite > group > test 2"],
+            paths
+        );
+    }
+
     #[test]
     fn trait_send() {
         assert_send::<TestResult>();
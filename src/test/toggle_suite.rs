@@ -0,0 +1,78 @@
+//! A test suite for the unit tests of the long-running modes
+//!
+//! The monitor, the status page, and `wait` rerun the same tests, so their unit tests need a suite
+//! whose test starts or stops failing between runs.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::test::{Test, TestGroup, TestResult, TestSuite};
+
+/// A test suite with the single test `crates.io > Database dumps > Fastly`
+///
+/// Clones of the suite share the result of the test, so that a test can change it while a runner
+/// owns the suite.
+#[derive(Clone, Default)]
+pub(crate) struct ToggleSuite {
+    /// Whether the test passes
+    success: Arc<AtomicBool>,
+}
+
+/// The group of the toggle suite
+struct ToggleGroup(ToggleSuite);
+
+/// The test of the toggle suite
+struct ToggleTest(ToggleSuite);
+
+impl ToggleSuite {
+    /// Create a suite whose test passes or fails
+    pub(crate) fn new(success: bool) -> Self {
+        let suite = Self::default();
+        suite.set_success(success);
+        suite
+    }
+
+    /// Change whether the test passes in the next runs
+    pub(crate) fn set_success(&self, success: bool) {
+        self.success.store(success, Ordering::SeqCst);
+    }
+}
+
+impl TestSuite for ToggleSuite {
+    fn name(&self) -> &'static str {
+        "crates.io"
+    }
+
+    fn groups(&self) -> Vec<Box<dyn TestGroup>> {
+        vec![Box::new(ToggleGroup(self.clone()))]
+    }
+}
+
+impl TestGroup for ToggleGroup {
+    fn name(&self) -> &'static str {
+        "Database dumps"
+    }
+
+    fn tests(&self) -> Vec<Box<dyn Test>> {
+        vec![Box::new(ToggleTest(self.0.clone()))]
+    }
+}
+
+#[async_trait]
+impl Test for ToggleTest {
+    fn name(&self) -> &str {
+        "Fastly"
+    }
+
+    async fn run(&self) -> TestResult {
+        let success = self.0.success.load(Ordering::SeqCst);
+
+        TestResult::builder()
+            .name("Fastly")
+            .success(success)
+            .message((!success).then(|| "HTTP 404".to_string()))
+            .build()
+    }
+}
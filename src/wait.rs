@@ -0,0 +1,278 @@
+//! Waiting for the infrastructure to become healthy
+//!
+//! Right after a deployment, caches may still serve stale content and new configurations may not
+//! have propagated to every edge location. Instead of failing on the first run, deploy pipelines
+//! can rerun the smoke tests until all of them pass or a timeout expires.
+
+use std::fmt::{Display, Formatter};
+use std::time::Instant;
+
+use getset::{CopyGetters, Getters};
+
+use crate::monitor::Interval;
+use crate::runner::Runner;
+use crate::side_channels::SideChannels;
+use crate::test::{Outcome, TestSuiteResult};
+
+/// The results of the attempt in which all tests passed
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, Getters)]
+pub struct Passed {
+    /// The results of the last attempt
+    #[getset(get = "pub")]
+    results: Vec<TestSuiteResult>,
+
+    /// The number of attempts, including the last one
+    #[getset(get_copy = "pub")]
+    attempts: usize,
+}
+
+/// Errors that can occur while waiting for the tests to pass
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum WaitError {
+    /// The filter of the runner selected no tests at all
+    NoTests,
+
+    /// An attempt was still running when the timeout expired
+    Cancelled {
+        /// The number of the attempt that was cancelled
+        attempt: usize,
+
+        /// The time that was waited for the tests to pass
+        timeout: Interval,
+    },
+
+    /// Tests were still failing when the timeout expired
+    Failing {
+        /// The results of the last attempt
+        results: Vec<TestSuiteResult>,
+
+        /// The number of tests that failed in the last attempt
+        failures: usize,
+
+        /// The time that was waited for the tests to pass
+        timeout: Interval,
+    },
+}
+
+/// Rerun the smoke tests until all of them pass or the timeout expires
+///
+/// Failures that the baseline of the runner expects don't count. After each failed attempt, the
+/// number of failing tests is printed and the tests are rerun after the interval. An attempt that is
+/// still running when the timeout expires is cancelled, and a filter that selects no tests at all
+/// fails right away. The results of every attempt are recorded in the side channels. A timeout that
+/// is too long to be represented as a point in time never expires.
+pub async fn wait(
+    runner: &Runner,
+    side_channels: &SideChannels,
+    timeout: Interval,
+    interval: Interval,
+) -> Result<Passed, WaitError> {
+    let deadline = Instant::now().checked_add(timeout.duration());
+    let mut attempt = 1;
+
+    loop {
+        let results = match deadline {
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+
+                let Ok(results) = tokio::time::timeout(remaining, runner.run()).await else {
+                    return Err(WaitError::Cancelled { attempt, timeout });
+                };
+
+                results
+            }
+            None => runner.run().await,
+        };
+
+        side_channels.record(&results).await;
+
+        let tests: Vec<_> = results
+            .iter()
+            .flat_map(TestSuiteResult::test_results)
+            .collect();
+
+        if tests.is_empty() {
+            return Err(WaitError::NoTests);
+        }
+
+        let failures = tests
+            .iter()
+            .filter(|(_, result)| result.outcome() == Outcome::Failed)
+            .count();
+
+        if failures == 0 {
+            return Ok(Passed {
+                results,
+                attempts: attempt,
+            });
+        }
+
+        let next_attempt = Instant::now().checked_add(interval.duration());
+
+        if deadline.is_some_and(|deadline| next_attempt.is_none_or(|next| next > deadline)) {
+            return Err(WaitError::Failing {
+                results,
+                failures,
+                timeout,
+            });
+        }
+
+        eprintln!("{failures} test(s) still failing, retrying in {interval}");
+
+        tokio::time::sleep(interval.duration()).await;
+        attempt += 1;
+    }
+}
+
+impl Display for WaitError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WaitError::NoTests => write!(f, "no tests match the filter"),
+            WaitError::Cancelled { attempt, timeout } => write!(
+                f,
+                "attempt {attempt} was still running after waiting for {timeout}"
+            ),
+            WaitError::Failing {
+                failures, timeout, ..
+            } => write!(
+                f,
+                "{failures} test(s) still failing after waiting for {timeout}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WaitError {}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::environment::Environment;
+    use crate::test::{Filter, Tag, ToggleSuite};
+    use crate::test_utils::*;
+
+    use super::*;
+
+    fn side_channels() -> SideChannels {
+        SideChannels::builder().env(Environment::Staging).build()
+    }
+
+    fn interval(millis: u64) -> Interval {
+        Interval::new(Duration::from_millis(millis))
+    }
+
+    #[tokio::test]
+    async fn wait_returns_first_passing_attempt() {
+        let runner = Runner::new().suite(ToggleSuite::new(true));
+
+        let passed = wait(&runner, &side_channels(), interval(1000), interval(10))
+            .await
+            .unwrap();
+
+        assert_eq!(1, passed.attempts());
+        assert_eq!(1, passed.results().len());
+    }
+
+    #[tokio::test]
+    async fn wait_with_timeout_beyond_representable_time() {
+        let runner = Runner::new().suite(ToggleSuite::new(true));
+        let forever = Interval::new(Duration::MAX);
+
+        let passed = wait(&runner, &side_channels(), forever, forever)
+            .await
+            .unwrap();
+
+        assert_eq!(1, passed.attempts());
+    }
+
+    #[tokio::test]
+    async fn wait_fails_when_next_attempt_is_beyond_representable_time() {
+        let runner = Runner::new().suite(ToggleSuite::new(false));
+
+        let error = wait(
+            &runner,
+            &side_channels(),
+            interval(1000),
+            Interval::new(Duration::MAX),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(error, WaitError::Failing { failures: 1, .. }));
+    }
+
+    #[tokio::test]
+    async fn wait_retries_until_tests_pass() {
+        let suite = ToggleSuite::new(false);
+        let runner = Runner::new().suite(suite.clone());
+
+        let recover = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            suite.set_success(true);
+        });
+
+        let passed = wait(&runner, &side_channels(), interval(5000), interval(20))
+            .await
+            .unwrap();
+        recover.await.unwrap();
+
+        assert!(passed.attempts() > 1);
+    }
+
+    #[tokio::test]
+    async fn wait_fails_when_timeout_expires() {
+        let runner = Runner::new().suite(ToggleSuite::new(false));
+
+        let error = wait(&runner, &side_channels(), interval(50), interval(20))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, WaitError::Failing { failures: 1, .. }));
+    }
+
+    #[tokio::test]
+    async fn wait_fails_without_tests() {
+        let filter = Filter::builder().tags(vec![Tag::cdn("cloudfront")]).build();
+        let runner = Runner::new().suite(ToggleSuite::new(true)).filter(filter);
+
+        let error = wait(&runner, &side_channels(), interval(1000), interval(10))
+            .await
+            .unwrap_err();
+
+        assert_eq!(WaitError::NoTests, error);
+    }
+
+    #[test]
+    fn wait_error_display() {
+        let error = WaitError::Cancelled {
+            attempt: 2,
+            timeout: "15m".parse().unwrap(),
+        };
+
+        assert_eq!(
+            "attempt 2 was still running after waiting for 15m",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Passed>();
+        assert_send::<WaitError>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Passed>();
+        assert_sync::<WaitError>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Passed>();
+        assert_unpin::<WaitError>();
+    }
+}
@@ -0,0 +1,116 @@
+//! Configuration for the webhook notifications
+
+use getset::{CopyGetters, Getters};
+use serde::Deserialize;
+#[cfg(test)]
+use typed_builder::TypedBuilder;
+
+use crate::config::{Validate, Validator};
+
+/// Configuration for the webhook notifications
+///
+/// The configuration is read from the `[webhook]` table of the configuration file. Notifications
+/// are only sent if a URL is configured, which is usually passed in as an environment variable,
+/// e.g. `SMOKE_TESTS_WEBHOOK__URL`, since it contains a secret.
+#[derive(
+    Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, CopyGetters, Getters, Deserialize,
+)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(test, derive(TypedBuilder))]
+pub struct Config {
+    /// The URL of the webhook, or `None` to not send notifications
+    #[serde(default, deserialize_with = "crate::config::deserialize_optional_url")]
+    #[cfg_attr(test, builder(default, setter(strip_option, into)))]
+    #[getset(get = "pub")]
+    url: Option<String>,
+
+    /// The format of the payload that is posted to the webhook
+    #[serde(default)]
+    #[cfg_attr(test, builder(default))]
+    #[getset(get_copy = "pub")]
+    format: Format,
+
+    /// The Zulip stream that the notifications are posted to, e.g. `t-infra`
+    #[serde(default)]
+    #[cfg_attr(test, builder(default, setter(strip_option, into)))]
+    #[getset(get = "pub")]
+    stream: Option<String>,
+
+    /// The Zulip topic that the notifications are posted to
+    #[serde(default = "default_topic")]
+    #[cfg_attr(test, builder(default = default_topic(), setter(into)))]
+    #[getset(get = "pub")]
+    topic: String,
+}
+
+/// The format of the payload that is posted to the webhook
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Format {
+    /// A JSON payload for Slack's incoming webhooks
+    #[default]
+    Slack,
+
+    /// A form for Zulip's API to send messages, with the credentials of a bot in the URL
+    Zulip,
+}
+
+/// Return the default Zulip topic
+fn default_topic() -> String {
+    "smoke tests".into()
+}
+
+impl Validate for Config {
+    fn validate(&self, validator: &mut Validator) {
+        if let Some(url) = &self.url {
+            validator.url("url", url);
+        }
+
+        if self.format == Format::Zulip && self.stream.is_none() {
+            validator.problem("stream", "expected a stream for Zulip notifications");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::test_utils::*;
+
+    use super::*;
+
+    #[test]
+    fn deserialize_with_defaults() {
+        let config: Config = toml::from_str("").unwrap();
+
+        assert_eq!(&None, config.url());
+        assert_eq!(Format::Slack, config.format());
+        assert_eq!("smoke tests", config.topic());
+    }
+
+    #[test]
+    fn validate_zulip_without_stream() {
+        let config = Config::builder().format(Format::Zulip).build();
+
+        let mut validator = Validator::new();
+        config.validate(&mut validator);
+
+        assert_eq!("stream", validator.problems()[0].key());
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Config>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Config>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Config>();
+    }
+}
@@ -0,0 +1,384 @@
+//! Notifications about failures and recoveries
+//!
+//! The results of the smoke tests can be posted to a chat, so that the infrastructure team learns
+//! about failures without watching the output of the tests. The notifier posts a summary of the
+//! failing tests with their messages and the environment to a webhook, and a message when a test
+//! that was failing passes again.
+//!
+//! Notifications are only sent by the monitor, which knows the previous result of each test. A
+//! single run can't tell a new failure from an old one, so it would repeat the same failures on
+//! every run and never send a recovery.
+//!
+//! Payloads are available for Slack's incoming webhooks and for Zulip's API to send messages.
+
+use getset::Getters;
+use serde_json::json;
+
+use crate::environment::Environment;
+use crate::http_client::custom_http_client;
+use crate::monitor::Transition;
+use crate::test::{Outcome, TestPath, TestSuiteResult};
+
+pub use self::config::{Config, Format};
+
+mod config;
+
+/// A notifier that posts notifications to a webhook
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct Webhook {
+    /// Configuration for the webhook
+    config: Config,
+}
+
+/// A summary of the tests that failed or recovered in an environment
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Getters)]
+pub struct Notification {
+    /// The environment in which the tests ran
+    #[getset(get = "pub")]
+    env: Environment,
+
+    /// The tests that failed, with their messages
+    #[getset(get = "pub")]
+    failures: Vec<(TestPath, Option<String>)>,
+
+    /// The tests that were failing and pass again
+    #[getset(get = "pub")]
+    recoveries: Vec<TestPath>,
+}
+
+impl Webhook {
+    /// Create a new notifier with the given configuration
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    /// Check if a URL is configured, i.e. if notifications are sent at all
+    pub fn is_enabled(&self) -> bool {
+        self.config.url().is_some()
+    }
+
+    /// Post the notification to the webhook
+    ///
+    /// Nothing is sent if no URL is configured or the notification is empty.
+    pub async fn notify(&self, notification: &Notification) -> Result<(), String> {
+        let Some(url) = self.config.url() else {
+            return Ok(());
+        };
+
+        if notification.is_empty() {
+            return Ok(());
+        }
+
+        let client = custom_http_client()
+            .build()
+            .map_err(|error| error.to_string())?;
+
+        let request = match self.config.format() {
+            Format::Slack => client
+                .post(url)
+                .json(&json!({ "text": notification.render(Format::Slack) })),
+            Format::Zulip => client.post(url).form(&[
+                ("type", "stream"),
+                ("to", self.config.stream().as_deref().unwrap_or_default()),
+                ("topic", self.config.topic()),
+                ("content", &notification.render(Format::Zulip)),
+            ]),
+        };
+
+        // The URL of the webhook contains a secret, so it must not end up in the error message
+        let response = request
+            .send()
+            .await
+            .map_err(|error| error.without_url().to_string())?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("webhook responded with HTTP {}", response.status()))
+        }
+    }
+}
+
+impl Notification {
+    /// Create a notification for the tests that failed unexpectedly in a single run
+    ///
+    /// Tests that are listed in the baseline of known failures are not included.
+    pub fn from_results(env: Environment, results: &[TestSuiteResult]) -> Self {
+        let failures = results
+            .iter()
+            .flat_map(TestSuiteResult::test_results)
+            .filter(|(_, result)| result.outcome() == Outcome::Failed)
+            .map(|(path, result)| (path, result.message().clone()))
+            .collect();
+
+        Self {
+            env,
+            failures,
+            recoveries: Vec::new(),
+        }
+    }
+
+    /// Create a notification for the tests whose result changed while monitoring
+    pub fn from_transitions(env: Environment, transitions: &[Transition]) -> Self {
+        let (recoveries, failures): (Vec<&Transition>, Vec<&Transition>) =
+            transitions.iter().partition(|transition| transition.to());
+
+        Self {
+            env,
+            failures: failures
+                .into_iter()
+                .map(|transition| (transition.path().clone(), transition.message().clone()))
+                .collect(),
+            recoveries: recoveries
+                .into_iter()
+                .map(|transition| transition.path().clone())
+                .collect(),
+        }
+    }
+
+    /// Check if the notification has nothing to report
+    pub fn is_empty(&self) -> bool {
+        self.failures.is_empty() && self.recoveries.is_empty()
+    }
+
+    /// Render the notification as a message in the markup of the given format
+    pub fn render(&self, format: Format) -> String {
+        let (bold, bullet, failed, recovered) = match format {
+            Format::Slack => ("*", "•", ":x:", ":white_check_mark:"),
+            Format::Zulip => ("**", "-", ":cross_mark:", ":check:"),
+        };
+
+        let mut message = String::new();
+
+        if !self.failures.is_empty() {
+            message.push_str(&format!(
+                "{failed} {} smoke test(s) failing in {bold}{}{bold}\n",
+                self.failures.len(),
+                self.env
+            ));
+
+            for (path, error) in &self.failures {
+                match error {
+                    Some(error) => message.push_str(&format!("{bullet} `{path}`: {error}\n")),
+                    None => message.push_str(&format!("{bullet} `{path}`\n")),
+                }
+            }
+        }
+
+        if !self.recoveries.is_empty() {
+            message.push_str(&format!(
+                "{recovered} {} smoke test(s) recovered in {bold}{}{bold}\n",
+                self.recoveries.len(),
+                self.env
+            ));
+
+            for path in &self.recoveries {
+                message.push_str(&format!("{bullet} `{path}`\n"));
+            }
+        }
+
+        message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use indoc::indoc;
+    use mockito::Matcher;
+    use pretty_assertions::assert_eq;
+
+    use crate::monitor::Monitor;
+    use crate::test::{TestGroupResult, TestResult};
+    use crate::test_utils::*;
+
+    use super::*;
+
+    fn results(fastly: bool) -> Vec<TestSuiteResult> {
+        let fastly = TestResult::builder()
+            .name("Fastly")
+            .success(fastly)
+            .message((!fastly).then(|| "HTTP 404".to_string()))
+            .build();
+        let cloudfront = TestResult::builder()
+            .name("CloudFront")
+            .success(false)
+            .expected_failure(Some("not deployed yet".into()))
+            .build();
+
+        vec![TestSuiteResult::builder()
+            .name("crates.io")
+            .results(vec![TestGroupResult::builder()
+                .name("Database dumps")
+                .results(vec![cloudfront, fastly])
+                .build()])
+            .build()]
+    }
+
+    fn recovery() -> Notification {
+        let mut monitor = Monitor::new();
+        monitor.update(&results(false), Utc::now());
+
+        let transitions = monitor.update(&results(true), Utc::now());
+
+        Notification::from_transitions(Environment::Production, &transitions)
+    }
+
+    #[test]
+    fn from_results_skips_expected_failures() {
+        let notification = Notification::from_results(Environment::Staging, &results(false));
+
+        let expected = indoc! {"
+            :x: 1 smoke test(s) failing in *staging*
+            • `crates.io > Database dumps > Fastly`: HTTP 404
+        "};
+
+        assert_eq!(expected, notification.render(Format::Slack));
+    }
+
+    #[test]
+    fn from_results_without_failures() {
+        let notification = Notification::from_results(Environment::Staging, &results(true));
+
+        assert!(notification.is_empty());
+    }
+
+    #[test]
+    fn from_transitions_with_recovery() {
+        let expected = indoc! {"
+            :check: 1 smoke test(s) recovered in **production**
+            - `crates.io > Database dumps > Fastly`
+        "};
+
+        assert_eq!(expected, recovery().render(Format::Zulip));
+    }
+
+    #[tokio::test]
+    async fn notify_slack() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mock = server
+            .mock("POST", "/hooks/smoke-tests")
+            .match_header("Content-Type", "application/json")
+            .match_body(Matcher::PartialJson(json!({
+                "text": ":x: 1 smoke test(s) failing in *staging*\n\
+                    • `crates.io > Database dumps > Fastly`: HTTP 404\n"
+            })))
+            .create_async()
+            .await;
+
+        let webhook = Webhook::new(
+            Config::builder()
+                .url(format!("{}/hooks/smoke-tests", server.url()))
+                .build(),
+        );
+
+        webhook
+            .notify(&Notification::from_results(
+                Environment::Staging,
+                &results(false),
+            ))
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn notify_zulip() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mock = server
+            .mock("POST", "/api/v1/messages")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("type".into(), "stream".into()),
+                Matcher::UrlEncoded("to".into(), "t-infra".into()),
+                Matcher::UrlEncoded("topic".into(), "smoke tests".into()),
+                Matcher::UrlEncoded("content".into(), recovery().render(Format::Zulip)),
+            ]))
+            .create_async()
+            .await;
+
+        let webhook = Webhook::new(
+            Config::builder()
+                .url(format!("{}/api/v1/messages", server.url()))
+                .format(Format::Zulip)
+                .stream("t-infra")
+                .build(),
+        );
+
+        webhook.notify(&recovery()).await.unwrap();
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn notify_with_error_status() {
+        let mut server = mockito::Server::new_async().await;
+
+        server
+            .mock("POST", "/")
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let webhook = Webhook::new(Config::builder().url(server.url()).build());
+
+        let error = webhook
+            .notify(&Notification::from_results(
+                Environment::Staging,
+                &results(false),
+            ))
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            "webhook responded with HTTP 500 Internal Server Error",
+            error
+        );
+    }
+
+    #[tokio::test]
+    async fn notify_hides_url_in_error() {
+        // Nothing listens on the port, so that the request fails before a response is received
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        drop(listener);
+
+        let webhook = Webhook::new(
+            Config::builder()
+                .url(format!("http://{address}/services/T000/B000/secret"))
+                .build(),
+        );
+
+        let error = webhook.notify(&recovery()).await.unwrap_err();
+
+        assert!(!error.contains("secret"));
+    }
+
+    #[tokio::test]
+    async fn notify_without_url() {
+        let webhook = Webhook::default();
+
+        assert!(!webhook.is_enabled());
+        assert_eq!(Ok(()), webhook.notify(&recovery()).await);
+    }
+
+    #[test]
+    fn trait_send() {
+        assert_send::<Webhook>();
+        assert_send::<Notification>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        assert_sync::<Webhook>();
+        assert_sync::<Notification>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        assert_unpin::<Webhook>();
+        assert_unpin::<Notification>();
+    }
+}